@@ -0,0 +1,28 @@
+//! Generates the C header from the bindings.
+//!
+//! The header is always written to `OUT_DIR`, where the tests compare it with
+//! the checked-in `include/liboptic.h`. Set `LIBOPTIC_REGENERATE_HEADER=1` to
+//! update the checked-in copy, too.
+
+fn main() {
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    println!("cargo:rerun-if-env-changed=LIBOPTIC_REGENERATE_HEADER");
+
+    let config = cbindgen::Config::from_file(format!("{crate_dir}/cbindgen.toml"))
+        .expect("the cbindgen config should be valid");
+
+    let bindings = cbindgen::Builder::new()
+        .with_crate(&crate_dir)
+        .with_config(config)
+        .generate()
+        .expect("failed to generate C bindings");
+    bindings.write_to_file(format!("{out_dir}/liboptic.h"));
+
+    // only touch the source tree when asked to
+    if std::env::var_os("LIBOPTIC_REGENERATE_HEADER").is_some() {
+        bindings.write_to_file(format!("{crate_dir}/include/liboptic.h"));
+    }
+}
@@ -0,0 +1,426 @@
+/* liboptic C bindings. This file is generated by cbindgen - do not edit it by hand! */
+
+#ifndef LIBOPTIC_H
+#define LIBOPTIC_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * A status code returned by fallible functions.
+ *
+ * Each variant past `NullPointer` mirrors one of the Rust `EdidErrorKind`
+ * variants.
+ */
+typedef enum OpticError {
+  /**
+   * Everything went fine.
+   */
+  OPTIC_ERROR_OK = 0,
+  /**
+   * A required pointer argument was `NULL`.
+   */
+  OPTIC_ERROR_NULL_POINTER = 1,
+  /**
+   * The parser panicked. Please report this alongside the EDID!
+   */
+  OPTIC_ERROR_PANICKED = 2,
+  /**
+   * An error this version of the bindings doesn't know about.
+   */
+  OPTIC_ERROR_UNKNOWN = 3,
+  /**
+   * An index argument was past the end of what it indexes.
+   */
+  OPTIC_ERROR_OUT_OF_RANGE = 4,
+  /**
+   * The block at the given index isn't the kind that was asked for.
+   */
+  OPTIC_ERROR_WRONG_BLOCK_KIND = 5,
+  OPTIC_ERROR_TOO_SHORT = 10,
+  OPTIC_ERROR_HEADER_TOO_SHORT = 11,
+  OPTIC_ERROR_NO_HEADER = 12,
+  OPTIC_ERROR_ID_BAD_VALUES = 13,
+  OPTIC_ERROR_CHAR_OUT_OF_BOUNDS = 14,
+  OPTIC_ERROR_ID_NO_ZEROES_ALLOWED = 15,
+  OPTIC_ERROR_BASIC_INFO_BAD_INTERFACE = 16,
+  OPTIC_ERROR_DESCRIPTOR_USED_RESERVED_KIND = 17,
+  OPTIC_ERROR_DESCRIPTOR_RANGE_LIMITS_USED_RESERVED_BITS = 18,
+  OPTIC_ERROR_DESCRIPTOR_RANGE_LIMITS_USED_RESERVED_VTS_FLAG = 19,
+  OPTIC_ERROR_DESCRIPTOR_RANGE_LIMITS_CVT_RESERVED_BITS = 20,
+  OPTIC_ERROR_DESCRIPTOR_UNEXPECTED_HEADER = 21,
+  OPTIC_ERROR_DESCRIPTOR_NO_FIRST_CVT = 22,
+  OPTIC_ERROR_ARRAY_STRING_ERROR = 23,
+  OPTIC_ERROR_TRY_FROM_SLICE = 24,
+  OPTIC_ERROR_BCD_ERROR = 25,
+  OPTIC_ERROR_LOAD_NOT_TEXT = 26,
+  OPTIC_ERROR_LOAD_BAD_HEX = 27,
+  OPTIC_ERROR_LOAD_ODD_HEX_LENGTH = 28,
+  OPTIC_ERROR_LOAD_NO_EDID = 29,
+  OPTIC_ERROR_PATCH_NO_FREE_DESCRIPTOR = 30,
+  OPTIC_ERROR_PATCH_NO_SUCH_EXTENSION = 31,
+  OPTIC_ERROR_PATCH_BAD_PRODUCT_NAME = 32,
+  OPTIC_ERROR_PATCH_VALUE_OUT_OF_RANGE = 33,
+  OPTIC_ERROR_PATCH_PREFERRED_TIMING_TOO_FAST = 34,
+  OPTIC_ERROR_ICC_BAD_PRIMARIES = 35,
+  OPTIC_ERROR_BAD_CHECKSUM = 36,
+  OPTIC_ERROR_MODELINE_MISSING_FIELD = 37,
+  OPTIC_ERROR_MODELINE_BAD_VALUE = 38,
+} OpticError;
+
+/**
+ * Which kind of date the display reported.
+ */
+typedef enum OpticDateKind {
+  /**
+   * The date the display was manufactured.
+   */
+  OPTIC_DATE_KIND_MANUFACTURE,
+  /**
+   * The year the display model was released.
+   */
+  OPTIC_DATE_KIND_MODEL_YEAR,
+} OpticDateKind;
+
+/**
+ * What the screen size bytes contained.
+ */
+typedef enum OpticScreenSizeKind {
+  /**
+   * The size isn't given, or may be dynamic (like on a projector).
+   */
+  OPTIC_SCREEN_SIZE_KIND_NONE,
+  /**
+   * The screen size in centimeters.
+   */
+  OPTIC_SCREEN_SIZE_KIND_CENTIMETERS,
+  /**
+   * Only an aspect ratio.
+   */
+  OPTIC_SCREEN_SIZE_KIND_ASPECT_RATIO,
+} OpticScreenSizeKind;
+
+/**
+ * The kind of an 18-byte block.
+ */
+typedef enum OpticBlockKind {
+  OPTIC_BLOCK_KIND_DETAILED_TIMING,
+  OPTIC_BLOCK_KIND_PRODUCT_SERIAL,
+  OPTIC_BLOCK_KIND_DATA_STRING,
+  OPTIC_BLOCK_KIND_RANGE_LIMITS,
+  OPTIC_BLOCK_KIND_PRODUCT_NAME,
+  OPTIC_BLOCK_KIND_COLOR_POINT,
+  OPTIC_BLOCK_KIND_STANDARD_TIMINGS,
+  OPTIC_BLOCK_KIND_DCM,
+  OPTIC_BLOCK_KIND_CVT,
+  OPTIC_BLOCK_KIND_ESTABLISHED_TIMINGS_III,
+  OPTIC_BLOCK_KIND_DUMMY,
+  OPTIC_BLOCK_KIND_MANUFACTURER,
+} OpticBlockKind;
+
+/**
+ * An owned, parsed EDID.
+ *
+ * Create one with `optic_edid_parse` and free it with `optic_edid_free`.
+ */
+typedef struct OpticEdid OpticEdid;
+
+/**
+ * When the display came from.
+ */
+typedef struct OpticDate {
+  enum OpticDateKind kind;
+  /**
+   * The week of manufacture, from 1-54. Zero when not given, or when
+   * `kind` is `OPTIC_DATE_KIND_MODEL_YEAR`.
+   */
+  uint8_t week;
+  uint16_t year;
+} OpticDate;
+
+/**
+ * The screen size or aspect ratio of the display.
+ */
+typedef struct OpticScreenSize {
+  enum OpticScreenSizeKind kind;
+  /**
+   * Zero when `kind` is `OPTIC_SCREEN_SIZE_KIND_NONE`.
+   */
+  uint16_t horizontal;
+  /**
+   * Zero when `kind` is `OPTIC_SCREEN_SIZE_KIND_NONE`.
+   */
+  uint16_t vertical;
+} OpticScreenSize;
+
+/**
+ * A coordinate on the CIE 1931 color space graph.
+ */
+typedef struct OpticColorCoordinate {
+  double x;
+  double y;
+} OpticColorCoordinate;
+
+/**
+ * The display's primaries and white point.
+ */
+typedef struct OpticChromaticity {
+  struct OpticColorCoordinate red;
+  struct OpticColorCoordinate green;
+  struct OpticColorCoordinate blue;
+  struct OpticColorCoordinate white_point;
+} OpticChromaticity;
+
+/**
+ * A detailed timing definition.
+ */
+typedef struct OpticDetailedTiming {
+  /**
+   * The pixel clock, in units of 10 kHz, like the EDID stores it.
+   */
+  uint16_t pixel_clock_khz;
+  uint16_t horizontal_addressable_video_px;
+  uint16_t horizontal_blanking_px;
+  uint16_t vertical_addressable_video_lines;
+  uint16_t vertical_blanking_lines;
+  uint16_t horizontal_front_porch;
+  uint16_t horizontal_sync_pulse_width_px;
+  uint8_t vertical_front_porch_lines;
+  uint8_t vertical_sync_pulse_width_lines;
+  /**
+   * Zero when not given.
+   */
+  uint16_t horizontal_addressable_video_size_mm;
+  /**
+   * Zero when not given.
+   */
+  uint16_t vertical_addressable_video_size_mm;
+  uint8_t horizontal_border_px;
+  uint8_t vertical_border_lines;
+  bool interlaced;
+  /**
+   * Whether the sync signal is digital, rather than analog.
+   */
+  bool digital_sync;
+} OpticDetailedTiming;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Parses `len` bytes of EDID at `data`.
+ *
+ * On success, `*out` is set to a new handle that must be freed with
+ * `optic_edid_free`. On failure, `*out` is set to `NULL`.
+ *
+ * # Safety
+ *
+ * `data` must point to at least `len` readable bytes, and `out` must be a
+ * valid pointer to write to.
+ */
+enum OpticError optic_edid_parse(const uint8_t *data, size_t len, struct OpticEdid **out);
+
+/**
+ * Frees an EDID handle. Passing `NULL` does nothing.
+ *
+ * # Safety
+ *
+ * `edid` must be `NULL` or a handle from `optic_edid_parse` that hasn't
+ * already been freed.
+ */
+void optic_edid_free(struct OpticEdid *edid);
+
+/**
+ * Writes the EDID's version and revision (like 1 and 4 for v1.4).
+ *
+ * # Safety
+ *
+ * `edid` must be a live handle. `version` and `revision` must be valid
+ * pointers to write to.
+ */
+enum OpticError optic_edid_version(const struct OpticEdid *edid,
+                                   uint8_t *version,
+                                   uint8_t *revision);
+
+/**
+ * Copies the manufacturer's name into `buf`.
+ *
+ * This is the company name when it's known, or its three-letter PNP ID
+ * otherwise. Returns the full length of the name, or zero if `edid` is
+ * `NULL`.
+ *
+ * # Safety
+ *
+ * `edid` must be a live handle. `buf` must be `NULL` or point to `buf_len`
+ * writable bytes.
+ */
+size_t optic_edid_manufacturer_name(const struct OpticEdid *edid, char *buf, size_t buf_len);
+
+/**
+ * Returns the manufacturer's product code, or zero if `edid` is `NULL`.
+ *
+ * # Safety
+ *
+ * `edid` must be `NULL` or a live handle.
+ */
+uint16_t optic_edid_product_code(const struct OpticEdid *edid);
+
+/**
+ * Writes the numeric serial number into `out`.
+ *
+ * Returns `false` (leaving `out` alone) when the display has no serial.
+ *
+ * # Safety
+ *
+ * `edid` must be a live handle, and `out` must be a valid pointer to write
+ * to.
+ */
+bool optic_edid_serial_number(const struct OpticEdid *edid, uint32_t *out);
+
+/**
+ * Writes the display's manufacture or model date into `out`.
+ *
+ * # Safety
+ *
+ * `edid` must be a live handle, and `out` must be a valid pointer to write
+ * to.
+ */
+enum OpticError optic_edid_date(const struct OpticEdid *edid, struct OpticDate *out);
+
+/**
+ * Copies the product name from the 0xFC descriptor into `buf`.
+ *
+ * Returns the full length of the name, or zero if there isn't one.
+ *
+ * # Safety
+ *
+ * `edid` must be a live handle. `buf` must be `NULL` or point to `buf_len`
+ * writable bytes.
+ */
+size_t optic_edid_product_name(const struct OpticEdid *edid, char *buf, size_t buf_len);
+
+/**
+ * Copies the serial number string from the 0xFF descriptor into `buf`.
+ *
+ * Returns the full length of the serial, or zero if there isn't one.
+ *
+ * # Safety
+ *
+ * `edid` must be a live handle. `buf` must be `NULL` or point to `buf_len`
+ * writable bytes.
+ */
+size_t optic_edid_product_serial(const struct OpticEdid *edid, char *buf, size_t buf_len);
+
+/**
+ * Whether the display has a digital input.
+ *
+ * # Safety
+ *
+ * `edid` must be `NULL` or a live handle.
+ */
+bool optic_edid_is_digital(const struct OpticEdid *edid);
+
+/**
+ * Writes the screen size (or aspect ratio) into `out`.
+ *
+ * # Safety
+ *
+ * `edid` must be a live handle, and `out` must be a valid pointer to write
+ * to.
+ */
+enum OpticError optic_edid_screen_size(const struct OpticEdid *edid, struct OpticScreenSize *out);
+
+/**
+ * Writes the display's gamma into `out`.
+ *
+ * Returns `false` (leaving `out` alone) when the gamma is instead given in
+ * an extension block.
+ *
+ * # Safety
+ *
+ * `edid` must be a live handle, and `out` must be a valid pointer to write
+ * to.
+ */
+bool optic_edid_gamma(const struct OpticEdid *edid, double *out);
+
+/**
+ * Writes the display's color characteristics into `out`.
+ *
+ * # Safety
+ *
+ * `edid` must be a live handle, and `out` must be a valid pointer to write
+ * to.
+ */
+enum OpticError optic_edid_chromaticity(const struct OpticEdid *edid,
+                                        struct OpticChromaticity *out);
+
+/**
+ * Writes the kind of the 18-byte block at `index` (from 0 to 3) into `out`.
+ *
+ * Returns `OPTIC_ERROR_OUT_OF_RANGE` if `index` is past the last block.
+ *
+ * # Safety
+ *
+ * `edid` must be a live handle, and `out` must be a valid pointer to write
+ * to.
+ */
+enum OpticError optic_edid_block_kind(const struct OpticEdid *edid,
+                                      size_t index,
+                                      enum OpticBlockKind *out);
+
+/**
+ * Writes the detailed timing at 18-byte block `index` (from 0 to 3) into
+ * `out`.
+ *
+ * Returns `OPTIC_ERROR_OUT_OF_RANGE` if `index` is past the last block, and
+ * `OPTIC_ERROR_WRONG_BLOCK_KIND` if that block isn't a detailed timing.
+ * Either way, `out` is left alone.
+ *
+ * # Safety
+ *
+ * `edid` must be a live handle, and `out` must be a valid pointer to write
+ * to.
+ */
+enum OpticError optic_edid_detailed_timing(const struct OpticEdid *edid,
+                                           size_t index,
+                                           struct OpticDetailedTiming *out);
+
+/**
+ * Returns the number of extension blocks following the base EDID, or zero
+ * if `edid` is `NULL`.
+ *
+ * # Safety
+ *
+ * `edid` must be `NULL` or a live handle.
+ */
+uint8_t optic_edid_extension_count(const struct OpticEdid *edid);
+
+/**
+ * Returns the base block's checksum byte, or zero if `edid` is `NULL`.
+ *
+ * # Safety
+ *
+ * `edid` must be `NULL` or a live handle.
+ */
+uint8_t optic_edid_checksum(const struct OpticEdid *edid);
+
+/**
+ * Returns a static, NUL-terminated description of the given error code.
+ *
+ * This takes an `int`, not an `OpticError`, so that codes from a newer
+ * version of the bindings are safe to pass in. Any code this version doesn't
+ * know about gets the same message as `OPTIC_ERROR_UNKNOWN`.
+ *
+ * The returned string must not be freed.
+ */
+const char *optic_error_message(int error);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* LIBOPTIC_H */
@@ -0,0 +1,207 @@
+//! Error codes returned over the C ABI.
+
+use core::ffi::{c_char, c_int};
+
+use liboptic_edid::error::{EdidError, EdidErrorKind};
+
+/// A status code returned by fallible functions.
+///
+/// Each variant past `NullPointer` mirrors one of the Rust `EdidErrorKind`
+/// variants.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OpticError {
+    /// Everything went fine.
+    Ok = 0,
+
+    /// A required pointer argument was `NULL`.
+    NullPointer = 1,
+    /// The parser panicked. Please report this alongside the EDID!
+    Panicked = 2,
+    /// An error this version of the bindings doesn't know about.
+    Unknown = 3,
+    /// An index argument was past the end of what it indexes.
+    OutOfRange = 4,
+    /// The block at the given index isn't the kind that was asked for.
+    WrongBlockKind = 5,
+
+    TooShort = 10,
+    HeaderTooShort = 11,
+    NoHeader = 12,
+    IdBadValues = 13,
+    CharOutOfBounds = 14,
+    IdNoZeroesAllowed = 15,
+    BasicInfoBadInterface = 16,
+    DescriptorUsedReservedKind = 17,
+    DescriptorRangeLimitsUsedReservedBits = 18,
+    DescriptorRangeLimitsUsedReservedVtsFlag = 19,
+    DescriptorRangeLimitsCvtReservedBits = 20,
+    DescriptorUnexpectedHeader = 21,
+    DescriptorNoFirstCvt = 22,
+    ArrayStringError = 23,
+    TryFromSlice = 24,
+    BcdError = 25,
+    LoadNotText = 26,
+    LoadBadHex = 27,
+    LoadOddHexLength = 28,
+    LoadNoEdid = 29,
+    PatchNoFreeDescriptor = 30,
+    PatchNoSuchExtension = 31,
+    PatchBadProductName = 32,
+    PatchValueOutOfRange = 33,
+    PatchPreferredTimingTooFast = 34,
+    IccBadPrimaries = 35,
+    BadChecksum = 36,
+    ModelineMissingField = 37,
+    ModelineBadValue = 38,
+}
+
+impl From<&EdidError> for OpticError {
+    fn from(value: &EdidError) -> Self {
+        match value.kind {
+            EdidErrorKind::TooShort { .. } => Self::TooShort,
+            EdidErrorKind::HeaderTooShort { .. } => Self::HeaderTooShort,
+            EdidErrorKind::NoHeader => Self::NoHeader,
+            EdidErrorKind::IdBadValues(_) => Self::IdBadValues,
+            EdidErrorKind::CharOutOfBounds(_) => Self::CharOutOfBounds,
+            EdidErrorKind::IdNoZeroesAllowed => Self::IdNoZeroesAllowed,
+            EdidErrorKind::BasicInfoBadInterface(_) => Self::BasicInfoBadInterface,
+            EdidErrorKind::DescriptorUsedReservedKind { .. } => Self::DescriptorUsedReservedKind,
+            EdidErrorKind::DescriptorRangeLimitsUsedReservedBits { .. } => {
+                Self::DescriptorRangeLimitsUsedReservedBits
+            }
+            EdidErrorKind::DescriptorRangeLimitsUsedReservedVTSFlag { .. } => {
+                Self::DescriptorRangeLimitsUsedReservedVtsFlag
+            }
+            EdidErrorKind::DescriptorRangeLimitsCvtReservedBits => {
+                Self::DescriptorRangeLimitsCvtReservedBits
+            }
+            EdidErrorKind::DescriptorUnexpectedHeader(_) => Self::DescriptorUnexpectedHeader,
+            EdidErrorKind::DescriptorNoFirstCvt => Self::DescriptorNoFirstCvt,
+            EdidErrorKind::ArrayStringError => Self::ArrayStringError,
+            EdidErrorKind::TryFromSlice(_) => Self::TryFromSlice,
+            EdidErrorKind::BcdError => Self::BcdError,
+            EdidErrorKind::LoadNotText => Self::LoadNotText,
+            EdidErrorKind::LoadBadHex { .. } => Self::LoadBadHex,
+            EdidErrorKind::LoadOddHexLength => Self::LoadOddHexLength,
+            EdidErrorKind::LoadNoEdid => Self::LoadNoEdid,
+            EdidErrorKind::PatchNoFreeDescriptor => Self::PatchNoFreeDescriptor,
+            EdidErrorKind::PatchNoSuchExtension { .. } => Self::PatchNoSuchExtension,
+            EdidErrorKind::PatchBadProductName => Self::PatchBadProductName,
+            EdidErrorKind::PatchValueOutOfRange { .. } => Self::PatchValueOutOfRange,
+            EdidErrorKind::PatchPreferredTimingTooFast => Self::PatchPreferredTimingTooFast,
+            EdidErrorKind::IccBadPrimaries => Self::IccBadPrimaries,
+            EdidErrorKind::BadChecksum { .. } => Self::BadChecksum,
+            EdidErrorKind::ModelineMissingField { .. } => Self::ModelineMissingField,
+            EdidErrorKind::ModelineBadValue { .. } => Self::ModelineBadValue,
+
+            // `EdidErrorKind` is non-exhaustive
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// Reads a status code that came from C, which might not be one of ours.
+impl TryFrom<c_int> for OpticError {
+    type Error = c_int;
+
+    fn try_from(value: c_int) -> Result<Self, Self::Error> {
+        Ok(match value {
+            0 => Self::Ok,
+            1 => Self::NullPointer,
+            2 => Self::Panicked,
+            3 => Self::Unknown,
+            4 => Self::OutOfRange,
+            5 => Self::WrongBlockKind,
+            10 => Self::TooShort,
+            11 => Self::HeaderTooShort,
+            12 => Self::NoHeader,
+            13 => Self::IdBadValues,
+            14 => Self::CharOutOfBounds,
+            15 => Self::IdNoZeroesAllowed,
+            16 => Self::BasicInfoBadInterface,
+            17 => Self::DescriptorUsedReservedKind,
+            18 => Self::DescriptorRangeLimitsUsedReservedBits,
+            19 => Self::DescriptorRangeLimitsUsedReservedVtsFlag,
+            20 => Self::DescriptorRangeLimitsCvtReservedBits,
+            21 => Self::DescriptorUnexpectedHeader,
+            22 => Self::DescriptorNoFirstCvt,
+            23 => Self::ArrayStringError,
+            24 => Self::TryFromSlice,
+            25 => Self::BcdError,
+            26 => Self::LoadNotText,
+            27 => Self::LoadBadHex,
+            28 => Self::LoadOddHexLength,
+            29 => Self::LoadNoEdid,
+            30 => Self::PatchNoFreeDescriptor,
+            31 => Self::PatchNoSuchExtension,
+            32 => Self::PatchBadProductName,
+            33 => Self::PatchValueOutOfRange,
+            34 => Self::PatchPreferredTimingTooFast,
+            35 => Self::IccBadPrimaries,
+            36 => Self::BadChecksum,
+            37 => Self::ModelineMissingField,
+            38 => Self::ModelineBadValue,
+            _ => return Err(value),
+        })
+    }
+}
+
+/// Returns a static, NUL-terminated description of the given error code.
+///
+/// This takes an `int`, not an `OpticError`, so that codes from a newer
+/// version of the bindings are safe to pass in. Any code this version doesn't
+/// know about gets the same message as `OPTIC_ERROR_UNKNOWN`.
+///
+/// The returned string must not be freed.
+#[no_mangle]
+pub extern "C" fn optic_error_message(error: c_int) -> *const c_char {
+    let error = OpticError::try_from(error).unwrap_or(OpticError::Unknown);
+    let msg: &'static core::ffi::CStr = match error {
+        OpticError::Ok => c"no error",
+        OpticError::NullPointer => c"a required pointer argument was NULL",
+        OpticError::Panicked => c"the parser panicked. please report this alongside the EDID",
+        OpticError::Unknown => c"an unknown error occurred",
+        OpticError::OutOfRange => c"an index argument was out of range",
+        OpticError::WrongBlockKind => c"the block at that index isn't the requested kind",
+        OpticError::TooShort => c"the given EDID data isn't long enough",
+        OpticError::HeaderTooShort => c"the EDID header is too short",
+        OpticError::NoHeader => c"the given EDID does not contain the expected EDID header",
+        OpticError::IdBadValues => c"failed to parse the vendor ID values into ASCII",
+        OpticError::CharOutOfBounds => c"a vendor ID character was out of bounds",
+        OpticError::IdNoZeroesAllowed => c"the vendor ID contained a zero code",
+        OpticError::BasicInfoBadInterface => c"the digital interface bits were not valid",
+        OpticError::DescriptorUsedReservedKind => c"a descriptor used a reserved kind byte",
+        OpticError::DescriptorRangeLimitsUsedReservedBits => {
+            c"the range limits descriptor used reserved bits"
+        }
+        OpticError::DescriptorRangeLimitsUsedReservedVtsFlag => {
+            c"the range limits descriptor used a reserved video timing support flag"
+        }
+        OpticError::DescriptorRangeLimitsCvtReservedBits => {
+            c"the range limits descriptor (CVT) contained reserved values"
+        }
+        OpticError::DescriptorUnexpectedHeader => c"a descriptor had an unexpected header",
+        OpticError::DescriptorNoFirstCvt => c"the CVT descriptor didn't provide its first code",
+        OpticError::ArrayStringError => c"an internal string overflowed",
+        OpticError::TryFromSlice => c"failed to convert a slice into an array",
+        OpticError::BcdError => c"couldn't read a binary-coded decimal number",
+        OpticError::LoadNotText => c"the input isn't a raw EDID, and isn't text either",
+        OpticError::LoadBadHex => c"the input contained a character that isn't hex",
+        OpticError::LoadOddHexLength => c"the input's hex had an odd number of digits",
+        OpticError::LoadNoEdid => c"couldn't find an EDID in the input",
+        OpticError::PatchNoFreeDescriptor => c"the EDID has no unused descriptor left",
+        OpticError::PatchNoSuchExtension => c"the EDID has no extension block at that index",
+        OpticError::PatchBadProductName => c"product names must be up to 13 ASCII characters",
+        OpticError::PatchValueOutOfRange => c"a patched value can't be represented in the EDID",
+        OpticError::PatchPreferredTimingTooFast => {
+            c"the preferred timing is faster than the new maximum pixel clock"
+        }
+        OpticError::IccBadPrimaries => c"the EDID's color primaries can't describe a color space",
+        OpticError::BadChecksum => c"a block of the EDID has the wrong checksum",
+        OpticError::ModelineMissingField => c"the modeline is missing a field",
+        OpticError::ModelineBadValue => c"the modeline has a value that isn't valid",
+    };
+
+    msg.as_ptr()
+}
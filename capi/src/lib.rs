@@ -0,0 +1,469 @@
+//! # `liboptic_capi`
+//!
+//! C bindings for `liboptic_edid`.
+//!
+//! Parse an EDID with `optic_edid_parse`, which gives you an owned
+//! `OpticEdid` handle. Read from it with the `optic_edid_*` accessors, then
+//! release it with `optic_edid_free`. The header lives at
+//! `include/liboptic.h`. It's generated by `cbindgen`, and only rewritten
+//! when `LIBOPTIC_REGENERATE_HEADER` is set at build time.
+//!
+//! Strings are copied into caller-provided buffers, `snprintf`-style: they're
+//! always NUL-terminated (when there's room) and the full length is returned,
+//! so you can check for truncation.
+
+use core::ffi::c_char;
+
+use liboptic_edid::{
+    structures::{
+        _18bytes::EighteenByteBlock, basic_info::vsi::VideoSignalInterface,
+        desc::DisplayDescriptor, id::Manufacturer,
+    },
+    Edid,
+};
+
+pub mod error;
+pub mod types;
+
+use error::OpticError;
+use types::*;
+
+/// An owned, parsed EDID.
+///
+/// Create one with `optic_edid_parse` and free it with `optic_edid_free`.
+pub struct OpticEdid {
+    inner: Edid,
+}
+
+/// Parses `len` bytes of EDID at `data`.
+///
+/// On success, `*out` is set to a new handle that must be freed with
+/// `optic_edid_free`. On failure, `*out` is set to `NULL`.
+///
+/// # Safety
+///
+/// `data` must point to at least `len` readable bytes, and `out` must be a
+/// valid pointer to write to.
+#[no_mangle]
+pub unsafe extern "C" fn optic_edid_parse(
+    data: *const u8,
+    len: usize,
+    out: *mut *mut OpticEdid,
+) -> OpticError {
+    if out.is_null() {
+        return OpticError::NullPointer;
+    }
+    *out = core::ptr::null_mut();
+
+    if data.is_null() {
+        return OpticError::NullPointer;
+    }
+    let bytes = core::slice::from_raw_parts(data, len);
+
+    // unwinding into C is undefined, so we stop any panics here
+    match std::panic::catch_unwind(|| Edid::new(bytes)) {
+        Ok(Ok(inner)) => {
+            *out = Box::into_raw(Box::new(OpticEdid { inner }));
+            OpticError::Ok
+        }
+        Ok(Err(e)) => OpticError::from(&e),
+        Err(_) => OpticError::Panicked,
+    }
+}
+
+/// Frees an EDID handle. Passing `NULL` does nothing.
+///
+/// # Safety
+///
+/// `edid` must be `NULL` or a handle from `optic_edid_parse` that hasn't
+/// already been freed.
+#[no_mangle]
+pub unsafe extern "C" fn optic_edid_free(edid: *mut OpticEdid) {
+    if !edid.is_null() {
+        drop(Box::from_raw(edid));
+    }
+}
+
+/// Gets the Rust EDID from a handle.
+///
+/// # Safety
+///
+/// `edid` must be `NULL` or a live handle.
+unsafe fn get<'a>(edid: *const OpticEdid) -> Option<&'a Edid> {
+    edid.as_ref().map(|e| &e.inner)
+}
+
+/// Copies `s` into `buf` as a NUL-terminated string, truncating if needed.
+///
+/// Returns the length of `s`, not including the NUL.
+///
+/// # Safety
+///
+/// `buf` must be `NULL` or point to `buf_len` writable bytes.
+unsafe fn write_str(s: &str, buf: *mut c_char, buf_len: usize) -> usize {
+    if !buf.is_null() && buf_len > 0 {
+        let n = s.len().min(buf_len - 1);
+        core::ptr::copy_nonoverlapping(s.as_ptr(), buf.cast::<u8>(), n);
+        *buf.add(n) = 0;
+    }
+
+    s.len()
+}
+
+/// Finds the first descriptor string of the given kind, trimmed of its
+/// padding.
+fn descriptor_str(edid: &Edid, f: impl Fn(&DisplayDescriptor) -> Option<&str>) -> Option<&str> {
+    blocks(edid).find_map(|b| match b {
+        EighteenByteBlock::Display(d) => f(d).map(|s| s.trim_end_matches(['\n', ' ', '\0'])),
+        EighteenByteBlock::Timing(_) => None,
+    })
+}
+
+/// All four 18-byte blocks, in order.
+fn blocks(edid: &Edid) -> impl Iterator<Item = &EighteenByteBlock> {
+    let b = &edid.eighteen_byte_data_blocks;
+    core::iter::once(&b.preferred_timing_mode).chain(b.blocks.iter())
+}
+
+/// Writes the EDID's version and revision (like 1 and 4 for v1.4).
+///
+/// # Safety
+///
+/// `edid` must be a live handle. `version` and `revision` must be valid
+/// pointers to write to.
+#[no_mangle]
+pub unsafe extern "C" fn optic_edid_version(
+    edid: *const OpticEdid,
+    version: *mut u8,
+    revision: *mut u8,
+) -> OpticError {
+    let Some(edid) = get(edid) else {
+        return OpticError::NullPointer;
+    };
+    if version.is_null() || revision.is_null() {
+        return OpticError::NullPointer;
+    }
+
+    *version = edid.version.version;
+    *revision = edid.version.revision;
+    OpticError::Ok
+}
+
+/// Copies the manufacturer's name into `buf`.
+///
+/// This is the company name when it's known, or its three-letter PNP ID
+/// otherwise. Returns the full length of the name, or zero if `edid` is
+/// `NULL`.
+///
+/// # Safety
+///
+/// `edid` must be a live handle. `buf` must be `NULL` or point to `buf_len`
+/// writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn optic_edid_manufacturer_name(
+    edid: *const OpticEdid,
+    buf: *mut c_char,
+    buf_len: usize,
+) -> usize {
+    let Some(edid) = get(edid) else {
+        return 0;
+    };
+
+    let name = match &edid.vendor_product_info.manufacturer_name {
+        Manufacturer::Name(name) => name.as_str(),
+        Manufacturer::Id(id) => id.as_str(),
+    };
+    write_str(name, buf, buf_len)
+}
+
+/// Returns the manufacturer's product code, or zero if `edid` is `NULL`.
+///
+/// # Safety
+///
+/// `edid` must be `NULL` or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn optic_edid_product_code(edid: *const OpticEdid) -> u16 {
+    get(edid).map_or(0, |e| e.vendor_product_info.product_code)
+}
+
+/// Writes the numeric serial number into `out`.
+///
+/// Returns `false` (leaving `out` alone) when the display has no serial.
+///
+/// # Safety
+///
+/// `edid` must be a live handle, and `out` must be a valid pointer to write
+/// to.
+#[no_mangle]
+pub unsafe extern "C" fn optic_edid_serial_number(edid: *const OpticEdid, out: *mut u32) -> bool {
+    let Some(serial) = get(edid).and_then(|e| e.vendor_product_info.serial_number) else {
+        return false;
+    };
+    if out.is_null() {
+        return false;
+    }
+
+    *out = serial;
+    true
+}
+
+/// Writes the display's manufacture or model date into `out`.
+///
+/// # Safety
+///
+/// `edid` must be a live handle, and `out` must be a valid pointer to write
+/// to.
+#[no_mangle]
+pub unsafe extern "C" fn optic_edid_date(
+    edid: *const OpticEdid,
+    out: *mut OpticDate,
+) -> OpticError {
+    let Some(edid) = get(edid) else {
+        return OpticError::NullPointer;
+    };
+    if out.is_null() {
+        return OpticError::NullPointer;
+    }
+
+    *out = OpticDate::from(&edid.vendor_product_info.date);
+    OpticError::Ok
+}
+
+/// Copies the product name from the 0xFC descriptor into `buf`.
+///
+/// Returns the full length of the name, or zero if there isn't one.
+///
+/// # Safety
+///
+/// `edid` must be a live handle. `buf` must be `NULL` or point to `buf_len`
+/// writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn optic_edid_product_name(
+    edid: *const OpticEdid,
+    buf: *mut c_char,
+    buf_len: usize,
+) -> usize {
+    let name = get(edid).and_then(|e| {
+        descriptor_str(e, |d| match d {
+            DisplayDescriptor::ProductName(s) => Some(s.as_str()),
+            _ => None,
+        })
+    });
+
+    write_str(name.unwrap_or(""), buf, buf_len)
+}
+
+/// Copies the serial number string from the 0xFF descriptor into `buf`.
+///
+/// Returns the full length of the serial, or zero if there isn't one.
+///
+/// # Safety
+///
+/// `edid` must be a live handle. `buf` must be `NULL` or point to `buf_len`
+/// writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn optic_edid_product_serial(
+    edid: *const OpticEdid,
+    buf: *mut c_char,
+    buf_len: usize,
+) -> usize {
+    let serial = get(edid).and_then(|e| {
+        descriptor_str(e, |d| match d {
+            DisplayDescriptor::ProductSerial(s) => Some(s.as_str()),
+            _ => None,
+        })
+    });
+
+    write_str(serial.unwrap_or(""), buf, buf_len)
+}
+
+/// Whether the display has a digital input.
+///
+/// # Safety
+///
+/// `edid` must be `NULL` or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn optic_edid_is_digital(edid: *const OpticEdid) -> bool {
+    get(edid).is_some_and(|e| {
+        !matches!(
+            e.basic_display_info.input_definition,
+            VideoSignalInterface::Analog { .. }
+        )
+    })
+}
+
+/// Writes the screen size (or aspect ratio) into `out`.
+///
+/// # Safety
+///
+/// `edid` must be a live handle, and `out` must be a valid pointer to write
+/// to.
+#[no_mangle]
+pub unsafe extern "C" fn optic_edid_screen_size(
+    edid: *const OpticEdid,
+    out: *mut OpticScreenSize,
+) -> OpticError {
+    let Some(edid) = get(edid) else {
+        return OpticError::NullPointer;
+    };
+    if out.is_null() {
+        return OpticError::NullPointer;
+    }
+
+    *out = OpticScreenSize::from(edid.basic_display_info.screen_size_or_aspect_ratio.as_ref());
+    OpticError::Ok
+}
+
+/// Writes the display's gamma into `out`.
+///
+/// Returns `false` (leaving `out` alone) when the gamma is instead given in
+/// an extension block.
+///
+/// # Safety
+///
+/// `edid` must be a live handle, and `out` must be a valid pointer to write
+/// to.
+#[no_mangle]
+pub unsafe extern "C" fn optic_edid_gamma(edid: *const OpticEdid, out: *mut f64) -> bool {
+    use num_traits::ToPrimitive as _;
+
+    let Some(gamma) = get(edid).and_then(|e| e.basic_display_info.reported_gamma) else {
+        return false;
+    };
+    if out.is_null() {
+        return false;
+    }
+
+    *out = gamma.to_f64().unwrap_or(0.0);
+    true
+}
+
+/// Writes the display's color characteristics into `out`.
+///
+/// # Safety
+///
+/// `edid` must be a live handle, and `out` must be a valid pointer to write
+/// to.
+#[no_mangle]
+pub unsafe extern "C" fn optic_edid_chromaticity(
+    edid: *const OpticEdid,
+    out: *mut OpticChromaticity,
+) -> OpticError {
+    let Some(edid) = get(edid) else {
+        return OpticError::NullPointer;
+    };
+    if out.is_null() {
+        return OpticError::NullPointer;
+    }
+
+    let c = &edid.color_characteristics;
+    *out = OpticChromaticity {
+        red: (&c.red).into(),
+        green: (&c.green).into(),
+        blue: (&c.blue).into(),
+        white_point: (&c.white_point).into(),
+    };
+    OpticError::Ok
+}
+
+/// Writes the kind of the 18-byte block at `index` (from 0 to 3) into `out`.
+///
+/// Returns `OPTIC_ERROR_OUT_OF_RANGE` if `index` is past the last block.
+///
+/// # Safety
+///
+/// `edid` must be a live handle, and `out` must be a valid pointer to write
+/// to.
+#[no_mangle]
+pub unsafe extern "C" fn optic_edid_block_kind(
+    edid: *const OpticEdid,
+    index: usize,
+    out: *mut OpticBlockKind,
+) -> OpticError {
+    let Some(edid) = get(edid) else {
+        return OpticError::NullPointer;
+    };
+    if out.is_null() {
+        return OpticError::NullPointer;
+    }
+    let Some(block) = blocks(edid).nth(index) else {
+        return OpticError::OutOfRange;
+    };
+
+    *out = match block {
+        EighteenByteBlock::Timing(_) => OpticBlockKind::DetailedTiming,
+        EighteenByteBlock::Display(d) => match d {
+            DisplayDescriptor::ProductSerial(_) => OpticBlockKind::ProductSerial,
+            DisplayDescriptor::DataString(_) => OpticBlockKind::DataString,
+            DisplayDescriptor::DisplayRangeLimits(_) => OpticBlockKind::RangeLimits,
+            DisplayDescriptor::ProductName(_) => OpticBlockKind::ProductName,
+            DisplayDescriptor::ColorPointData { .. } => OpticBlockKind::ColorPoint,
+            DisplayDescriptor::StandardTimingIdentifications { .. } => {
+                OpticBlockKind::StandardTimings
+            }
+            DisplayDescriptor::DcmData { .. } => OpticBlockKind::Dcm,
+            DisplayDescriptor::Cvt3ByteTimingCodes { .. } => OpticBlockKind::Cvt,
+            DisplayDescriptor::EstablishedTimingsIII { .. } => {
+                OpticBlockKind::EstablishedTimingsIii
+            }
+            DisplayDescriptor::DummyDescriptor => OpticBlockKind::Dummy,
+            DisplayDescriptor::Manufacturer { .. } => OpticBlockKind::Manufacturer,
+        },
+    };
+    OpticError::Ok
+}
+
+/// Writes the detailed timing at 18-byte block `index` (from 0 to 3) into
+/// `out`.
+///
+/// Returns `OPTIC_ERROR_OUT_OF_RANGE` if `index` is past the last block, and
+/// `OPTIC_ERROR_WRONG_BLOCK_KIND` if that block isn't a detailed timing.
+/// Either way, `out` is left alone.
+///
+/// # Safety
+///
+/// `edid` must be a live handle, and `out` must be a valid pointer to write
+/// to.
+#[no_mangle]
+pub unsafe extern "C" fn optic_edid_detailed_timing(
+    edid: *const OpticEdid,
+    index: usize,
+    out: *mut OpticDetailedTiming,
+) -> OpticError {
+    let Some(edid) = get(edid) else {
+        return OpticError::NullPointer;
+    };
+    if out.is_null() {
+        return OpticError::NullPointer;
+    }
+
+    match blocks(edid).nth(index) {
+        Some(EighteenByteBlock::Timing(timing)) => {
+            *out = OpticDetailedTiming::from(timing);
+            OpticError::Ok
+        }
+        Some(EighteenByteBlock::Display(_)) => OpticError::WrongBlockKind,
+        None => OpticError::OutOfRange,
+    }
+}
+
+/// Returns the number of extension blocks following the base EDID, or zero
+/// if `edid` is `NULL`.
+///
+/// # Safety
+///
+/// `edid` must be `NULL` or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn optic_edid_extension_count(edid: *const OpticEdid) -> u8 {
+    get(edid).map_or(0, |e| e.extension_info)
+}
+
+/// Returns the base block's checksum byte, or zero if `edid` is `NULL`.
+///
+/// # Safety
+///
+/// `edid` must be `NULL` or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn optic_edid_checksum(edid: *const OpticEdid) -> u8 {
+    get(edid).map_or(0, |e| e.checksum)
+}
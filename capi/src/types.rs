@@ -0,0 +1,196 @@
+//! FFI-safe mirrors of the EDID structures.
+//!
+//! The Rust structures use `Ratio`, `ArrayString`, `Option<T>` and enums
+//! that carry data, none of which have a stable C layout. These types flatten
+//! them into plain numbers and tagged structs.
+
+use liboptic_edid::structures::{
+    _18bytes::timing::{DetailedTimingDefinition, SignalInterfaceType, SyncSignal},
+    basic_info::SizeOrRatio,
+    color::ColorCoordinate,
+    id::Date,
+};
+use num_traits::ToPrimitive as _;
+
+/// Which kind of date the display reported.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OpticDateKind {
+    /// The date the display was manufactured.
+    Manufacture,
+    /// The year the display model was released.
+    ModelYear,
+}
+
+/// When the display came from.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OpticDate {
+    pub kind: OpticDateKind,
+    /// The week of manufacture, from 1-54. Zero when not given, or when
+    /// `kind` is `OPTIC_DATE_KIND_MODEL_YEAR`.
+    pub week: u8,
+    pub year: u16,
+}
+
+impl From<&Date> for OpticDate {
+    fn from(value: &Date) -> Self {
+        match value {
+            Date::Manufacture { week, year } => Self {
+                kind: OpticDateKind::Manufacture,
+                week: week.unwrap_or(0),
+                year: *year,
+            },
+            Date::ModelYear(year) => Self {
+                kind: OpticDateKind::ModelYear,
+                week: 0,
+                year: *year,
+            },
+        }
+    }
+}
+
+/// A coordinate on the CIE 1931 color space graph.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OpticColorCoordinate {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl From<&ColorCoordinate> for OpticColorCoordinate {
+    fn from(value: &ColorCoordinate) -> Self {
+        Self {
+            x: value.x.to_f64().unwrap_or(0.0),
+            y: value.y.to_f64().unwrap_or(0.0),
+        }
+    }
+}
+
+/// The display's primaries and white point.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OpticChromaticity {
+    pub red: OpticColorCoordinate,
+    pub green: OpticColorCoordinate,
+    pub blue: OpticColorCoordinate,
+    pub white_point: OpticColorCoordinate,
+}
+
+/// What the screen size bytes contained.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OpticScreenSizeKind {
+    /// The size isn't given, or may be dynamic (like on a projector).
+    None,
+    /// The screen size in centimeters.
+    Centimeters,
+    /// Only an aspect ratio.
+    AspectRatio,
+}
+
+/// The screen size or aspect ratio of the display.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OpticScreenSize {
+    pub kind: OpticScreenSizeKind,
+    /// Zero when `kind` is `OPTIC_SCREEN_SIZE_KIND_NONE`.
+    pub horizontal: u16,
+    /// Zero when `kind` is `OPTIC_SCREEN_SIZE_KIND_NONE`.
+    pub vertical: u16,
+}
+
+impl From<Option<&SizeOrRatio>> for OpticScreenSize {
+    fn from(value: Option<&SizeOrRatio>) -> Self {
+        match value {
+            None => Self {
+                kind: OpticScreenSizeKind::None,
+                horizontal: 0,
+                vertical: 0,
+            },
+            Some(SizeOrRatio::ScreenSize {
+                horizontal_cm,
+                vertical_cm,
+            }) => Self {
+                kind: OpticScreenSizeKind::Centimeters,
+                horizontal: *horizontal_cm as u16,
+                vertical: *vertical_cm as u16,
+            },
+            Some(SizeOrRatio::AspectRatio {
+                horizontal,
+                vertical,
+            }) => Self {
+                kind: OpticScreenSizeKind::AspectRatio,
+                horizontal: *horizontal,
+                vertical: *vertical,
+            },
+        }
+    }
+}
+
+/// The kind of an 18-byte block.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OpticBlockKind {
+    DetailedTiming,
+    ProductSerial,
+    DataString,
+    RangeLimits,
+    ProductName,
+    ColorPoint,
+    StandardTimings,
+    Dcm,
+    Cvt,
+    EstablishedTimingsIii,
+    Dummy,
+    Manufacturer,
+}
+
+/// A detailed timing definition.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OpticDetailedTiming {
+    /// The pixel clock, in units of 10 kHz, like the EDID stores it.
+    pub pixel_clock_khz: u16,
+    pub horizontal_addressable_video_px: u16,
+    pub horizontal_blanking_px: u16,
+    pub vertical_addressable_video_lines: u16,
+    pub vertical_blanking_lines: u16,
+    pub horizontal_front_porch: u16,
+    pub horizontal_sync_pulse_width_px: u16,
+    pub vertical_front_porch_lines: u8,
+    pub vertical_sync_pulse_width_lines: u8,
+    /// Zero when not given.
+    pub horizontal_addressable_video_size_mm: u16,
+    /// Zero when not given.
+    pub vertical_addressable_video_size_mm: u16,
+    pub horizontal_border_px: u8,
+    pub vertical_border_lines: u8,
+    pub interlaced: bool,
+    /// Whether the sync signal is digital, rather than analog.
+    pub digital_sync: bool,
+}
+
+impl From<&DetailedTimingDefinition> for OpticDetailedTiming {
+    fn from(t: &DetailedTimingDefinition) -> Self {
+        Self {
+            pixel_clock_khz: t.pixel_clock_khz,
+            horizontal_addressable_video_px: t.horizontal_addressable_video_px,
+            horizontal_blanking_px: t.horizontal_blanking_px,
+            vertical_addressable_video_lines: t.vertical_addressable_video_lines,
+            vertical_blanking_lines: t.vertical_blanking_lines,
+            horizontal_front_porch: t.horizontal_front_porch,
+            horizontal_sync_pulse_width_px: t.horizontal_sync_pulse_width_px,
+            vertical_front_porch_lines: t.vertical_front_porch_lines,
+            vertical_sync_pulse_width_lines: t.vertical_sync_pulse_width_lines,
+            horizontal_addressable_video_size_mm: t
+                .horizontal_addressable_video_size_mm
+                .unwrap_or(0),
+            vertical_addressable_video_size_mm: t.vertical_addressable_video_size_mm.unwrap_or(0),
+            horizontal_border_px: t.horizontal_border_px,
+            vertical_border_lines: t.vertical_border_lines,
+            interlaced: t.signal_interface_type == SignalInterfaceType::Interlaced,
+            digital_sync: matches!(t.sync_signal, SyncSignal::Digital(_)),
+        }
+    }
+}
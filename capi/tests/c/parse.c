@@ -0,0 +1,137 @@
+/* Parses an EDID file through the C bindings and checks a few values.
+ *
+ * usage: parse <path to raw edid>
+ */
+
+#include <assert.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "liboptic.h"
+
+int main(int argc, char **argv) {
+    if (argc != 2) {
+        fprintf(stderr, "usage: %s <edid>\n", argv[0]);
+        return 2;
+    }
+
+    FILE *f = fopen(argv[1], "rb");
+    if (!f) {
+        perror("fopen");
+        return 2;
+    }
+    uint8_t data[512];
+    size_t len = fread(data, 1, sizeof(data), f);
+    fclose(f);
+
+    /* the calls happen outside of `assert`, so they still run with NDEBUG */
+    OpticError err;
+    bool ok;
+    size_t n;
+
+    /* bad input gives an error and a NULL handle */
+    OpticEdid *edid = (OpticEdid *)1;
+    err = optic_edid_parse(data, 8, &edid);
+    assert(err == OPTIC_ERROR_TOO_SHORT);
+    assert(edid == NULL);
+    err = optic_edid_parse(NULL, 0, &edid);
+    assert(err == OPTIC_ERROR_NULL_POINTER);
+    printf("error message: %s\n", optic_error_message(OPTIC_ERROR_NO_HEADER));
+
+    /* codes it doesn't know about are fine, too */
+    assert(strcmp(optic_error_message(1000), optic_error_message(OPTIC_ERROR_UNKNOWN)) == 0);
+    assert(strcmp(optic_error_message(-1), optic_error_message(OPTIC_ERROR_UNKNOWN)) == 0);
+
+    err = optic_edid_parse(data, len, &edid);
+    if (err != OPTIC_ERROR_OK) {
+        fprintf(stderr, "failed to parse: %s\n", optic_error_message(err));
+        return 1;
+    }
+
+    uint8_t version, revision;
+    err = optic_edid_version(edid, &version, &revision);
+    assert(err == OPTIC_ERROR_OK);
+    assert(version == 1 && revision == 4);
+
+    char name[64];
+    size_t name_len = optic_edid_manufacturer_name(edid, name, sizeof(name));
+    assert(name_len == strlen("Dell Inc."));
+    assert(strcmp(name, "Dell Inc.") == 0);
+
+    /* truncation still NUL-terminates and reports the full length */
+    char tiny[4];
+    n = optic_edid_manufacturer_name(edid, tiny, sizeof(tiny));
+    assert(n == name_len);
+    assert(strcmp(tiny, "Del") == 0);
+
+    uint16_t product_code = optic_edid_product_code(edid);
+    assert(product_code == 41191);
+
+    uint32_t serial = 0;
+    ok = optic_edid_serial_number(edid, &serial);
+    assert(ok);
+    assert(serial == 1);
+
+    OpticDate date;
+    err = optic_edid_date(edid, &date);
+    assert(err == OPTIC_ERROR_OK);
+    assert(date.kind == OPTIC_DATE_KIND_MANUFACTURE);
+    assert(date.week == 28 && date.year == 2018);
+
+    char product[16];
+    optic_edid_product_name(edid, product, sizeof(product));
+    assert(strcmp(product, "Dell S2417DG") == 0);
+
+    OpticScreenSize size;
+    err = optic_edid_screen_size(edid, &size);
+    assert(err == OPTIC_ERROR_OK);
+    assert(size.kind == OPTIC_SCREEN_SIZE_KIND_CENTIMETERS);
+    assert(size.horizontal == 53 && size.vertical == 30);
+
+    double gamma = 0.0;
+    ok = optic_edid_gamma(edid, &gamma);
+    assert(ok);
+    assert(gamma > 2.19 && gamma < 2.21);
+
+    OpticChromaticity chroma;
+    err = optic_edid_chromaticity(edid, &chroma);
+    assert(err == OPTIC_ERROR_OK);
+    assert(chroma.white_point.x > 0.312 && chroma.white_point.x < 0.313);
+
+    OpticBlockKind kind;
+    err = optic_edid_block_kind(edid, 0, &kind);
+    assert(err == OPTIC_ERROR_OK);
+    assert(kind == OPTIC_BLOCK_KIND_DETAILED_TIMING);
+    err = optic_edid_block_kind(edid, 4, &kind);
+    assert(err == OPTIC_ERROR_OUT_OF_RANGE);
+    err = optic_edid_block_kind(NULL, 0, &kind);
+    assert(err == OPTIC_ERROR_NULL_POINTER);
+
+    OpticDetailedTiming timing;
+    err = optic_edid_detailed_timing(edid, 4, &timing);
+    assert(err == OPTIC_ERROR_OUT_OF_RANGE);
+    err = optic_edid_detailed_timing(edid, 3, &timing);
+    assert(err == OPTIC_ERROR_WRONG_BLOCK_KIND);
+    err = optic_edid_detailed_timing(edid, 0, &timing);
+    assert(err == OPTIC_ERROR_OK);
+    assert(timing.horizontal_addressable_video_px == 2560);
+    assert(timing.vertical_addressable_video_lines == 1440);
+    assert(!timing.interlaced);
+
+    ok = optic_edid_is_digital(edid);
+    assert(ok);
+    uint8_t extensions = optic_edid_extension_count(edid);
+    assert(extensions == 1);
+    uint8_t checksum = optic_edid_checksum(edid);
+    assert(checksum == 0x51);
+
+    /* keep the compiler quiet when the asserts are compiled out */
+    (void)ok;
+    (void)n;
+
+    printf("%s %s (%u) v%u.%u\n", name, product, product_code, version, revision);
+
+    optic_edid_free(edid);
+    optic_edid_free(NULL);
+    return 0;
+}
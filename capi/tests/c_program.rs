@@ -0,0 +1,64 @@
+//! Builds and runs the C test program against the compiled library.
+
+#![cfg(target_os = "linux")]
+
+use std::{path::PathBuf, process::Command};
+
+/// the checked-in header should match what cbindgen makes now
+#[test]
+fn header_up_to_date() {
+    let generated = include_str!(concat!(env!("OUT_DIR"), "/liboptic.h"));
+    let checked_in = std::fs::read_to_string(
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("include/liboptic.h"),
+    )
+    .unwrap();
+
+    assert!(
+        generated == checked_in,
+        "`include/liboptic.h` is out of date. \
+        Run `LIBOPTIC_REGENERATE_HEADER=1 cargo build -p liboptic_capi` to update it."
+    );
+}
+
+#[test]
+fn c_parse_program() {
+    let manifest = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+
+    // the test binary lives in `target/<profile>/deps`, next to the libraries
+    let deps = std::env::current_exe()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .to_path_buf();
+    let profile = deps.parent().unwrap();
+    let static_lib = profile.join("libliboptic_capi.a");
+    assert!(static_lib.exists(), "the static library should be built");
+
+    // with `NDEBUG`, the checks go away but the calls should still run
+    for (name, flags) in [("parse", &[][..]), ("parse_ndebug", &["-DNDEBUG"][..])] {
+        let exe = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+        let status = Command::new("cc")
+            .arg(manifest.join("tests/c/parse.c"))
+            .args(flags)
+            .arg("-I")
+            .arg(manifest.join("include"))
+            .arg(&static_lib)
+            .args(["-lpthread", "-ldl", "-lm", "-o"])
+            .arg(&exe)
+            .status()
+            .expect("a C compiler should be installed");
+        assert!(status.success(), "the C program should compile ({name})");
+
+        let output = Command::new(&exe)
+            .arg(manifest.join("../edid/tests/assets/dell_s2417dg.raw.input"))
+            .output()
+            .unwrap();
+        println!("{}", String::from_utf8_lossy(&output.stdout));
+        eprintln!("{}", String::from_utf8_lossy(&output.stderr));
+
+        assert!(
+            output.status.success(),
+            "the C program should pass ({name})"
+        );
+    }
+}
@@ -0,0 +1,76 @@
+//! Finds and decodes EDIDs from the user's input.
+
+use liboptic_edid::linux;
+use std::{
+    io::Read as _,
+    path::{Path, PathBuf},
+};
+
+/// An EDID, and where it came from.
+#[derive(Debug)]
+pub struct Source {
+    /// A name for the user, like a path or `<stdin>`.
+    pub name: String,
+    pub bytes: Vec<u8>,
+}
+
+/// Reads the EDID at `path`.
+///
+/// A path of `-` reads from stdin, and directories (like DRM connectors in
+/// the `sysfs`) have their `edid` file read instead.
+pub fn read(path: &Path) -> Result<Source, Box<dyn core::error::Error>> {
+    let (name, raw) = if path == Path::new("-") {
+        let mut buf = Vec::new();
+        std::io::stdin().read_to_end(&mut buf)?;
+        (String::from("<stdin>"), buf)
+    } else if path.is_dir() {
+        (
+            path.display().to_string(),
+            std::fs::read(path.join("edid"))?,
+        )
+    } else {
+        (path.display().to_string(), std::fs::read(path)?)
+    };
+
+    Ok(Source {
+        bytes: liboptic_edid::io::load(&raw).map_err(|e| format!("{name}: {e}"))?,
+        name,
+    })
+}
+
+/// Finds every connector in the Linux `sysfs` with a non-empty EDID.
+pub fn drm_connectors() -> Result<Vec<PathBuf>, Box<dyn core::error::Error>> {
+    Ok(linux::connectors()?
+        .into_iter()
+        .filter(|connector| !connector.raw_edid.is_empty())
+        .map(|connector| Path::new(linux::SYSFS_DRM).join(connector.name))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ASSETS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../edid/tests/assets");
+
+    #[test]
+    fn reads_any_format() {
+        let raw = std::fs::read(format!("{ASSETS}/dell_s2417dg.raw.input")).unwrap();
+
+        for name in ["dell_s2417dg.raw.input", "formats/edid_decode.txt"] {
+            let source = read(&Path::new(ASSETS).join(name)).unwrap();
+            assert_eq!(source.bytes, raw, "{name}");
+        }
+
+        // xrandr has a few edids, so we just take the first
+        let xrandr = read(&Path::new(ASSETS).join("formats/xrandr_verbose.txt")).unwrap();
+        assert_eq!(xrandr.bytes.len(), 128);
+    }
+
+    #[test]
+    fn not_an_edid() {
+        let path = Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml"));
+        let err = read(path).unwrap_err().to_string();
+        assert!(err.starts_with(&path.display().to_string()), "{err}");
+    }
+}
@@ -0,0 +1,211 @@
+//! # `liboptic`
+//!
+//! An `edid-decode`-compatible command-line tool.
+//!
+//! Reads EDIDs from files, stdin, or the Linux `sysfs`, and prints a report
+//! about each one.
+
+use std::{path::PathBuf, process::ExitCode};
+
+use clap::Parser;
+use liboptic_edid::{checksum, modeline::Modeline, Edid};
+
+mod input;
+mod report;
+
+/// Decodes EDIDs, like `edid-decode`.
+///
+/// Inputs may be raw binary, hex text, or the output of `edid-decode`,
+/// `xrandr --verbose`, or an Xorg log.
+#[derive(Debug, Parser)]
+#[command(name = "liboptic", version)]
+struct Args {
+    /// Files to read. Use `-` for stdin, or a DRM connector directory like
+    /// `/sys/class/drm/card1-DP-3`.
+    ///
+    /// Reads from stdin when none are given.
+    inputs: Vec<PathBuf>,
+
+    /// Read every connected display from `/sys/class/drm/*/edid`.
+    #[arg(long)]
+    drm: bool,
+
+    /// Print the parsed EDID as JSON.
+    #[arg(long, conflicts_with_all = ["hex", "modelines"])]
+    json: bool,
+
+    /// Check that the EDID parses and that its checksums are valid.
+    ///
+    /// Exits with a failure code if any EDID doesn't pass.
+    #[arg(long)]
+    check: bool,
+
+    /// Only print the EDID's bytes as hex.
+    #[arg(long, conflicts_with = "modelines")]
+    hex: bool,
+
+    /// Only print X11 modelines for the detailed timings.
+    #[arg(long)]
+    modelines: bool,
+
+    /// Compare two EDIDs, printing each field that differs.
+    ///
+    /// Like `diff`, exits with a failure code if they differ.
+    #[arg(long, conflicts_with_all = ["json", "check", "hex", "modelines"])]
+    diff: bool,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+
+    let mut paths = args.inputs.clone();
+    if args.drm {
+        match input::drm_connectors() {
+            Ok(connectors) => paths.extend(connectors),
+            Err(e) => {
+                eprintln!("failed to read `/sys/class/drm`: {e}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    if paths.is_empty() {
+        paths.push(PathBuf::from("-"));
+    }
+
+    if args.diff {
+        return diff(&paths);
+    }
+
+    let mut failed = false;
+    let many = paths.len() > 1;
+    for path in paths {
+        let source = match input::read(&path) {
+            Ok(s) => s,
+            Err(e) => {
+                eprintln!("failed to read `{}`: {e}", path.display());
+                failed = true;
+                continue;
+            }
+        };
+
+        if many {
+            println!("--- {} ---", source.name);
+        }
+        failed |= !run(&args, &source);
+        if many {
+            println!();
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Handles one EDID. Returns `false` if something went wrong.
+fn run(args: &Args, source: &input::Source) -> bool {
+    let bytes = &source.bytes;
+
+    if args.hex {
+        println!("{}", report::hex(bytes));
+        return true;
+    }
+
+    let edid = match Edid::new(bytes) {
+        Ok(edid) => edid,
+        Err(e) => {
+            eprintln!("{}: failed to parse EDID: {e}", source.name);
+            if args.check {
+                println!("EDID conformity: FAIL");
+            }
+            return false;
+        }
+    };
+
+    if args.json {
+        match serde_json::to_string_pretty(&edid) {
+            Ok(json) => println!("{json}"),
+            Err(e) => {
+                eprintln!("{}: failed to make JSON: {e}", source.name);
+                return false;
+            }
+        }
+    } else if args.modelines {
+        let b = &edid.eighteen_byte_data_blocks;
+        for block in core::iter::once(&b.preferred_timing_mode).chain(b.blocks.iter()) {
+            if let liboptic_edid::structures::_18bytes::EighteenByteBlock::Timing(t) = block {
+                println!("{}", Modeline::from(t));
+            }
+        }
+    } else if !args.check {
+        print!("{}", report::report(&edid, bytes));
+    }
+
+    if args.check {
+        check(bytes)
+    } else {
+        true
+    }
+}
+
+/// Compares the EDIDs at two paths.
+fn diff(paths: &[PathBuf]) -> ExitCode {
+    let [old, new] = paths else {
+        eprintln!("`--diff` needs exactly two EDIDs, but got {}", paths.len());
+        return ExitCode::FAILURE;
+    };
+
+    let mut sources = Vec::with_capacity(2);
+    for path in [old, new] {
+        match input::read(path) {
+            Ok(s) => sources.push(s),
+            Err(e) => {
+                eprintln!("failed to read `{}`: {e}", path.display());
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let differences = match liboptic_edid::diff::diff_bytes(&sources[0].bytes, &sources[1].bytes) {
+        Ok(d) => d,
+        Err(e) => {
+            eprintln!("failed to parse EDID: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if differences.is_empty() {
+        println!("No differences.");
+        return ExitCode::SUCCESS;
+    }
+    for difference in differences {
+        println!("{difference}");
+    }
+    ExitCode::FAILURE
+}
+
+/// Checks each block's checksum, printing any failures.
+fn check(bytes: &[u8]) -> bool {
+    let mut ok = true;
+    for (i, block) in bytes.chunks(128).enumerate() {
+        if block.len() != 128 {
+            println!("Block {i}: truncated to {} bytes", block.len());
+            ok = false;
+            continue;
+        }
+
+        let status = checksum::status(block);
+        if !status.is_valid() {
+            println!(
+                "Block {i}: checksum failed (is 0x{:02x}, should be 0x{:02x})",
+                status.stored, status.expected
+            );
+            ok = false;
+        }
+    }
+
+    println!("EDID conformity: {}", if ok { "PASS" } else { "FAIL" });
+    ok
+}
@@ -0,0 +1,533 @@
+//! A human-readable report, laid out like `edid-decode`'s.
+
+use std::fmt::Write as _;
+
+use liboptic_edid::{
+    checksum,
+    structures::{
+        _18bytes::{
+            timing::{DetailedTimingDefinition, SyncPolarity},
+            EighteenByteBlock,
+        },
+        basic_info::{
+            feature_support::{ColorEncodingFormats, ColorSupport, ColorType},
+            vsi::{
+                analog::SignalLevelStandard,
+                digital::{ColorBitDepth, SupportedVideoInterface},
+                VideoSignalInterface,
+            },
+            SizeOrRatio,
+        },
+        color::ColorCoordinate,
+        desc::{display_range_limits::RangeLimitsDesc, DisplayDescriptor},
+        id::{Date, Manufacturer},
+        std_timings::STiming,
+        version::Interpretation,
+    },
+    Edid,
+};
+use num_rational::Ratio;
+
+/// Prints the raw bytes in rows of sixteen.
+pub fn hex(bytes: &[u8]) -> String {
+    bytes
+        .chunks(16)
+        .map(|row| {
+            row.iter()
+                .map(|b| format!("{b:02x}"))
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Builds the full report for an EDID.
+pub fn report(edid: &Edid, bytes: &[u8]) -> String {
+    let mut out = String::new();
+    let o = &mut out;
+
+    _ = writeln!(
+        o,
+        "edid-decode (hex):\n\n{}\n\n----------------\n",
+        hex(bytes)
+    );
+    _ = writeln!(o, "Block 0, Base EDID:");
+    _ = writeln!(
+        o,
+        "  EDID Structure Version & Revision: {}.{}",
+        edid.version.version, edid.version.revision
+    );
+    vendor_product(o, edid);
+    basic_display(o, edid);
+    color(o, edid);
+    established(o, edid);
+    standard(o, edid);
+    blocks(o, edid);
+    _ = writeln!(o, "  Extension blocks: {}", edid.extension_info);
+    _ = writeln!(
+        o,
+        "Checksum: 0x{:02x}{}",
+        edid.checksum,
+        checksum_note(&bytes[..128])
+    );
+
+    for (i, block) in bytes[128..].chunks(128).enumerate() {
+        _ = writeln!(o, "\n----------------\n");
+        _ = writeln!(o, "Block {}, {}:", i + 1, extension_name(block[0]));
+        _ = writeln!(o, "  (this extension isn't decoded by liboptic yet)");
+        if block.len() == 128 {
+            _ = writeln!(o, "Checksum: 0x{:02x}{}", block[127], checksum_note(block));
+        }
+    }
+
+    out
+}
+
+/// Says when a block's checksum is wrong.
+fn checksum_note(block: &[u8]) -> String {
+    let status = checksum::status(block);
+    if status.is_valid() {
+        String::new()
+    } else {
+        format!(" (should be 0x{:02x})", status.expected)
+    }
+}
+
+/// Names an extension block by its tag.
+pub fn extension_name(tag: u8) -> &'static str {
+    match tag {
+        0x02 => "CTA-861 Extension Block",
+        0x10 => "Video Timing Extension Block",
+        0x20 => "EDID 2.0 Extension Block",
+        0x40 => "Display Information Extension Block",
+        0x50 => "Localized String Extension Block",
+        0x60 => "Microdisplay Interface Extension Block",
+        0x70 => "DisplayID Extension Block",
+        0xF0 => "Block Map Extension Block",
+        0xFF => "Manufacturer-Specific Extension Block",
+        _ => "Unknown Extension Block",
+    }
+}
+
+fn vendor_product(o: &mut String, edid: &Edid) {
+    let id = &edid.vendor_product_info;
+    _ = writeln!(o, "  Vendor & Product Identification:");
+    match &id.manufacturer_name {
+        Manufacturer::Name(name) => _ = writeln!(o, "    Manufacturer: {name}"),
+        Manufacturer::Id(pnp) => _ = writeln!(o, "    Manufacturer: {pnp}"),
+    }
+    _ = writeln!(o, "    Model: {}", id.product_code);
+    if let Some(serial) = id.serial_number {
+        _ = writeln!(o, "    Serial Number: {serial} (0x{serial:08x})");
+    }
+    match id.date {
+        Date::Manufacture {
+            week: Some(week),
+            year,
+        } => _ = writeln!(o, "    Made in: week {week} of {year}"),
+        Date::Manufacture { week: None, year } => _ = writeln!(o, "    Made in: {year}"),
+        Date::ModelYear(year) => _ = writeln!(o, "    Model year: {year}"),
+    }
+}
+
+fn basic_display(o: &mut String, edid: &Edid) {
+    let info = &edid.basic_display_info;
+    _ = writeln!(o, "  Basic Display Parameters & Features:");
+
+    match &info.input_definition {
+        VideoSignalInterface::Analog {
+            signal_level_standard,
+            sync_types,
+            serrations,
+            ..
+        } => {
+            _ = writeln!(o, "    Analog display");
+            let level = match signal_level_standard {
+                SignalLevelStandard::_0700S_0300L_1000T => "0.700 : 0.300 : 1.000 V p-p",
+                SignalLevelStandard::_0714S_0286L_1000T => "0.714 : 0.286 : 1.000 V p-p",
+                SignalLevelStandard::_1000S_0400L_1400T => "1.000 : 0.400 : 1.400 V p-p",
+                SignalLevelStandard::_0700S_0000L_0700T => "0.700 : 0.000 : 0.700 V p-p",
+            };
+            _ = writeln!(o, "    Signal Level Standard: {level}");
+            if sync_types.separate_sync_h_and_v {
+                _ = writeln!(o, "    Separate sync supported");
+            }
+            if sync_types.composite_sync_horizontal {
+                _ = writeln!(o, "    Composite sync (on HSync) supported");
+            }
+            if sync_types.composite_sync_green_video {
+                _ = writeln!(o, "    Sync on green supported");
+            }
+            if *serrations {
+                _ = writeln!(o, "    Serration on VSync supported");
+            }
+        }
+        VideoSignalInterface::Digital {
+            color_bit_depth,
+            supported_interface,
+        } => {
+            _ = writeln!(o, "    Digital display");
+            let depth = match color_bit_depth {
+                ColorBitDepth::Undefined => "undefined",
+                ColorBitDepth::D6Bits => "6",
+                ColorBitDepth::D8Bits => "8",
+                ColorBitDepth::D10Bits => "10",
+                ColorBitDepth::D12Bits => "12",
+                ColorBitDepth::D14Bits => "14",
+                ColorBitDepth::D16Bits => "16",
+                ColorBitDepth::Reserved => "reserved",
+            };
+            _ = writeln!(o, "    Bits per primary color channel: {depth}");
+            if let Some(interface) = supported_interface {
+                let name = match interface {
+                    SupportedVideoInterface::Dvi => "DVI",
+                    SupportedVideoInterface::HdmiA => "HDMI-a",
+                    SupportedVideoInterface::HdmiB => "HDMI-b",
+                    SupportedVideoInterface::Mddi => "MDDI",
+                    SupportedVideoInterface::DisplayPort => "DisplayPort",
+                };
+                _ = writeln!(o, "    {name} interface");
+            }
+        }
+        VideoSignalInterface::LegacyDigital { dfp_1x_compatible } => {
+            _ = writeln!(o, "    Digital display");
+            if *dfp_1x_compatible {
+                _ = writeln!(o, "    DFP 1.x compatible TMDS");
+            }
+        }
+    }
+
+    match &info.screen_size_or_aspect_ratio {
+        Some(SizeOrRatio::ScreenSize {
+            horizontal_cm,
+            vertical_cm,
+        }) => {
+            _ = writeln!(
+                o,
+                "    Maximum image size: {horizontal_cm} cm x {vertical_cm} cm"
+            );
+        }
+        Some(SizeOrRatio::AspectRatio {
+            horizontal,
+            vertical,
+        }) => _ = writeln!(o, "    Aspect ratio: {horizontal}:{vertical}"),
+        None => _ = writeln!(o, "    Image size is variable"),
+    }
+
+    match info.reported_gamma {
+        Some(gamma) => _ = writeln!(o, "    Gamma: {:.2}", exact(gamma)),
+        None => _ = writeln!(o, "    Gamma is defined in an extension block"),
+    }
+
+    let features = &info.feature_support;
+    let pm = &features.power_management;
+    for (supported, name) in [
+        (pm.standby, "Standby"),
+        (pm.suspend, "Suspend"),
+        (pm.active_off, "Active Off"),
+    ] {
+        if supported {
+            _ = writeln!(o, "    DPMS levels: {name}");
+        }
+    }
+
+    let color = match &features.color_support {
+        ColorSupport::Type(ColorType::MonochromeOrGrayscale) => "Monochrome or grayscale display",
+        ColorSupport::Type(ColorType::RgbColor) => "RGB color display",
+        ColorSupport::Type(ColorType::NonRgbColor) => "Non-RGB color display",
+        ColorSupport::Type(ColorType::Undefined) => "Undefined display color type",
+        ColorSupport::EncodingFormats(f) => match f {
+            ColorEncodingFormats::Rgb444 => "Supported color formats: RGB 4:4:4",
+            ColorEncodingFormats::Rgb444_YCrCb444 => {
+                "Supported color formats: RGB 4:4:4, YCrCb 4:4:4"
+            }
+            ColorEncodingFormats::Rgb444_YCrCb422 => {
+                "Supported color formats: RGB 4:4:4, YCrCb 4:2:2"
+            }
+            ColorEncodingFormats::Rgb444_YCrCb444_YCrCb422 => {
+                "Supported color formats: RGB 4:4:4, YCrCb 4:4:4, YCrCb 4:2:2"
+            }
+        },
+    };
+    _ = writeln!(o, "    {color}");
+
+    if features.srgb_std {
+        _ = writeln!(o, "    Default (sRGB) color space is primary color space");
+    }
+    if features.says_pixel_format_and_refresh {
+        _ = writeln!(
+            o,
+            "    First detailed timing includes the native pixel format and preferred refresh rate"
+        );
+    }
+    if features.is_continuous_freq {
+        _ = writeln!(o, "    Display is continuous frequency");
+    }
+    if features.supports_default_gtf {
+        _ = writeln!(o, "    Supports GTF timings within operating range");
+    }
+}
+
+fn color(o: &mut String, edid: &Edid) {
+    let c = &edid.color_characteristics;
+    let coord = |c: &ColorCoordinate| format!("{:.4}, {:.4}", exact(c.x), exact(c.y));
+
+    _ = writeln!(o, "  Color Characteristics:");
+    _ = writeln!(o, "    Red  : {}", coord(&c.red));
+    _ = writeln!(o, "    Green: {}", coord(&c.green));
+    _ = writeln!(o, "    Blue : {}", coord(&c.blue));
+    _ = writeln!(o, "    White: {}", coord(&c.white_point));
+}
+
+fn established(o: &mut String, edid: &Edid) {
+    let (i, ii) = (&edid.established_timings.i, &edid.established_timings.ii);
+    let m = &edid.established_timings.manufacturer_timings;
+
+    let all = [
+        (i._720x400_70hz, "720x400    70.082 Hz"),
+        (i._720x400_88hz, "720x400    87.849 Hz"),
+        (i._640x480_60hz, "640x480    59.940 Hz"),
+        (i._640x480_67hz, "640x480    66.667 Hz"),
+        (i._640x480_72hz, "640x480    72.809 Hz"),
+        (i._640x480_75hz, "640x480    75.000 Hz"),
+        (i._800x600_56hz, "800x600    56.250 Hz"),
+        (i._800x600_60hz, "800x600    60.317 Hz"),
+        (ii._800x600_72hz, "800x600    72.188 Hz"),
+        (ii._800x600_75hz, "800x600    75.000 Hz"),
+        (ii._832x624_75hz, "832x624    74.551 Hz"),
+        (ii._1024x768_87hz_interlaced, "1024x768i  86.957 Hz"),
+        (ii._1024x768_60hz, "1024x768   60.004 Hz"),
+        (ii._1024x768_70hz, "1024x768   70.069 Hz"),
+        (ii._1024x768_75hz, "1024x768   75.029 Hz"),
+        (ii._1280x1024_75hz, "1280x1024  75.025 Hz"),
+        (m._1152x870_75hz, "1152x870   75.062 Hz"),
+    ];
+
+    _ = writeln!(o, "  Established Timings I & II:");
+    let mut any = false;
+    for (_, timing) in all.iter().filter(|(set, _)| *set) {
+        _ = writeln!(o, "    {timing}");
+        any = true;
+    }
+    if !any {
+        _ = writeln!(o, "    none");
+    }
+}
+
+/// Prints a standard timing as `1920x1080 60 Hz 16:9`.
+fn std_timing(t: &STiming, interpretation: Interpretation) -> String {
+    let (w, h) = t.aspect_ratio.ratio(interpretation);
+    let ratio = format!("{w}:{h}");
+    format!(
+        "{}x{} {:>3} Hz {ratio:>5}",
+        t.horizontal_addr_pixel_ct,
+        t.vertical_active(interpretation),
+        t.field_refresh_rate
+    )
+}
+
+fn standard(o: &mut String, edid: &Edid) {
+    let s = &edid.standard_timings;
+
+    _ = writeln!(o, "  Standard Timings:");
+    let mut any = false;
+    for t in s.iter() {
+        _ = writeln!(o, "    {}", std_timing(t, s.interpretation));
+        any = true;
+    }
+    if !any {
+        _ = writeln!(o, "    none");
+    }
+}
+
+/// Prints a detailed timing in three lines.
+fn dtd(o: &mut String, n: usize, t: &DetailedTimingDefinition) {
+    let size = match (
+        t.horizontal_addressable_video_size_mm,
+        t.vertical_addressable_video_size_mm,
+    ) {
+        (Some(h), Some(v)) => format!(" ({h} mm x {v} mm)"),
+        _ => String::new(),
+    };
+    let pol = |p: Option<SyncPolarity>, positive, negative| match p {
+        Some(SyncPolarity::Positive) => positive,
+        Some(SyncPolarity::Negative) => negative,
+        None => "",
+    };
+    let h_pol = pol(t.h_sync_polarity(), " Hpol P", " Hpol N");
+    let v_pol = pol(t.v_sync_polarity(), " Vpol P", " Vpol N");
+
+    _ = writeln!(
+        o,
+        "    DTD {n}: {:>5}x{:<5} {:>10.6} Hz {:>10.6} kHz {:>10.6} MHz{size}",
+        t.horizontal_addressable_video_px,
+        t.vertical_addressable_video_lines,
+        t.refresh_rate().map_or(0.0, ratio),
+        t.h_freq_khz().map_or(0.0, ratio),
+        t.pixel_clock_hz() as f64 / 1_000_000.0,
+    );
+    _ = writeln!(
+        o,
+        "                 Hfront {:>4} Hsync {:>3} Hback {:>4}{h_pol}",
+        t.horizontal_front_porch,
+        t.horizontal_sync_pulse_width_px,
+        t.h_back_porch()
+    );
+    _ = writeln!(
+        o,
+        "                 Vfront {:>4} Vsync {:>3} Vback {:>4}{v_pol}",
+        t.vertical_front_porch_lines,
+        t.vertical_sync_pulse_width_lines,
+        t.v_back_porch()
+    );
+}
+
+/// Converts an exact rate to a float for printing.
+fn ratio(r: Ratio<u64>) -> f64 {
+    *r.numer() as f64 / *r.denom() as f64
+}
+
+/// Converts a small exact value, like a gamma, to a float for printing.
+fn exact<T: Copy + Into<f64>>(r: Ratio<T>) -> f64 {
+    (*r.numer()).into() / (*r.denom()).into()
+}
+
+/// Trims the newline and padding off of a descriptor string.
+fn trim(s: &str) -> &str {
+    s.trim_end_matches(['\n', ' '])
+}
+
+fn blocks(o: &mut String, edid: &Edid) {
+    let b = &edid.eighteen_byte_data_blocks;
+    let all = core::iter::once(&b.preferred_timing_mode).chain(b.blocks.iter());
+
+    _ = writeln!(o, "  Detailed Timing Descriptors:");
+    let mut dtds = 0;
+    for block in all {
+        match block {
+            EighteenByteBlock::Timing(t) => {
+                dtds += 1;
+                dtd(o, dtds, t);
+            }
+            EighteenByteBlock::Display(d) => descriptor(o, d, b.interpretation),
+        }
+    }
+}
+
+fn descriptor(o: &mut String, d: &DisplayDescriptor, interpretation: Interpretation) {
+    match d {
+        DisplayDescriptor::ProductSerial(s) => {
+            _ = writeln!(o, "    Display Product Serial Number: '{}'", trim(s));
+        }
+        DisplayDescriptor::DataString(s) => {
+            _ = writeln!(o, "    Alphanumeric Data String: '{}'", trim(s));
+        }
+        DisplayDescriptor::ProductName(s) => {
+            _ = writeln!(o, "    Display Product Name: '{}'", trim(s));
+        }
+        DisplayDescriptor::DisplayRangeLimits(r) => {
+            let l = r.limits();
+            let kind = match r {
+                RangeLimitsDesc::LimitsOnly { .. } => "Range Limits Only",
+                RangeLimitsDesc::GtfSupported { .. } => "GTF",
+                RangeLimitsDesc::GtfSecondaryCurveSupported { .. } => "Secondary GTF",
+                RangeLimitsDesc::CvtSupported { .. } => "CVT",
+            };
+            _ = writeln!(o, "    Display Range Limits:");
+            _ = writeln!(
+                o,
+                "      Monitor ranges ({kind}): {}-{} Hz V, {}-{} kHz H, max dotclock {} MHz",
+                l.min_v_rate_hz,
+                l.max_v_rate_hz,
+                l.min_h_rate_khz,
+                l.max_h_rate_khz,
+                l.max_pixel_clock_mhz
+            );
+        }
+        DisplayDescriptor::ColorPointData { w1, w2 } => {
+            _ = writeln!(o, "    Color Point Data:");
+            for w in [w1, w2] {
+                let gamma = w
+                    .gamma
+                    .map(|g| format!("{:.2}", exact(g)))
+                    .unwrap_or_else(|| "in extension".into());
+                _ = writeln!(
+                    o,
+                    "      Index: {} White: {:.4}, {:.4} Gamma: {gamma}",
+                    w.index_number,
+                    exact(w.coord.x),
+                    exact(w.coord.y)
+                );
+            }
+        }
+        DisplayDescriptor::StandardTimingIdentifications { timings } => {
+            _ = writeln!(o, "    Standard Timing Identifications:");
+            for t in timings.iter().flatten() {
+                _ = writeln!(o, "      {}", std_timing(t, interpretation));
+            }
+        }
+        DisplayDescriptor::DcmData {
+            version_number,
+            red,
+            green,
+            blue,
+        } => {
+            _ = writeln!(
+                o,
+                "    Display Color Management Data (version {version_number})"
+            );
+            for (name, curve) in [("Red", red), ("Green", green), ("Blue", blue)] {
+                _ = writeln!(
+                    o,
+                    "      {name} a3: {:.2}, a2: {:.2}",
+                    exact(curve.a3),
+                    exact(curve.a2)
+                );
+            }
+        }
+        DisplayDescriptor::Cvt3ByteTimingCodes { version_number, .. } => {
+            _ = writeln!(o, "    CVT 3 Byte Timing Codes (version {version_number})");
+        }
+        DisplayDescriptor::EstablishedTimingsIII { .. } => {
+            _ = writeln!(o, "    Established timings III");
+        }
+        DisplayDescriptor::DummyDescriptor => _ = writeln!(o, "    Dummy Descriptor"),
+        DisplayDescriptor::Manufacturer { data } => {
+            _ = writeln!(
+                o,
+                "    Manufacturer-Specified Display Descriptor (0x{:02x})",
+                data[3]
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dell_s2417dg_report() {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../edid/tests/assets/dell_s2417dg.raw.input"
+        );
+        let bytes = std::fs::read(path).unwrap();
+        let edid = Edid::new(&bytes).unwrap();
+        let got = report(&edid, &bytes);
+
+        for line in [
+            "Block 0, Base EDID:",
+            "  EDID Structure Version & Revision: 1.4",
+            "    Made in: week 28 of 2018",
+            "    Maximum image size: 53 cm x 30 cm",
+            "    Gamma: 2.20",
+            "                 Vfront    3 Vsync   5 Vback   33 Vpol N",
+            "    Display Product Name: 'Dell S2417DG'",
+            "Block 1, CTA-861 Extension Block:",
+        ] {
+            assert!(got.contains(line), "missing `{line}` in:\n{got}");
+        }
+    }
+}
@@ -1,24 +1,36 @@
-//! Loads an EDID file from the Linux `sysfs` and parses it.
+//! Finds the displays connected to this machine and parses their EDIDs.
+//!
+//! Run with `cargo run --example linux --features linux`.
 
-#![cfg(target_os = "linux")]
+#[cfg(target_os = "linux")]
+use liboptic_edid::linux::{self, ConnectorStatus};
 
+#[cfg(target_os = "linux")]
 fn main() -> Result<(), Box<dyn core::error::Error>> {
-    // let's parse an edid file from disk! on linux, each monitor has one
-    // located on the `sysfs`!
-    //
-    // here's mine! you can find yours by `cat`-ing each `card<N-STUFF>` and
-    // looking for files with gibberish in them .
-    let file_path = "/sys/class/drm/card1-DP-3/edid";
+    // on linux, each connector on your graphics card has a folder in the
+    // `sysfs`. the `linux` module reads all of them for us!
+    let connectors = linux::connectors()?;
 
-    // now, load the file into a slice. make sure to use a method that returns
-    // raw bytes! a string won't work for this.
-    let bytes = std::fs::read(file_path)?;
+    for connector in connectors {
+        // skip anything without a display plugged in
+        if connector.status != ConnectorStatus::Connected {
+            continue;
+        }
 
-    // finally, just feed it to the parser
-    let parsed_edid = liboptic_edid::Edid::new(&bytes)?;
+        println!("{} ({} modes)", connector.name, connector.modes.len());
 
-    // here's the checksum!
-    println!("Checksum: {:x}", parsed_edid.checksum);
+        // the kernel gives us an edid for each display. here's its checksum!
+        match connector.edid {
+            Some(edid) => println!("  Checksum: {:x}", edid.checksum),
+            None => println!("  No EDID :("),
+        }
+    }
 
     Ok(())
 }
+
+// there's no sysfs elsewhere, but the example should still build
+#[cfg(not(target_os = "linux"))]
+fn main() {
+    eprintln!("This example only runs on Linux.");
+}
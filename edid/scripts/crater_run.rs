@@ -105,9 +105,9 @@ fn parse_all() -> Vec<Entry> {
             if let Ok(entry) = entry {
                 if let Some(res) = run(entry.path()) {
                     runs.push(res.clone());
-                    if res.parsed_edid.is_err() {
+                    if let Err(ref e) = res.parsed_edid {
                         println!(
-                            "err on {}th run! (path: {})",
+                            "err on {}th run! (path: {})\n{e}",
                             runs.len(),
                             res.path.display()
                         );
@@ -1,5 +1,8 @@
 //! reads a display's edid from disk and prints its sections.
 //!
+//! pass a filename from `tests/assets/` to pick which one. for a full report,
+//! try the `liboptic` command-line tool instead.
+//!
 //! ```cargo
 //! [dependencies]
 //! colored = "2.1.0"
@@ -10,12 +13,19 @@ use colored::Colorize;
 
 fn main() {
     // open up the file
-    const PATH: &str = "linuxhw_edid_Digital_Goldstar_GSM7666_FE91A60D5B6E.input";
-    let info = edid_by_filename(PATH);
+    const DEFAULT_PATH: &str = "linuxhw_edid_Digital_Goldstar_GSM7666_FE91A60D5B6E.input";
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_PATH.to_string());
+    let info = if path.contains(".raw") {
+        raw_edid_by_filename(&path)
+    } else {
+        edid_by_filename(&path)
+    };
 
     use std::format as f;
 
-    println!("for file: `{PATH}`...\n");
+    println!("for file: `{path}`...\n");
 
     println!("{}", f!("Header: {:x?}", &info[0x00..0x08]).blue());
     println!(
@@ -102,12 +112,7 @@ fn main() {
 /// Grabs a raw (not encoded) EDID from disk at `tests/assets/`
 #[allow(unused)]
 pub(crate) fn raw_edid_by_filename(name: &str) -> Vec<u8> {
-    let path = std::env::current_dir()
-        .unwrap()
-        .join("tests/assets")
-        .join(name);
-
-    std::fs::read(path).unwrap()
+    std::fs::read(path().join(name)).unwrap()
 }
 
 /// Grabs an EDID from disk at `tests/assets/`
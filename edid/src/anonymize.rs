@@ -0,0 +1,397 @@
+//! Removes identifying information from EDIDs, so they can be shared.
+//!
+//! EDIDs carry serial numbers that can identify a specific display (and by
+//! extension, its owner). These get pasted into bug reports all the time, so
+//! `anonymize` makes that a bit safer.
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+use crate::checksum::fix_checksums;
+use crate::parser::header::{BLOCK_LEN, EDID_HEADER};
+use crate::prelude::internal::*;
+
+/// Microsoft's IEEE OUI, which it uses for its CTA vendor-specific data
+/// block. (as stored, in little-endian)
+const MICROSOFT_OUI: [u8; 3] = [0x5C, 0x12, 0xCA];
+
+/// What to remove from an EDID, on top of its serial numbers.
+///
+/// The numeric serial number and any serial number descriptors (tag
+/// `0xFF`) are always removed.
+#[repr(C)]
+#[derive(Clone, Debug, Default, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AnonymizeOptions {
+    /// Clears the week of manufacture. The year stays, since it's useful
+    /// for figuring out how a display behaves.
+    pub manufacture_week: bool,
+
+    /// Blanks any data string descriptors (tag `0xFE`). Some displays keep
+    /// another serial number in these.
+    pub data_strings: bool,
+
+    /// Zeroes the serial numbers in DisplayID Product Identification blocks.
+    pub extension_serials: bool,
+
+    /// Zeroes container IDs, which are unique to each display. These are in
+    /// DisplayID Container ID blocks and Microsoft's CTA vendor-specific
+    /// block.
+    pub container_ids: bool,
+}
+
+impl AnonymizeOptions {
+    /// Removes everything that this module knows about.
+    pub fn all() -> Self {
+        Self {
+            manufacture_week: true,
+            data_strings: true,
+            extension_serials: true,
+            container_ids: true,
+        }
+    }
+}
+
+/// Makes a copy of `edid` without its identifying information.
+///
+/// Every block gets a new checksum afterwards. This doesn't require the EDID
+/// to parse, as broken EDIDs are usually the ones being shared!
+///
+/// ```edition2021
+/// use liboptic_edid::{anonymize, AnonymizeOptions, Edid};
+///
+/// let original = std::fs::read("tests/assets/dell_s2417dg.raw.input")?;
+/// let anonymous = anonymize(&original, AnonymizeOptions::all())?;
+///
+/// let edid = Edid::new(&anonymous)?;
+/// assert_eq!(edid.vendor_product_info.serial_number, None);
+/// #
+/// # Ok::<(), Box<dyn core::error::Error>>(())
+/// ```
+#[cfg_attr(feature = "tracing", tracing::instrument(skip(edid)))]
+pub fn anonymize(edid: &[u8], options: AnonymizeOptions) -> Result<Vec<u8>, EdidError> {
+    if edid.len() < BLOCK_LEN {
+        tracing::error!("The EDID is too short to anonymize. (len: {})", edid.len());
+        return Err(EdidErrorKind::TooShort {
+            got: edid.len().min(u8::MAX.into()) as u8,
+            expected: BLOCK_LEN as u8,
+        }
+        .into());
+    }
+    if !edid.starts_with(&EDID_HEADER) {
+        return Err(EdidErrorKind::NoHeader.into());
+    }
+
+    // drop any partial block at the end
+    let mut bytes = edid[..edid.len() - edid.len() % BLOCK_LEN].to_vec();
+
+    // the numeric serial number
+    bytes[0x0C..=0x0F].fill(0x00);
+
+    // a week of `0xFF` means the year is a model year, so we keep that
+    if options.manufacture_week && bytes[0x10] != 0xFF {
+        bytes[0x10] = 0x00;
+    }
+
+    // the base block's descriptors
+    for start in [0x36, 0x48, 0x5A, 0x6C] {
+        descriptor(&mut bytes[start..start + 18], &options);
+    }
+
+    for block in bytes[BLOCK_LEN..].chunks_exact_mut(BLOCK_LEN) {
+        match block[0] {
+            0x02 => cta(block, &options),
+            0x70 => displayid(block, &options),
+            other => tracing::debug!("Skipping extension with tag `{other:#x}`."),
+        }
+    }
+
+    fix_checksums(&mut bytes);
+    Ok(bytes)
+}
+
+/// Blanks an 18-byte descriptor, if it's one we should remove.
+fn descriptor(descriptor: &mut [u8], options: &AnonymizeOptions) {
+    // timings don't hold anything personal
+    if descriptor[0] != 0x00 || descriptor[1] != 0x00 {
+        return;
+    }
+
+    let blank = match descriptor[3] {
+        0xFF => true,
+        0xFE => options.data_strings,
+        _ => false,
+    };
+
+    if blank {
+        tracing::info!("Blanking descriptor with tag `{:#x}`.", descriptor[3]);
+        descriptor[5] = 0x0A;
+        descriptor[6..].fill(0x20);
+    }
+}
+
+/// Cleans up a CTA-861 extension block.
+fn cta(block: &mut [u8], options: &AnonymizeOptions) {
+    // a zero offset means there's nothing in the block at all
+    if block[2] == 0x00 {
+        return;
+    }
+
+    // byte 2 says where the detailed timings start. before that is the data
+    // block collection
+    let dtd_start = usize::from(block[2]).clamp(4, BLOCK_LEN - 1);
+
+    if options.container_ids {
+        let mut i = 4;
+        while i < dtd_start {
+            let tag = block[i] >> 5;
+            let len = usize::from(block[i] & 0x1F);
+            let end = (i + 1 + len).min(dtd_start);
+            let payload = &mut block[i + 1..end];
+
+            // microsoft's block has a version, a byte of flags, then the
+            // container id
+            if tag == 3 && payload.starts_with(&MICROSOFT_OUI) && payload.len() >= 21 {
+                tracing::info!("Clearing the container ID in Microsoft's CTA block.");
+                payload[5..21].fill(0x00);
+            }
+
+            i = end;
+        }
+    }
+
+    // display descriptors can live alongside the timings, too
+    let mut start = dtd_start;
+    while start + 18 < BLOCK_LEN {
+        descriptor(&mut block[start..start + 18], options);
+        start += 18;
+    }
+}
+
+/// Cleans up a DisplayID extension block.
+fn displayid(block: &mut [u8], options: &AnonymizeOptions) {
+    // the section starts after the extension tag, and its payload starts
+    // after a four-byte header
+    let payload_end = (5 + usize::from(block[2])).min(BLOCK_LEN - 2);
+
+    let mut i = 5;
+    while i + 3 <= payload_end {
+        let tag = block[i];
+        let len = usize::from(block[i + 2]);
+        let end = (i + 3 + len).min(payload_end);
+        let payload = &mut block[i + 3..end];
+
+        match tag {
+            // product identification (v1.x and v2.x). the serial comes after
+            // the oui and product code
+            0x00 | 0x20 if options.extension_serials && payload.len() >= 10 => {
+                tracing::info!("Clearing the DisplayID product serial.");
+                payload[5..9].fill(0x00);
+                if options.manufacture_week && payload[9] != 0xFF {
+                    payload[9] = 0x00;
+                }
+            }
+
+            // container id
+            0x29 if options.container_ids && payload.len() >= 16 => {
+                tracing::info!("Clearing the DisplayID container ID.");
+                payload[..16].fill(0x00);
+            }
+
+            _ => (),
+        }
+
+        i = end;
+    }
+
+    // displayid has its own checksum, covering the section
+    let sum = block[1..payload_end]
+        .iter()
+        .fold(0_u8, |sum, b| sum.wrapping_add(*b));
+    block[payload_end] = sum.wrapping_neg();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a displayid extension with a product id and container id
+    fn displayid_block() -> [u8; BLOCK_LEN] {
+        let mut block = [0x00; BLOCK_LEN];
+        let section = [
+            0x70, 0x20, 0x00, 0x00, 0x00, // header. we'll fill the length in later
+            // product identification
+            0x20, 0x00, 0x0F, // tag, revision, length
+            0x00, 0x1A, 0x2B, // oui
+            0x34, 0x12, // product code
+            0xDE, 0xAD, 0xBE, 0xEF, // serial
+            0x2A, 0x21, // week, year
+            0x00, // string length
+            0x12, 0x34, 0x56, // padding to fill out the length
+            // container id
+            0x29, 0x00, 0x10, // tag, revision, length
+            0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E,
+            0x0F, 0x10,
+        ];
+        block[..section.len()].copy_from_slice(&section);
+        block[2] = (section.len() - 5) as u8;
+
+        // and the section checksum
+        let sum = block[1..section.len()]
+            .iter()
+            .fold(0_u8, |s, b| s.wrapping_add(*b));
+        block[section.len()] = sum.wrapping_neg();
+        block
+    }
+
+    /// a cta extension with microsoft's block, and a serial descriptor
+    fn cta_block() -> [u8; BLOCK_LEN] {
+        let mut block = [0x00; BLOCK_LEN];
+        let data = [
+            0x02, 0x03, 0x1B, 0x00, // header
+            0x75, // vendor-specific, 21 bytes
+            0x5C, 0x12, 0xCA, 0x02, 0x01, // oui, version, flags
+            0xAA, 0xAA, 0xAA, 0xAA, 0xAA, 0xAA, 0xAA, 0xAA, 0xAA, 0xAA, 0xAA, 0xAA, 0xAA, 0xAA,
+            0xAA, 0xAA,
+        ];
+        block[..data.len()].copy_from_slice(&data);
+
+        // a serial descriptor, where the timings go
+        block[0x1B..0x1B + 18].copy_from_slice(&[
+            0x00, 0x00, 0x00, 0xFF, 0x00, b'S', b'E', b'C', b'R', b'E', b'T', 0x0A, 0x20, 0x20,
+            0x20, 0x20, 0x20, 0x20,
+        ]);
+        block
+    }
+
+    /// the dell, with two more extensions stuck on the end
+    fn dell_with_extensions() -> Vec<u8> {
+        let mut input = raw_edid_by_filename("dell_s2417dg.raw.input");
+        input.extend_from_slice(&displayid_block());
+        input.extend_from_slice(&cta_block());
+        input[0x7E] = 3;
+        fix_checksums(&mut input);
+        input
+    }
+
+    fn assert_checksums(bytes: &[u8]) {
+        for block in bytes.chunks(BLOCK_LEN) {
+            assert_eq!(block.iter().fold(0_u8, |s, b| s.wrapping_add(*b)), 0);
+        }
+    }
+
+    #[test]
+    fn dell_s2417dg_defaults() {
+        logger();
+        let input = raw_edid_by_filename("dell_s2417dg.raw.input");
+        let got = anonymize(&input, AnonymizeOptions::default()).unwrap();
+        assert_checksums(&got);
+
+        let edid = Edid::new(&got).unwrap();
+        assert_eq!(edid.vendor_product_info.serial_number, None);
+        assert_eq!(
+            edid.eighteen_byte_data_blocks.blocks[0],
+            EighteenByteBlock::Display(DisplayDescriptor::ProductSerial(
+                arrayvec::ArrayString::from("\n            ").unwrap()
+            ))
+        );
+
+        // and nothing else should change
+        let original = Edid::new(&input).unwrap();
+        assert_eq!(
+            edid.vendor_product_info.date,
+            original.vendor_product_info.date
+        );
+        assert_eq!(got[BLOCK_LEN..], input[BLOCK_LEN..]);
+    }
+
+    #[test]
+    fn everything() {
+        logger();
+        let input = dell_with_extensions();
+        let got = anonymize(&input, AnonymizeOptions::all()).unwrap();
+        assert_checksums(&got);
+
+        // week of manufacture
+        assert_eq!(got[0x10], 0x00);
+
+        // the displayid serial, week, and container id
+        let displayid = &got[BLOCK_LEN * 2..BLOCK_LEN * 3];
+        assert_eq!(displayid[13..17], [0x00; 4]);
+        assert_eq!(displayid[17], 0x00);
+        assert_eq!(displayid[18], 0x21, "the year should stay");
+        assert_eq!(displayid[26..42], [0x00; 16]);
+
+        // its section checksum should be fixed up, too
+        let section_end = 5 + usize::from(displayid[2]);
+        assert_eq!(
+            displayid[1..=section_end]
+                .iter()
+                .fold(0_u8, |s, b| s.wrapping_add(*b)),
+            0
+        );
+
+        // and the cta block's container id and serial
+        let cta = &got[BLOCK_LEN * 3..];
+        assert_eq!(cta[10..26], [0x00; 16]);
+        assert_eq!(cta[0x1B + 5], 0x0A);
+        assert!(cta[0x1B + 6..0x1B + 18].iter().all(|b| *b == 0x20));
+    }
+
+    /// the extension options are off by default
+    #[test]
+    fn extensions_untouched() {
+        logger();
+        let input = dell_with_extensions();
+        let got = anonymize(&input, AnonymizeOptions::default()).unwrap();
+
+        assert_eq!(
+            got[BLOCK_LEN * 2..BLOCK_LEN * 3],
+            input[BLOCK_LEN * 2..BLOCK_LEN * 3]
+        );
+        assert_eq!(got[BLOCK_LEN * 3 + 10..BLOCK_LEN * 3 + 26], [0xAA; 16]);
+    }
+
+    #[test]
+    fn data_strings() {
+        logger();
+        let input = edid_by_filename("1.input");
+        let got = anonymize(
+            &input,
+            AnonymizeOptions {
+                data_strings: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let blocks = Edid::new(&got).unwrap().eighteen_byte_data_blocks.blocks;
+        for block in &blocks[1..] {
+            assert_eq!(
+                *block,
+                EighteenByteBlock::Display(DisplayDescriptor::DataString(
+                    arrayvec::ArrayString::from("\n            ").unwrap()
+                ))
+            );
+        }
+    }
+
+    #[test]
+    fn not_an_edid() {
+        logger();
+        assert!(matches!(
+            anonymize(&[0x00; 12], AnonymizeOptions::default()),
+            Err(EdidError {
+                kind: EdidErrorKind::TooShort { got: 12, .. },
+                ..
+            })
+        ));
+        assert!(matches!(
+            anonymize(&[0x00; 128], AnonymizeOptions::default()),
+            Err(EdidError {
+                kind: EdidErrorKind::NoHeader,
+                ..
+            })
+        ));
+    }
+}
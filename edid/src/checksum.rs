@@ -0,0 +1,135 @@
+//! Checks and repairs the checksum at the end of each EDID block.
+//!
+//! Every 128-byte block ends with a byte that makes the whole block sum to
+//! zero. Parsing doesn't require that by default, since plenty of real
+//! displays get it wrong, so this module tells you which blocks did:
+//!
+//! ```edition2021
+//! use liboptic_edid::checksum;
+//!
+//! let mut data = std::fs::read("tests/assets/dell_s2417dg.raw.input")?;
+//! assert!(checksum::statuses(&data).all(|s| s.is_valid()));
+//!
+//! // break the base block, then fix it again
+//! data[0x10] ^= 0xFF;
+//! assert!(!checksum::status(&data[..128]).is_valid());
+//!
+//! checksum::fix_checksums(&mut data);
+//! assert!(checksum::statuses(&data).all(|s| s.is_valid()));
+//! #
+//! # Ok::<(), Box<dyn core::error::Error>>(())
+//! ```
+
+use crate::parser::header::BLOCK_LEN;
+
+/// The most blocks an EDID can have: the base block, then up to 255
+/// extension blocks.
+pub const MAX_BLOCKS: usize = 256;
+
+/// A block's checksum byte, next to the one it should have.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChecksumStatus {
+    /// The checksum byte at the end of the block.
+    pub stored: u8,
+
+    /// The byte that would make the block sum to zero.
+    pub expected: u8,
+}
+
+impl ChecksumStatus {
+    /// Whether the stored checksum is correct.
+    pub fn is_valid(&self) -> bool {
+        self.stored == self.expected
+    }
+}
+
+/// Finds the checksum status of one block.
+///
+/// Only the first 128 bytes are used.
+///
+/// # Panics
+///
+/// If `block` is shorter than 128 bytes.
+pub fn status(block: &[u8]) -> ChecksumStatus {
+    let block = &block[..BLOCK_LEN];
+    ChecksumStatus {
+        stored: block[BLOCK_LEN - 1],
+        expected: expected(block),
+    }
+}
+
+/// Finds the checksum status of the base block and each extension block, in
+/// order.
+///
+/// A trailing partial block is skipped.
+pub fn statuses(edid: &[u8]) -> impl Iterator<Item = ChecksumStatus> + '_ {
+    edid.chunks_exact(BLOCK_LEN).map(status)
+}
+
+/// Recalculates the checksum of every block in the given EDID.
+///
+/// Each block's last byte is set so that the whole block sums to zero. A
+/// trailing partial block is left alone.
+pub fn fix_checksums(edid: &mut [u8]) {
+    for block in edid.chunks_exact_mut(BLOCK_LEN) {
+        block[BLOCK_LEN - 1] = expected(block);
+    }
+}
+
+/// The checksum byte that makes `block` sum to zero.
+fn expected(block: &[u8]) -> u8 {
+    block[..BLOCK_LEN - 1]
+        .iter()
+        .fold(0_u8, |sum, b| sum.wrapping_add(*b))
+        .wrapping_neg()
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+    use alloc::vec::Vec;
+
+    use crate::prelude::internal::*;
+
+    use super::{fix_checksums, statuses, ChecksumStatus};
+
+    #[test]
+    fn dell_statuses() {
+        logger();
+        let input = raw_edid_by_filename("dell_s2417dg.raw.input");
+
+        let got: Vec<_> = statuses(&input).collect();
+        assert_eq!(got.len(), 2);
+        assert_eq!(
+            got[0],
+            ChecksumStatus {
+                stored: 0x51,
+                expected: 0x51
+            }
+        );
+        assert!(got[1].is_valid());
+    }
+
+    #[test]
+    fn fix_in_place() {
+        logger();
+        let input = raw_edid_by_filename("dell_s2417dg.raw.input");
+
+        // break the extension block
+        let mut broken = input.clone();
+        broken[128 + 0x20] = broken[128 + 0x20].wrapping_add(1);
+        let got: Vec<_> = statuses(&broken).collect();
+        assert!(got[0].is_valid());
+        assert_eq!(got[1].stored, input[255]);
+        assert_eq!(got[1].expected, input[255].wrapping_sub(1));
+
+        // fixing it only touches the checksum byte
+        let before = broken.clone();
+        fix_checksums(&mut broken);
+        assert!(statuses(&broken).all(|s| s.is_valid()));
+        assert_eq!(broken[..255], before[..255]);
+        assert_eq!(broken[255], input[255].wrapping_sub(1));
+    }
+}
@@ -0,0 +1,598 @@
+//! Color math on the display's chromaticities.
+//!
+//! The EDID gives CIE 1931 xy coordinates for the display's red, green, and
+//! blue primaries, plus its white point. From those, this module builds the
+//! matrices a color-managed pipeline needs, finds the white point's color
+//! temperature, and compares the display's gamut against common standards.
+//!
+//! ```edition2021
+//! use liboptic_edid::{colorimetry::{Chromaticity, Standard}, Edid};
+//!
+//! let data = std::fs::read("tests/assets/dell_s2417dg.raw.input")?;
+//! let colors = Edid::new(&data)?.color_characteristics;
+//!
+//! let srgb = colors.coverage(Standard::Srgb, Chromaticity::Xy);
+//! assert!(srgb.to_string().starts_with("99."));
+//! #
+//! # Ok::<(), Box<dyn core::error::Error>>(())
+//! ```
+
+use num_traits::{ToPrimitive as _, Zero as _};
+
+use crate::prelude::internal::*;
+use crate::structures::color::{ColorCharacteristics, ColorCoordinate};
+
+/// A 3x3 matrix, in row-major order.
+pub type Matrix3 = [[Decimal; 3]; 3];
+
+/// The color spaces that standards are usually compared in.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Chromaticity {
+    /// CIE 1931 xy, which is what the EDID uses.
+    Xy,
+
+    /// CIE 1976 u'v'. This is closer to how we see color differences, so
+    /// its coverage numbers are usually more meaningful.
+    UvPrime,
+}
+
+/// Well-known RGB color standards.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Standard {
+    /// sRGB, which shares its primaries with BT.709.
+    Srgb,
+
+    /// DCI-P3, with the DCI white point.
+    DciP3,
+
+    /// Adobe RGB (1998).
+    AdobeRgb,
+
+    /// BT.2020, for UHD video.
+    Bt2020,
+}
+
+impl Standard {
+    /// The primaries and white point of this standard.
+    pub fn primaries(&self) -> ColorCharacteristics {
+        // in ten-thousandths
+        let c = |x, y| ColorCoordinate::new(Ratio::new(x, 10_000), Ratio::new(y, 10_000));
+        let d65 = c(3127, 3290);
+
+        match self {
+            Self::Srgb => ColorCharacteristics {
+                red: c(6400, 3300),
+                green: c(3000, 6000),
+                blue: c(1500, 600),
+                white_point: d65,
+            },
+            Self::DciP3 => ColorCharacteristics {
+                red: c(6800, 3200),
+                green: c(2650, 6900),
+                blue: c(1500, 600),
+                white_point: c(3140, 3510),
+            },
+            Self::AdobeRgb => ColorCharacteristics {
+                red: c(6400, 3300),
+                green: c(2100, 7100),
+                blue: c(1500, 600),
+                white_point: d65,
+            },
+            Self::Bt2020 => ColorCharacteristics {
+                red: c(7080, 2920),
+                green: c(1700, 7970),
+                blue: c(1310, 460),
+                white_point: d65,
+            },
+        }
+    }
+}
+
+/// The correlated color temperature of a white point.
+#[repr(C)]
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ColorTemperature {
+    /// The temperature of the closest point on the Planckian locus, in
+    /// kelvin.
+    pub cct_k: u16,
+
+    /// How far the white point is from the Planckian locus, in CIE 1960 uv.
+    /// Positive values are above the locus (greener), and negative values
+    /// are below it (pinker).
+    pub duv: Decimal,
+}
+
+/// Why the primaries in an EDID look like they weren't measured.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Placeholder {
+    /// A coordinate is zero.
+    Zeroed,
+
+    /// The primaries don't form a triangle, like when they're all the same.
+    Degenerate,
+
+    /// The white point isn't inside the primaries' triangle.
+    WhiteOutsideGamut,
+
+    /// The primaries are exactly sRGB's. Some displays really are sRGB, but
+    /// this usually means the manufacturer copied the values from the
+    /// standard instead of measuring.
+    CopiedFromSrgb,
+}
+
+impl ColorCoordinate {
+    /// Converts this to CIE 1976 u'v'.
+    pub fn to_uv_prime(&self) -> (Decimal, Decimal) {
+        let ((x, y), d) = (self.xy(), self.denominator());
+        (dec!(4) * x / d, dec!(9) * y / d)
+    }
+
+    /// Converts this to CIE XYZ, with a luminance (Y) of 1.
+    ///
+    /// Returns `None` if `y` is zero.
+    pub fn to_xyz(&self) -> Option<[Decimal; 3]> {
+        let (x, y) = self.xy();
+        if y.is_zero() {
+            return None;
+        }
+
+        Some([x / y, Decimal::ONE, (Decimal::ONE - x - y) / y])
+    }
+
+    /// Finds the correlated color temperature and Duv of this coordinate.
+    ///
+    /// This searches the Planckian locus (using Krystek's approximation)
+    /// for its closest point. Returns `None` outside of 1000 K to 15000 K,
+    /// or if the coordinate is too far from the locus (a Duv over 0.05) for
+    /// a temperature to mean anything.
+    #[cfg_attr(feature = "tracing", tracing::instrument)]
+    pub fn color_temperature(&self) -> Option<ColorTemperature> {
+        let (u, v) = self.to_uv_1960();
+        let distance = |t: Decimal| {
+            let (pu, pv) = planckian_uv(t);
+            (u - pu) * (u - pu) + (v - pv) * (v - pv)
+        };
+
+        // find roughly where it is, then narrow it down
+        let mut best = MIN_CCT;
+        let mut t = MIN_CCT;
+        while t <= MAX_CCT {
+            if distance(t) < distance(best) {
+                best = t;
+            }
+            t += dec!(100);
+        }
+
+        let (mut lo, mut hi) = (
+            (best - dec!(100)).max(MIN_CCT),
+            (best + dec!(100)).min(MAX_CCT),
+        );
+        for _ in 0..40 {
+            let third = (hi - lo) / dec!(3);
+            if distance(lo + third) < distance(hi - third) {
+                hi -= third;
+            } else {
+                lo += third;
+            }
+        }
+        let cct = ((lo + hi) / dec!(2)).round();
+
+        // the sign says which side of the locus we're on
+        let (pu, pv) = planckian_uv(cct);
+        let mut duv = sqrt(distance(cct));
+        if v < pv {
+            duv = -duv;
+        }
+        tracing::debug!("Closest point on the locus: ({pu}, {pv}) at {cct} K");
+
+        if cct <= MIN_CCT || cct >= MAX_CCT || duv.abs() > dec!(0.05) {
+            tracing::warn!("This white point isn't near the Planckian locus.");
+            return None;
+        }
+
+        Some(ColorTemperature {
+            cct_k: cct.to_u16()?,
+            duv: duv.round_dp(4),
+        })
+    }
+
+    /// CIE 1960 uv, which Duv is measured in.
+    fn to_uv_1960(&self) -> (Decimal, Decimal) {
+        let ((x, y), d) = (self.xy(), self.denominator());
+        (dec!(4) * x / d, dec!(6) * y / d)
+    }
+
+    /// The shared denominator of the uv conversions.
+    fn denominator(&self) -> Decimal {
+        let (x, y) = self.xy();
+        dec!(-2) * x + dec!(12) * y + dec!(3)
+    }
+
+    /// The coordinate as decimals, for the math above.
+    fn xy(&self) -> (Decimal, Decimal) {
+        (to_decimal(&self.x), to_decimal(&self.y))
+    }
+}
+
+impl ColorCharacteristics {
+    /// The matrix that converts linear RGB on this display into CIE XYZ.
+    ///
+    /// The white point maps to a luminance (Y) of 1. Returns `None` if the
+    /// primaries can't make a matrix, like when they're all zero.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn rgb_to_xyz(&self) -> Option<Matrix3> {
+        let r = self.red.to_xyz()?;
+        let g = self.green.to_xyz()?;
+        let b = self.blue.to_xyz()?;
+        let w = self.white_point.to_xyz()?;
+
+        // scale each primary so that they add up to the white point
+        let primaries = [[r[0], g[0], b[0]], [r[1], g[1], b[1]], [r[2], g[2], b[2]]];
+        let s = multiply_vector(&invert(&primaries)?, &w);
+
+        Some(core::array::from_fn(|row| {
+            core::array::from_fn(|col| primaries[row][col] * s[col])
+        }))
+    }
+
+    /// The matrix that converts CIE XYZ into linear RGB on this display.
+    pub fn xyz_to_rgb(&self) -> Option<Matrix3> {
+        invert(&self.rgb_to_xyz()?)
+    }
+
+    /// The area of the primaries' triangle in the given chromaticity
+    /// diagram.
+    pub fn gamut_area(&self, space: Chromaticity) -> Decimal {
+        area(&self.triangle(space))
+    }
+
+    /// How much of a standard's gamut this display covers, as a percentage.
+    ///
+    /// This is the area where the two triangles overlap, over the area of
+    /// the standard's triangle. Unlike a ratio of areas, this can't go over
+    /// 100%. Rounded to one decimal place.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip(self)))]
+    pub fn coverage(&self, standard: Standard, space: Chromaticity) -> Decimal {
+        let target = standard.primaries().triangle(space);
+        let overlap = clip(&self.triangle(space), &target);
+
+        let target_area = area(&target);
+        if target_area.is_zero() {
+            return Decimal::ZERO;
+        }
+        (area(&overlap) / target_area * dec!(100)).round_dp(1)
+    }
+
+    /// Checks whether the primaries look like placeholders instead of
+    /// measurements.
+    pub fn placeholder(&self) -> Option<Placeholder> {
+        let points = [&self.red, &self.green, &self.blue, &self.white_point];
+        if points.iter().any(|p| p.x.is_zero() || p.y.is_zero()) {
+            return Some(Placeholder::Zeroed);
+        }
+
+        let triangle = self.triangle(Chromaticity::Xy);
+        if area(&triangle) < dec!(0.001) {
+            return Some(Placeholder::Degenerate);
+        }
+
+        if !inside(&triangle, self.white_point.xy()) {
+            return Some(Placeholder::WhiteOutsideGamut);
+        }
+
+        // edids store these in 10 bits, so sRGB gets rounded a bit
+        let srgb = Standard::Srgb.primaries();
+        let copied = [
+            (&self.red, &srgb.red),
+            (&self.green, &srgb.green),
+            (&self.blue, &srgb.blue),
+        ]
+        .iter()
+        .all(|(a, b)| {
+            let (a, b) = (a.xy(), b.xy());
+            (a.0 - b.0).abs() < dec!(0.001) && (a.1 - b.1).abs() < dec!(0.001)
+        });
+        if copied {
+            return Some(Placeholder::CopiedFromSrgb);
+        }
+
+        None
+    }
+
+    /// The red, green, and blue points in the given diagram.
+    fn triangle(&self, space: Chromaticity) -> [(Decimal, Decimal); 3] {
+        [&self.red, &self.green, &self.blue].map(|c| match space {
+            Chromaticity::Xy => c.xy(),
+            Chromaticity::UvPrime => c.to_uv_prime(),
+        })
+    }
+}
+
+/// Turns one of the EDID's exact ratios into a decimal.
+pub(crate) fn to_decimal<T: Clone + Into<i64>>(ratio: &Ratio<T>) -> Decimal {
+    Decimal::from(ratio.numer().clone().into()) / Decimal::from(ratio.denom().clone().into())
+}
+
+/// The range of temperatures that Krystek's approximation is good for.
+const MIN_CCT: Decimal = dec!(1000);
+const MAX_CCT: Decimal = dec!(15000);
+
+/// The CIE 1960 uv of a black body at temperature `t`, in kelvin.
+///
+/// This is Krystek's rational approximation (1985).
+fn planckian_uv(t: Decimal) -> (Decimal, Decimal) {
+    let t2 = t * t;
+    let u = (dec!(0.860117757) + dec!(1.54118254e-4) * t + dec!(1.28641212e-7) * t2)
+        / (Decimal::ONE + dec!(8.42420235e-4) * t + dec!(7.08145163e-7) * t2);
+    let v = (dec!(0.317398726) + dec!(4.22806245e-5) * t + dec!(4.20481691e-8) * t2)
+        / (Decimal::ONE - dec!(2.89741816e-5) * t + dec!(1.61456053e-7) * t2);
+    (u, v)
+}
+
+/// A square root, by Newton's method. `rust_decimal` only has one behind its
+/// `maths` feature.
+fn sqrt(n: Decimal) -> Decimal {
+    if n <= Decimal::ZERO {
+        return Decimal::ZERO;
+    }
+
+    let mut x = if n > Decimal::ONE { n } else { Decimal::ONE };
+    for _ in 0..64 {
+        let next = (x + n / x) / dec!(2);
+        if next == x {
+            break;
+        }
+        x = next;
+    }
+    x
+}
+
+/// Inverts a 3x3 matrix, if it can be inverted.
+pub(crate) fn invert(m: &Matrix3) -> Option<Matrix3> {
+    let cofactor = |r: usize, c: usize| {
+        let (r1, r2) = ((r + 1) % 3, (r + 2) % 3);
+        let (c1, c2) = ((c + 1) % 3, (c + 2) % 3);
+        m[r1][c1] * m[r2][c2] - m[r1][c2] * m[r2][c1]
+    };
+
+    let det = m[0][0] * cofactor(0, 0) + m[0][1] * cofactor(0, 1) + m[0][2] * cofactor(0, 2);
+    if det.is_zero() {
+        tracing::warn!("This matrix can't be inverted.");
+        return None;
+    }
+
+    // the inverse is the transposed cofactors over the determinant
+    Some(core::array::from_fn(|row| {
+        core::array::from_fn(|col| cofactor(col, row) / det)
+    }))
+}
+
+#[cfg(feature = "alloc")] // only icc profiles need this
+pub(crate) fn multiply(a: &Matrix3, b: &Matrix3) -> Matrix3 {
+    core::array::from_fn(|row| {
+        core::array::from_fn(|col| (0..3).map(|i| a[row][i] * b[i][col]).sum())
+    })
+}
+
+pub(crate) fn multiply_vector(m: &Matrix3, v: &[Decimal; 3]) -> [Decimal; 3] {
+    core::array::from_fn(|row| (0..3).map(|col| m[row][col] * v[col]).sum())
+}
+
+/// The area of a polygon, with the shoelace formula.
+fn area(points: &[(Decimal, Decimal)]) -> Decimal {
+    let n = points.len();
+    let twice: Decimal = (0..n)
+        .map(|i| {
+            let (a, b) = (points[i], points[(i + 1) % n]);
+            a.0 * b.1 - b.0 * a.1
+        })
+        .sum();
+    (twice / dec!(2)).abs()
+}
+
+/// Which side of the line from `a` to `b` that `p` is on. Positive is to the
+/// left.
+fn side(a: (Decimal, Decimal), b: (Decimal, Decimal), p: (Decimal, Decimal)) -> Decimal {
+    (b.0 - a.0) * (p.1 - a.1) - (b.1 - a.1) * (p.0 - a.0)
+}
+
+/// Puts a triangle's points in counter-clockwise order.
+fn counter_clockwise(t: &[(Decimal, Decimal); 3]) -> [(Decimal, Decimal); 3] {
+    if side(t[0], t[1], t[2]) < Decimal::ZERO {
+        [t[0], t[2], t[1]]
+    } else {
+        *t
+    }
+}
+
+/// Whether `p` is inside (or on the edge of) a triangle.
+fn inside(t: &[(Decimal, Decimal); 3], p: (Decimal, Decimal)) -> bool {
+    let t = counter_clockwise(t);
+    (0..3).all(|i| side(t[i], t[(i + 1) % 3], p) >= Decimal::ZERO)
+}
+
+/// Clips `subject` to the inside of `clip` (Sutherland-Hodgman), giving
+/// their overlap.
+fn clip(
+    subject: &[(Decimal, Decimal); 3],
+    clip: &[(Decimal, Decimal); 3],
+) -> arrayvec::ArrayVec<(Decimal, Decimal), 9> {
+    let clip = counter_clockwise(clip);
+
+    // clipping a triangle by three edges gives at most six points, and
+    // we briefly need room for a few more while working
+    let mut output: arrayvec::ArrayVec<(Decimal, Decimal), 9> =
+        counter_clockwise(subject).into_iter().collect();
+
+    for i in 0..3 {
+        let (a, b) = (clip[i], clip[(i + 1) % 3]);
+        let input = core::mem::take(&mut output);
+        let n = input.len();
+
+        for j in 0..n {
+            let (current, previous) = (input[j], input[(j + n - 1) % n]);
+            let (cur_in, prev_in) = (
+                side(a, b, current) >= Decimal::ZERO,
+                side(a, b, previous) >= Decimal::ZERO,
+            );
+
+            if cur_in != prev_in {
+                // where the edge crosses the clipping line
+                let (sp, sc) = (side(a, b, previous), side(a, b, current));
+                let t = sp / (sp - sc);
+                output.push((
+                    previous.0 + (current.0 - previous.0) * t,
+                    previous.1 + (current.1 - previous.1) * t,
+                ));
+            }
+            if cur_in {
+                output.push(current);
+            }
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// makes a coordinate from decimals, like `c(dec!(0.3127), dec!(0.3290))`
+    fn c(x: Decimal, y: Decimal) -> ColorCoordinate {
+        let ratio = |d: Decimal| Ratio::new(d.mantissa() as u32, 10_u32.pow(d.scale()));
+        ColorCoordinate::new(ratio(x), ratio(y))
+    }
+
+    fn rounded(m: Matrix3) -> Matrix3 {
+        m.map(|row| row.map(|d| d.round_dp(4)))
+    }
+
+    /// the well-known srgb matrices
+    #[test]
+    fn srgb_matrices() {
+        let srgb = Standard::Srgb.primaries();
+
+        assert_eq!(
+            rounded(srgb.rgb_to_xyz().unwrap()),
+            [
+                [dec!(0.4124), dec!(0.3576), dec!(0.1805)],
+                [dec!(0.2126), dec!(0.7152), dec!(0.0722)],
+                [dec!(0.0193), dec!(0.1192), dec!(0.9505)],
+            ]
+        );
+        assert_eq!(
+            rounded(srgb.xyz_to_rgb().unwrap()),
+            [
+                [dec!(3.2410), dec!(-1.5374), dec!(-0.4986)],
+                [dec!(-0.9692), dec!(1.8760), dec!(0.0416)],
+                [dec!(0.0556), dec!(-0.2040), dec!(1.0570)],
+            ]
+        );
+    }
+
+    #[test]
+    fn d65_temperature() {
+        let d65 = c(dec!(0.3127), dec!(0.3290));
+        let temp = d65.color_temperature().unwrap();
+
+        assert!((6500..=6510).contains(&temp.cct_k), "{}", temp.cct_k);
+        assert!(
+            (temp.duv - dec!(0.0032)).abs() <= dec!(0.0001),
+            "{}",
+            temp.duv
+        );
+
+        // illuminant a is right on the locus
+        let a = c(dec!(0.44757), dec!(0.40745));
+        let temp = a.color_temperature().unwrap();
+        assert!((2850..=2860).contains(&temp.cct_k), "{}", temp.cct_k);
+        assert!(temp.duv.abs() < dec!(0.0005));
+
+        // and pure green isn't anywhere near it
+        let green = c(dec!(0.3), dec!(0.6));
+        assert_eq!(green.color_temperature(), None);
+    }
+
+    #[test]
+    fn coverage() {
+        let srgb = Standard::Srgb.primaries();
+        let p3 = Standard::DciP3.primaries();
+
+        for space in [Chromaticity::Xy, Chromaticity::UvPrime] {
+            assert_eq!(srgb.coverage(Standard::Srgb, space), dec!(100));
+            assert_eq!(p3.coverage(Standard::Srgb, space), dec!(100));
+        }
+
+        // srgb covers about three quarters of p3 in xy, and bt.2020 covers
+        // all of adobe rgb
+        assert_eq!(srgb.coverage(Standard::DciP3, Chromaticity::Xy), dec!(73.7));
+        assert_eq!(
+            Standard::Bt2020
+                .primaries()
+                .coverage(Standard::AdobeRgb, Chromaticity::UvPrime),
+            dec!(100)
+        );
+        assert!(
+            srgb.gamut_area(Chromaticity::Xy)
+                < Standard::AdobeRgb.primaries().gamut_area(Chromaticity::Xy)
+        );
+    }
+
+    #[test]
+    fn dell_s2417dg_colors() {
+        logger();
+        let input = raw_edid_by_filename("dell_s2417dg.raw.input");
+        let colors = Edid::new(&input).unwrap().color_characteristics;
+
+        // it just reports srgb's primaries
+        let srgb = colors.coverage(Standard::Srgb, Chromaticity::UvPrime);
+        assert!(srgb > dec!(99), "{srgb}");
+        assert_eq!(colors.placeholder(), Some(Placeholder::CopiedFromSrgb));
+
+        // the matrices should undo each other
+        let forward = colors.rgb_to_xyz().unwrap();
+        let back = colors.xyz_to_rgb().unwrap();
+        let white = multiply_vector(&back, &multiply_vector(&forward, &[Decimal::ONE; 3]));
+        assert_eq!(white.map(|d| d.round_dp(6)), [Decimal::ONE; 3]);
+    }
+
+    #[test]
+    fn placeholders() {
+        let zeroed = ColorCharacteristics {
+            red: c(dec!(0), dec!(0)),
+            green: c(dec!(0), dec!(0)),
+            blue: c(dec!(0), dec!(0)),
+            white_point: c(dec!(0), dec!(0)),
+        };
+        assert_eq!(zeroed.placeholder(), Some(Placeholder::Zeroed));
+        assert_eq!(zeroed.rgb_to_xyz(), None);
+
+        let same = ColorCharacteristics {
+            red: c(dec!(0.3), dec!(0.3)),
+            green: c(dec!(0.3), dec!(0.3)),
+            blue: c(dec!(0.3), dec!(0.3)),
+            white_point: c(dec!(0.3), dec!(0.3)),
+        };
+        assert_eq!(same.placeholder(), Some(Placeholder::Degenerate));
+
+        let mut white_outside = Standard::AdobeRgb.primaries();
+        white_outside.white_point = c(dec!(0.1), dec!(0.8));
+        assert_eq!(
+            white_outside.placeholder(),
+            Some(Placeholder::WhiteOutsideGamut)
+        );
+
+        assert_eq!(
+            Standard::Srgb.primaries().placeholder(),
+            Some(Placeholder::CopiedFromSrgb)
+        );
+    }
+}
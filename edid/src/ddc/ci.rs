@@ -0,0 +1,716 @@
+//! Controls a display with DDC/CI, using the VESA Monitor Control Command
+//! Set (MCCS).
+//!
+//! Displays expose their settings as Virtual Control Panel (VCP) features,
+//! each with a one-byte code. For example, brightness is `0x10`. Most of
+//! them are just a number between zero and some maximum that the display
+//! picks.
+//!
+//! ```edition2021,ignore
+//! use liboptic_edid::ddc::{self, ci};
+//!
+//! let mut bus = ddc::I2cDevice::open(5)?;
+//!
+//! // turn the brightness up to 75%
+//! let brightness = ci::get_vcp(&mut bus, ci::vcp::BRIGHTNESS)?;
+//! ci::set_vcp(&mut bus, ci::vcp::BRIGHTNESS, brightness.maximum * 3 / 4)?;
+//! ```
+
+#[cfg(any(feature = "alloc", test))]
+extern crate alloc;
+#[cfg(feature = "alloc")]
+use alloc::{
+    string::{String, ToString as _},
+    vec::Vec,
+};
+use arrayvec::ArrayVec;
+
+use super::DdcTransport;
+use crate::error::DdcError;
+use crate::prelude::internal::tracing;
+
+/// The I²C address of the display's DDC/CI interface.
+pub const DDC_CI_ADDRESS: u8 = 0x37;
+
+/// The address that we (the host) send messages from.
+const HOST_ADDRESS: u8 = 0x51;
+
+/// The address the display sends replies from. This is `DDC_CI_ADDRESS`,
+/// shifted for a write.
+const DISPLAY_ADDRESS: u8 = 0x6E;
+
+/// Replies are checksummed as if they were sent to this address.
+const REPLY_CHECKSUM_SEED: u8 = 0x50;
+
+// opcodes
+const GET_VCP: u8 = 0x01;
+const GET_VCP_REPLY: u8 = 0x02;
+const SET_VCP: u8 = 0x03;
+#[cfg(any(feature = "alloc", test))]
+const CAPABILITIES: u8 = 0xF3;
+#[cfg(any(feature = "alloc", test))]
+const CAPABILITIES_REPLY: u8 = 0xE3;
+
+// how long displays need to process each request, in milliseconds
+const GET_VCP_DELAY: u32 = 40;
+const SET_VCP_DELAY: u32 = 50;
+#[cfg(feature = "alloc")]
+const CAPABILITIES_DELAY: u32 = 50;
+
+#[cfg(feature = "alloc")]
+/// Capabilities strings are usually a few hundred bytes. Anything past this
+/// is probably a display stuck repeating itself.
+const MAX_CAPABILITIES_LEN: usize = 4096;
+
+/// Some common VCP feature codes.
+pub mod vcp {
+    /// The luminance of the image.
+    pub const BRIGHTNESS: u8 = 0x10;
+
+    /// The contrast of the image.
+    pub const CONTRAST: u8 = 0x12;
+
+    /// The active input, like DisplayPort 1 (`0x0F`) or HDMI 1 (`0x11`).
+    pub const INPUT_SOURCE: u8 = 0x60;
+
+    /// The display's power mode. `0x01` is on, and `0x04` and `0x05` are
+    /// the two kinds of off.
+    pub const POWER_MODE: u8 = 0xD6;
+}
+
+/// The state of a VCP feature.
+#[repr(C)]
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VcpValue {
+    /// How the feature behaves when set.
+    pub kind: VcpKind,
+
+    /// The largest value the feature accepts.
+    pub maximum: u16,
+
+    /// The feature's value right now.
+    pub current: u16,
+}
+
+/// How a VCP feature behaves when set.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VcpKind {
+    /// The value stays until it's set again, like brightness.
+    SetParameter,
+
+    /// Setting it does something once, like a factory reset.
+    Momentary,
+}
+
+/// Reads the VCP feature with the given `code`.
+#[cfg_attr(feature = "tracing", tracing::instrument(skip(transport)))]
+pub fn get_vcp<T: DdcTransport>(
+    transport: &mut T,
+    code: u8,
+) -> Result<VcpValue, DdcError<T::Error>> {
+    send(transport, &[GET_VCP, code])?;
+    transport.wait(GET_VCP_DELAY);
+
+    let mut buffer = [0x00; 11];
+    let reply = receive(transport, &mut buffer)?;
+
+    match *reply {
+        [GET_VCP_REPLY, 0x00, reply_code, kind, max_hi, max_lo, cur_hi, cur_lo]
+            if reply_code == code =>
+        {
+            Ok(VcpValue {
+                kind: if kind == 0x01 {
+                    VcpKind::Momentary
+                } else {
+                    VcpKind::SetParameter
+                },
+                maximum: u16::from_be_bytes([max_hi, max_lo]),
+                current: u16::from_be_bytes([cur_hi, cur_lo]),
+            })
+        }
+        [GET_VCP_REPLY, 0x01, ..] => {
+            tracing::warn!("the display doesn't support vcp code {code:#04x}.");
+            Err(DdcError::CiUnsupportedVcp { code })
+        }
+        _ => Err(malformed(reply)),
+    }
+}
+
+/// Sets the VCP feature with the given `code` to `value`.
+///
+/// Displays don't reply to this, so you might want to `get_vcp` afterwards
+/// to check that it worked.
+#[cfg_attr(feature = "tracing", tracing::instrument(skip(transport)))]
+pub fn set_vcp<T: DdcTransport>(
+    transport: &mut T,
+    code: u8,
+    value: u16,
+) -> Result<(), DdcError<T::Error>> {
+    let [hi, lo] = value.to_be_bytes();
+    send(transport, &[SET_VCP, code, hi, lo])?;
+    transport.wait(SET_VCP_DELAY);
+    Ok(())
+}
+
+#[cfg(feature = "alloc")]
+/// Fetches the display's capabilities string.
+///
+/// Use `Capabilities::parse` to make sense of it.
+#[cfg_attr(feature = "tracing", tracing::instrument(skip(transport)))]
+pub fn capabilities_string<T: DdcTransport>(
+    transport: &mut T,
+) -> Result<String, DdcError<T::Error>> {
+    let mut caps = Vec::new();
+
+    // the display sends the string a fragment at a time, until one's empty
+    loop {
+        if caps.len() > MAX_CAPABILITIES_LEN {
+            tracing::error!("the capabilities string never ended.");
+            return Err(DdcError::CiCapabilitiesTooLong);
+        }
+
+        let offset = caps.len() as u16;
+        let [hi, lo] = offset.to_be_bytes();
+        send(transport, &[CAPABILITIES, hi, lo])?;
+        transport.wait(CAPABILITIES_DELAY);
+
+        let mut buffer = [0x00; 38];
+        let reply = receive(transport, &mut buffer)?;
+        let [CAPABILITIES_REPLY, reply_hi, reply_lo, ref data @ ..] = *reply else {
+            return Err(malformed(reply));
+        };
+        if u16::from_be_bytes([reply_hi, reply_lo]) != offset {
+            tracing::error!("the display replied with the wrong fragment.");
+            return Err(malformed(reply));
+        }
+
+        if data.is_empty() {
+            break;
+        }
+        caps.extend_from_slice(data);
+    }
+
+    // some displays null-terminate it
+    let end = caps.iter().position(|b| *b == 0x00).unwrap_or(caps.len());
+    Ok(String::from_utf8_lossy(&caps[..end]).into_owned())
+}
+
+/// Sends a message with the given `payload` to the display.
+fn send<T: DdcTransport>(transport: &mut T, payload: &[u8]) -> Result<(), DdcError<T::Error>> {
+    let mut packet = ArrayVec::<u8, 8>::new();
+    packet.push(HOST_ADDRESS);
+    packet.push(0x80 | payload.len() as u8);
+    packet.extend(payload.iter().copied());
+
+    // the checksum includes the display's address, even though the
+    // transport sends that for us
+    let checksum = packet.iter().fold(DISPLAY_ADDRESS, |sum, b| sum ^ b);
+    packet.push(checksum);
+
+    transport
+        .write(DDC_CI_ADDRESS, &packet)
+        .map_err(DdcError::Transport)
+}
+
+/// Reads a reply from the display into `buffer`, returning its payload.
+fn receive<'buf, T: DdcTransport>(
+    transport: &mut T,
+    buffer: &'buf mut [u8],
+) -> Result<&'buf [u8], DdcError<T::Error>> {
+    transport
+        .read(DDC_CI_ADDRESS, buffer)
+        .map_err(DdcError::Transport)?;
+
+    let len = match *buffer {
+        [DISPLAY_ADDRESS, len, ..] if len & 0x80 != 0 => usize::from(len & 0x7F),
+        _ => return Err(malformed(buffer)),
+    };
+
+    // we need the two header bytes, the payload, and the checksum
+    let end = 2 + len;
+    if buffer.len() <= end {
+        tracing::error!("the reply says it's longer than we can hold. (len: {len})");
+        return Err(malformed(buffer));
+    }
+
+    let checksum = buffer[..end]
+        .iter()
+        .fold(REPLY_CHECKSUM_SEED, |sum, b| sum ^ b);
+    if checksum != buffer[end] {
+        tracing::error!(
+            "the reply had a bad checksum. (got: {:#04x}, expected: {checksum:#04x})",
+            buffer[end]
+        );
+        return Err(DdcError::CiBadChecksum);
+    }
+
+    // an empty reply means the display had nothing to say
+    if len == 0 {
+        return Err(DdcError::CiNullReply);
+    }
+
+    Ok(&buffer[2..end])
+}
+
+/// Makes an error holding the start of a `reply`.
+fn malformed<E: core::fmt::Debug + core::fmt::Display>(reply: &[u8]) -> DdcError<E> {
+    let mut start = [0x00; 4];
+    let len = reply.len().min(start.len());
+    start[..len].copy_from_slice(&reply[..len]);
+
+    tracing::error!("malformed ddc/ci reply. (start: {start:x?})");
+    DdcError::CiMalformedReply(start)
+}
+
+#[cfg(feature = "alloc")]
+/// A display's parsed capabilities string.
+///
+/// These look like `(prot(monitor)type(lcd)model(S2417DG)cmds(01 02 03)
+/// vcp(10 12 60(0F 11))mccs_ver(2.1))`.
+#[repr(C)]
+#[derive(Clone, Debug, Default, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Capabilities {
+    /// The protocol class. This is almost always `monitor`.
+    pub protocol: Option<String>,
+
+    /// The display technology, like `lcd` or `crt`.
+    pub display_type: Option<String>,
+
+    /// The display's model name.
+    pub model: Option<String>,
+
+    /// The DDC/CI opcodes the display supports.
+    pub commands: Vec<u8>,
+
+    /// The VCP features the display supports.
+    pub vcp_features: Vec<VcpFeature>,
+
+    /// The version of MCCS the display follows, like `2.1`.
+    pub mccs_version: Option<String>,
+
+    /// Any other entries, as `(keyword, value)` pairs.
+    pub other: Vec<(String, String)>,
+}
+
+#[cfg(feature = "alloc")]
+/// A VCP feature listed in a capabilities string.
+#[repr(C)]
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VcpFeature {
+    /// The feature's code.
+    pub code: u8,
+
+    /// The values the feature accepts, for features that take a list of
+    /// them, like the input source. Otherwise, this is empty.
+    pub values: Vec<u8>,
+}
+
+#[cfg(feature = "alloc")]
+impl Capabilities {
+    /// Parses a capabilities string.
+    ///
+    /// Returns `None` if its parentheses aren't balanced, or if a command or
+    /// VCP code isn't hex.
+    #[cfg_attr(feature = "tracing", tracing::instrument)]
+    pub fn parse(caps: &str) -> Option<Self> {
+        let mut parsed = Self::default();
+
+        for (keyword, value) in entries(caps)? {
+            match keyword {
+                "prot" => parsed.protocol = Some(value.to_string()),
+                "type" => parsed.display_type = Some(value.to_string()),
+                "model" => parsed.model = Some(value.to_string()),
+                "cmds" => parsed.commands = hex_codes(value)?,
+                "vcp" => parsed.vcp_features = vcp_features(value)?,
+                "mccs_ver" => parsed.mccs_version = Some(value.to_string()),
+                _ => parsed.other.push((keyword.to_string(), value.to_string())),
+            }
+        }
+
+        Some(parsed)
+    }
+
+    /// Checks if the display listed the VCP feature with the given `code`.
+    pub fn supports(&self, code: u8) -> bool {
+        self.vcp_features.iter().any(|feature| feature.code == code)
+    }
+}
+
+#[cfg(feature = "alloc")]
+/// Splits the string into its `keyword(value)` entries.
+fn entries(caps: &str) -> Option<Vec<(&str, &str)>> {
+    let mut caps = caps.trim().trim_end_matches('\0');
+
+    // the whole thing is usually (but not always!) wrapped in parentheses
+    if caps.starts_with('(') && closing_paren(caps) == Some(caps.len() - 1) {
+        caps = &caps[1..caps.len() - 1];
+    }
+
+    let mut entries = Vec::new();
+    let mut rest = caps.trim_start();
+    while !rest.is_empty() {
+        let open = rest.find('(')?;
+        let close = closing_paren(&rest[open..])? + open;
+
+        entries.push((rest[..open].trim(), rest[open + 1..close].trim()));
+        rest = rest[close + 1..].trim_start();
+    }
+
+    Some(entries)
+}
+
+#[cfg(feature = "alloc")]
+/// Finds the parenthesis that closes the one at the start of `s`.
+fn closing_paren(s: &str) -> Option<usize> {
+    let mut depth = 0_usize;
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    None
+}
+
+#[cfg(feature = "alloc")]
+/// Parses a list of hex codes, like `01 02 03`.
+///
+/// Some displays don't put spaces between them, so longer words are split
+/// into pairs of digits.
+fn hex_codes(list: &str) -> Option<Vec<u8>> {
+    let mut codes = Vec::new();
+    for word in list.split_whitespace() {
+        if word.len() % 2 != 0 {
+            return None;
+        }
+
+        for pair in word.as_bytes().chunks_exact(2) {
+            let pair = core::str::from_utf8(pair).ok()?;
+            codes.push(u8::from_str_radix(pair, 16).ok()?);
+        }
+    }
+
+    Some(codes)
+}
+
+#[cfg(feature = "alloc")]
+/// Parses the `vcp` entry, like `10 12 60(0F 11 12)`.
+fn vcp_features(list: &str) -> Option<Vec<VcpFeature>> {
+    let mut features = Vec::new();
+    let mut rest = list.trim_start();
+
+    while !rest.is_empty() {
+        // grab the codes up to the next list of values
+        let end = rest.find('(').unwrap_or(rest.len());
+        features.extend(hex_codes(&rest[..end])?.into_iter().map(|code| VcpFeature {
+            code,
+            values: Vec::new(),
+        }));
+        rest = &rest[end..];
+
+        // the values belong to the code just before them
+        if rest.starts_with('(') {
+            let close = closing_paren(rest)?;
+            features.last_mut()?.values = hex_codes(&rest[1..close])?;
+            rest = rest[close + 1..].trim_start();
+        }
+    }
+
+    Some(features)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{collections::BTreeMap, vec, vec::Vec};
+
+    use crate::prelude::internal::*;
+
+    use super::*;
+
+    /// a capabilities string from a dell s2417dg
+    const DELL_CAPS: &str = "(prot(monitor)type(lcd)model(S2417DG)cmds(01 02 03 07 0C E3 F3)\
+        vcp(02 04 05 08 10 12 14(05 08 0B 0C) 16 18 1A 52 60(0F 11 12) AA(01 02) AC AE B2 B6 \
+        C6 C8 C9 D6(01 04 05) DC(00 02 03 05) DF E0 E1 E2(00 01 02 04 0E 12 14 19 1D) F0(00 05) \
+        F1 F2 FD)mswhql(1)asset_eep(40)mccs_ver(2.1))";
+
+    /// a pretend monitor on the other end of the bus
+    struct FakeMonitor {
+        features: BTreeMap<u8, (u16, u16)>,
+        caps: &'static str,
+
+        /// the reply to the last request
+        reply: Vec<u8>,
+        /// send replies with a bad checksum
+        corrupt: bool,
+    }
+
+    impl FakeMonitor {
+        fn new() -> Self {
+            Self {
+                features: BTreeMap::from([
+                    (vcp::BRIGHTNESS, (100, 75)),
+                    (vcp::CONTRAST, (100, 50)),
+                    (vcp::INPUT_SOURCE, (0x12, 0x0F)),
+                    (vcp::POWER_MODE, (0x05, 0x01)),
+                ]),
+                caps: DELL_CAPS,
+                reply: Vec::new(),
+                corrupt: false,
+            }
+        }
+    }
+
+    impl DdcTransport for FakeMonitor {
+        type Error = &'static str;
+
+        fn write(&mut self, address: u8, data: &[u8]) -> Result<(), Self::Error> {
+            if address != DDC_CI_ADDRESS {
+                return Err("nack");
+            }
+
+            // check the host's message first
+            let [HOST_ADDRESS, len, ref payload @ .., checksum] = *data else {
+                return Err("bad message");
+            };
+            if usize::from(len & 0x7F) != payload.len() {
+                return Err("bad length");
+            }
+            if data[..data.len() - 1]
+                .iter()
+                .fold(DISPLAY_ADDRESS, |sum, b| sum ^ b)
+                != checksum
+            {
+                return Err("bad checksum");
+            }
+
+            self.reply = match *payload {
+                [GET_VCP, code] => match self.features.get(&code) {
+                    Some((max, cur)) => {
+                        let ([max_hi, max_lo], [cur_hi, cur_lo]) =
+                            (max.to_be_bytes(), cur.to_be_bytes());
+                        vec![
+                            GET_VCP_REPLY,
+                            0x00,
+                            code,
+                            0x00,
+                            max_hi,
+                            max_lo,
+                            cur_hi,
+                            cur_lo,
+                        ]
+                    }
+                    None => vec![GET_VCP_REPLY, 0x01, code, 0, 0, 0, 0, 0],
+                },
+                [SET_VCP, code, hi, lo] => {
+                    if let Some((_, cur)) = self.features.get_mut(&code) {
+                        *cur = u16::from_be_bytes([hi, lo]);
+                    }
+                    Vec::new()
+                }
+                [CAPABILITIES, hi, lo] => {
+                    let offset = usize::from(u16::from_be_bytes([hi, lo]));
+                    let end = self.caps.len().min(offset + 32);
+                    let mut reply = vec![CAPABILITIES_REPLY, hi, lo];
+                    reply.extend_from_slice(&self.caps.as_bytes()[offset.min(end)..end]);
+                    reply
+                }
+                _ => return Err("unknown opcode"),
+            };
+
+            Ok(())
+        }
+
+        fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+            if address != DDC_CI_ADDRESS {
+                return Err("nack");
+            }
+
+            let mut frame = vec![DISPLAY_ADDRESS, 0x80 | self.reply.len() as u8];
+            frame.extend_from_slice(&self.reply);
+            let checksum = frame.iter().fold(REPLY_CHECKSUM_SEED, |sum, b| sum ^ b);
+            frame.push(if self.corrupt { !checksum } else { checksum });
+
+            buffer.fill(0x00);
+            let len = frame.len().min(buffer.len());
+            buffer[..len].copy_from_slice(&frame[..len]);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn get_and_set_brightness() {
+        logger();
+        let mut monitor = FakeMonitor::new();
+
+        let brightness = get_vcp(&mut monitor, vcp::BRIGHTNESS).unwrap();
+        assert_eq!(
+            brightness,
+            VcpValue {
+                kind: VcpKind::SetParameter,
+                maximum: 100,
+                current: 75,
+            }
+        );
+
+        set_vcp(&mut monitor, vcp::BRIGHTNESS, 30).unwrap();
+        assert_eq!(get_vcp(&mut monitor, vcp::BRIGHTNESS).unwrap().current, 30);
+    }
+
+    #[test]
+    fn other_features() {
+        logger();
+        let mut monitor = FakeMonitor::new();
+
+        assert_eq!(get_vcp(&mut monitor, vcp::CONTRAST).unwrap().current, 50);
+        assert_eq!(
+            get_vcp(&mut monitor, vcp::POWER_MODE).unwrap().current,
+            0x01
+        );
+
+        // switch over to hdmi 1
+        set_vcp(&mut monitor, vcp::INPUT_SOURCE, 0x11).unwrap();
+        assert_eq!(
+            get_vcp(&mut monitor, vcp::INPUT_SOURCE).unwrap().current,
+            0x11
+        );
+    }
+
+    #[test]
+    fn unsupported_feature() {
+        logger();
+        let mut monitor = FakeMonitor::new();
+
+        assert_eq!(
+            get_vcp(&mut monitor, 0x42),
+            Err(DdcError::CiUnsupportedVcp { code: 0x42 })
+        );
+    }
+
+    #[test]
+    fn bad_replies() {
+        logger();
+        let mut monitor = FakeMonitor::new();
+        monitor.corrupt = true;
+        assert_eq!(
+            get_vcp(&mut monitor, vcp::BRIGHTNESS),
+            Err(DdcError::CiBadChecksum)
+        );
+
+        // a null message is the display saying it's busy
+        let mut buffer = [0x00; 11];
+        monitor.corrupt = false;
+        monitor.reply.clear();
+        assert_eq!(
+            receive(&mut monitor, &mut buffer),
+            Err(DdcError::CiNullReply)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn fetch_capabilities() {
+        logger();
+        let mut monitor = FakeMonitor::new();
+
+        // this takes quite a few fragments
+        assert!(DELL_CAPS.len() > 32 * 5);
+        assert_eq!(capabilities_string(&mut monitor).unwrap(), DELL_CAPS);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn parse_capabilities() {
+        logger();
+        let caps = Capabilities::parse(DELL_CAPS).unwrap();
+
+        assert_eq!(caps.protocol.as_deref(), Some("monitor"));
+        assert_eq!(caps.display_type.as_deref(), Some("lcd"));
+        assert_eq!(caps.model.as_deref(), Some("S2417DG"));
+        assert_eq!(caps.mccs_version.as_deref(), Some("2.1"));
+        assert_eq!(
+            caps.commands,
+            [
+                0x01,
+                0x02,
+                0x03,
+                0x07,
+                0x0C,
+                CAPABILITIES_REPLY,
+                CAPABILITIES
+            ]
+        );
+        assert_eq!(caps.vcp_features.len(), 30);
+
+        assert!(caps.supports(vcp::BRIGHTNESS));
+        assert!(!caps.supports(0x42));
+        assert_eq!(
+            caps.vcp_features
+                .iter()
+                .find(|f| f.code == vcp::INPUT_SOURCE),
+            Some(&VcpFeature {
+                code: vcp::INPUT_SOURCE,
+                values: vec![0x0F, 0x11, 0x12],
+            })
+        );
+
+        let other = caps
+            .other
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(other, [("mswhql", "1"), ("asset_eep", "40")]);
+    }
+
+    /// some displays squish their codes together and skip the outer parens
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn parse_squished_capabilities() {
+        logger();
+        let caps = Capabilities::parse("prot(monitor) vcp(021012(01 02)16) mccs_ver(2.2)").unwrap();
+
+        assert_eq!(caps.protocol.as_deref(), Some("monitor"));
+        assert_eq!(
+            caps.vcp_features,
+            [
+                VcpFeature {
+                    code: 0x02,
+                    values: vec![]
+                },
+                VcpFeature {
+                    code: 0x10,
+                    values: vec![]
+                },
+                VcpFeature {
+                    code: 0x12,
+                    values: vec![0x01, 0x02]
+                },
+                VcpFeature {
+                    code: 0x16,
+                    values: vec![]
+                },
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn parse_broken_capabilities() {
+        logger();
+        assert_eq!(Capabilities::parse("(prot(monitor)vcp(10 12)"), None);
+        assert_eq!(Capabilities::parse("(vcp(10 XY))"), None);
+        assert_eq!(Capabilities::parse("(cmds(01 2))"), None);
+        assert_eq!(Capabilities::parse(""), Some(Capabilities::default()));
+    }
+}
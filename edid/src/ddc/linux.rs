@@ -0,0 +1,139 @@
+//! A transport for the `/dev/i2c-N` devices on Linux.
+
+extern crate std;
+
+use std::{fs::File, io, os::fd::AsRawFd as _, path::Path};
+
+use super::{DdcTransport, EDID_ADDRESS, SEGMENT_ADDRESS};
+use crate::error::DdcError;
+
+/// The `ioctl` for combined transfers. (see `linux/i2c-dev.h`)
+const I2C_RDWR: u64 = 0x0707;
+
+/// Marks a message as a read. (see `linux/i2c.h`)
+const I2C_M_RD: u16 = 0x0001;
+
+/// One part of a combined transfer, as the kernel expects it.
+#[repr(C)]
+struct I2cMsg {
+    addr: u16,
+    flags: u16,
+    len: u16,
+    buf: *mut u8,
+}
+
+/// The argument to `I2C_RDWR`.
+#[repr(C)]
+struct I2cRdwrIoctlData {
+    msgs: *mut I2cMsg,
+    nmsgs: u32,
+}
+
+/// An I²C bus from the `i2c-dev` kernel module, like `/dev/i2c-5`.
+///
+/// Each GPU connector usually has its own bus. You can find the bus for a
+/// connector with `ls /sys/class/drm/card1-DP-3/ | grep i2c`.
+#[derive(Debug)]
+pub struct I2cDevice {
+    file: File,
+}
+
+impl I2cDevice {
+    /// Opens the bus at `/dev/i2c-{bus}`.
+    pub fn open(bus: u32) -> io::Result<Self> {
+        Self::open_path(std::format!("/dev/i2c-{bus}"))
+    }
+
+    /// Opens the bus at the given `path`.
+    pub fn open_path<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = File::options().read(true).write(true).open(path)?;
+        Ok(Self { file })
+    }
+
+    /// Sends all the `messages` as a single transfer, with repeated starts
+    /// between them.
+    fn transfer(&mut self, messages: &mut [I2cMsg]) -> io::Result<()> {
+        let mut data = I2cRdwrIoctlData {
+            msgs: messages.as_mut_ptr(),
+            nmsgs: messages.len() as u32,
+        };
+
+        // SAFETY: `data` points at `messages`, and each message points at a
+        // buffer at least `len` bytes long. they all outlive the call.
+        let result = unsafe {
+            libc::ioctl(
+                self.file.as_raw_fd(),
+                I2C_RDWR as _,
+                &mut data as *mut I2cRdwrIoctlData,
+            )
+        };
+
+        if result < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+}
+
+/// Makes a message that writes `data` to `address`.
+fn write_msg(address: u8, data: &[u8]) -> I2cMsg {
+    I2cMsg {
+        addr: address.into(),
+        flags: 0,
+        len: data.len() as u16,
+        // the kernel doesn't write to this, despite the pointer being `mut`
+        buf: data.as_ptr().cast_mut(),
+    }
+}
+
+/// Makes a message that reads into `buffer` from `address`.
+fn read_msg(address: u8, buffer: &mut [u8]) -> I2cMsg {
+    I2cMsg {
+        addr: address.into(),
+        flags: I2C_M_RD,
+        len: buffer.len() as u16,
+        buf: buffer.as_mut_ptr(),
+    }
+}
+
+impl DdcTransport for I2cDevice {
+    type Error = io::Error;
+
+    fn write(&mut self, address: u8, data: &[u8]) -> Result<(), Self::Error> {
+        self.transfer(&mut [write_msg(address, data)])
+    }
+
+    fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.transfer(&mut [read_msg(address, buffer)])
+    }
+
+    fn wait(&mut self, milliseconds: u32) {
+        std::thread::sleep(core::time::Duration::from_millis(milliseconds.into()));
+    }
+
+    /// Reads with a single combined transfer, so the display doesn't reset
+    /// its segment pointer in the middle.
+    fn read_segment(
+        &mut self,
+        segment: u8,
+        offset: u8,
+        buffer: &mut [u8],
+    ) -> Result<(), DdcError<Self::Error>> {
+        let segment = [segment];
+        let offset = [offset];
+        let read = read_msg(EDID_ADDRESS, buffer);
+
+        // some displays nack the segment pointer when it's unused, so only
+        // send it when we need it
+        if segment[0] == 0 {
+            self.transfer(&mut [write_msg(EDID_ADDRESS, &offset), read])
+        } else {
+            self.transfer(&mut [
+                write_msg(SEGMENT_ADDRESS, &segment),
+                write_msg(EDID_ADDRESS, &offset),
+                read,
+            ])
+        }
+        .map_err(DdcError::Transport)
+    }
+}
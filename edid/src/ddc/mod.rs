@@ -0,0 +1,454 @@
+//! Reads EDIDs straight from a display, over its I²C bus.
+//!
+//! Displays keep their EDID in a small memory at address `0x50` on the
+//! DDC lines of their cable. That memory can only hold 256 bytes, so
+//! Enhanced DDC (E-DDC) adds a segment pointer at `0x30`, which selects
+//! the 256-byte segment that later reads come from.
+//!
+//! This module can read an EDID from anything that implements
+//! `DdcTransport`. On Linux, the `linux` feature adds `I2cDevice`, which
+//! talks to the `/dev/i2c-N` devices from the `i2c-dev` kernel module.
+//! Over the same transport, the `ci` module can change the display's
+//! settings, like its brightness.
+//!
+//! ```edition2021,ignore
+//! use liboptic_edid::{ddc, Edid};
+//!
+//! let mut bus = ddc::I2cDevice::open(5)?;
+//! let edid = Edid::new(ddc::fetch(&mut bus)?)?;
+//! ```
+
+#[cfg(any(feature = "alloc", test))]
+extern crate alloc;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use arrayvec::ArrayVec;
+
+pub mod ci;
+#[cfg(all(feature = "linux", target_os = "linux"))]
+mod linux;
+#[cfg(all(feature = "linux", target_os = "linux"))]
+pub use linux::I2cDevice;
+
+use crate::error::DdcError;
+use crate::parser::header::EDID_HEADER;
+use crate::prelude::internal::tracing;
+
+/// The I²C address of the display's EDID memory.
+pub const EDID_ADDRESS: u8 = 0x50;
+
+/// The I²C address of the E-DDC segment pointer.
+pub const SEGMENT_ADDRESS: u8 = 0x30;
+
+pub use crate::parser::header::BLOCK_LEN;
+
+/// How many times `fetch` re-reads a block with a bad checksum.
+pub const DEFAULT_RETRIES: u8 = 3;
+
+/// Something that can send and receive bytes on a display's I²C bus.
+///
+/// Addresses are 7-bit, so the EDID is at `0x50`, not `0xA0`.
+pub trait DdcTransport {
+    /// The error returned when a transfer fails.
+    type Error: core::fmt::Debug + core::fmt::Display;
+
+    /// Writes `data` to the device at `address`.
+    fn write(&mut self, address: u8, data: &[u8]) -> Result<(), Self::Error>;
+
+    /// Fills `buffer` with bytes read from the device at `address`.
+    fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Self::Error>;
+
+    /// Fills `buffer` with the EDID bytes at `offset` within `segment`.
+    ///
+    /// E-DDC displays reset their segment pointer after each transfer, so
+    /// the segment, offset and read have to go out as one combined transfer
+    /// (with repeated starts). The default can only do that for segment
+    /// zero, where there's no pointer to set. For any other segment, it
+    /// returns `DdcError::UnsupportedCombinedTransfer`, so transports that
+    /// can do combined transfers should override this.
+    fn read_segment(
+        &mut self,
+        segment: u8,
+        offset: u8,
+        buffer: &mut [u8],
+    ) -> Result<(), DdcError<Self::Error>> {
+        if segment != 0 {
+            tracing::error!("can't read segment {segment} without a combined transfer.");
+            return Err(DdcError::UnsupportedCombinedTransfer { segment });
+        }
+
+        self.write(EDID_ADDRESS, &[offset])
+            .map_err(DdcError::Transport)?;
+        self.read(EDID_ADDRESS, buffer).map_err(DdcError::Transport)
+    }
+
+    /// Waits for the given number of `milliseconds`.
+    ///
+    /// DDC/CI displays need some time to answer each request. The default
+    /// doesn't wait at all, which is fine for transports that can't sleep
+    /// (or don't need to).
+    fn wait(&mut self, milliseconds: u32) {
+        _ = milliseconds;
+    }
+}
+
+impl<T: DdcTransport + ?Sized> DdcTransport for &mut T {
+    type Error = T::Error;
+
+    fn write(&mut self, address: u8, data: &[u8]) -> Result<(), Self::Error> {
+        (**self).write(address, data)
+    }
+
+    fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        (**self).read(address, buffer)
+    }
+
+    fn read_segment(
+        &mut self,
+        segment: u8,
+        offset: u8,
+        buffer: &mut [u8],
+    ) -> Result<(), DdcError<Self::Error>> {
+        (**self).read_segment(segment, offset, buffer)
+    }
+
+    fn wait(&mut self, milliseconds: u32) {
+        (**self).wait(milliseconds);
+    }
+}
+
+/// Reads the display's whole EDID, including its extension blocks.
+///
+/// Blocks with a bad checksum are read again up to `DEFAULT_RETRIES` times.
+#[cfg(feature = "alloc")]
+pub fn fetch<T: DdcTransport>(transport: &mut T) -> Result<Vec<u8>, DdcError<T::Error>> {
+    fetch_with_retries(transport, DEFAULT_RETRIES)
+}
+
+/// Reads the display's whole EDID, including its extension blocks.
+///
+/// Blocks with a bad checksum are read again up to `retries` times.
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "tracing", tracing::instrument(skip(transport)))]
+pub fn fetch_with_retries<T: DdcTransport>(
+    transport: &mut T,
+    retries: u8,
+) -> Result<Vec<u8>, DdcError<T::Error>> {
+    let first = base_block(transport, retries)?;
+    let extensions = first[126];
+
+    let mut edid = Vec::with_capacity(BLOCK_LEN * (usize::from(extensions) + 1));
+    edid.extend_from_slice(&first);
+    for index in 1..=extensions {
+        edid.extend_from_slice(&block(transport, index, retries)?);
+    }
+
+    Ok(edid)
+}
+
+/// Reads the display's whole EDID into room for up to `N` blocks, without
+/// allocating.
+///
+/// This is for targets without an allocator. Most displays have one or two
+/// blocks, and it's rare to see more than four. If the display has more than
+/// `N`, this returns `DdcError::TooManyExtensions`.
+///
+/// ```edition2021,ignore
+/// let blocks = ddc::fetch_blocks::<_, 4>(&mut bus, ddc::DEFAULT_RETRIES)?;
+/// let edid = Edid::new(blocks.as_flattened())?;
+/// ```
+#[cfg_attr(feature = "tracing", tracing::instrument(skip(transport)))]
+pub fn fetch_blocks<T: DdcTransport, const N: usize>(
+    transport: &mut T,
+    retries: u8,
+) -> Result<ArrayVec<[u8; BLOCK_LEN], N>, DdcError<T::Error>> {
+    let first = base_block(transport, retries)?;
+    let extensions = first[126];
+    if usize::from(extensions) >= N {
+        tracing::error!("there's only room for {N} blocks, but it has {extensions} extensions.");
+        return Err(DdcError::TooManyExtensions { extensions });
+    }
+
+    let mut blocks = ArrayVec::new();
+    blocks.push(first);
+    for index in 1..=extensions {
+        blocks.push(block(transport, index, retries)?);
+    }
+
+    Ok(blocks)
+}
+
+/// Reads the base block, making sure it starts with the header.
+fn base_block<T: DdcTransport>(
+    transport: &mut T,
+    retries: u8,
+) -> Result<[u8; BLOCK_LEN], DdcError<T::Error>> {
+    let first = block(transport, 0, retries)?;
+    if !first.starts_with(&EDID_HEADER) {
+        tracing::error!("the first block has no header. (start: {:x?})", &first[..8]);
+        return Err(DdcError::NoHeader);
+    }
+
+    // the base block says how many extensions follow it
+    tracing::debug!("the display has {} extension blocks.", first[126]);
+    Ok(first)
+}
+
+/// Reads the block at `index`, retrying when its checksum is bad.
+#[cfg_attr(feature = "tracing", tracing::instrument(skip(transport)))]
+fn block<T: DdcTransport>(
+    transport: &mut T,
+    index: u8,
+    retries: u8,
+) -> Result<[u8; BLOCK_LEN], DdcError<T::Error>> {
+    // each segment holds two blocks
+    let segment = index / 2;
+    let offset = (index % 2) * BLOCK_LEN as u8;
+
+    for attempt in 0..=retries {
+        let mut buffer = [0x00; BLOCK_LEN];
+        transport.read_segment(segment, offset, &mut buffer)?;
+
+        // all the bytes in a block add up to zero
+        if buffer.iter().fold(0_u8, |sum, b| sum.wrapping_add(*b)) == 0 {
+            return Ok(buffer);
+        }
+
+        tracing::warn!("block {index} had a bad checksum on attempt {attempt}.");
+    }
+
+    tracing::error!("block {index} never had a valid checksum.");
+    Err(DdcError::BadChecksum { block: index })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::prelude::internal::*;
+
+    use super::*;
+
+    /// a pretend display, serving an edid from memory
+    struct FakeDisplay {
+        edid: Vec<u8>,
+        segment: u8,
+        offset: u8,
+
+        /// how many reads to mess up before working properly
+        corrupt_reads: usize,
+        /// every segment we were asked to read from
+        segments_read: Vec<u8>,
+    }
+
+    impl FakeDisplay {
+        fn new(edid: Vec<u8>) -> Self {
+            Self {
+                edid,
+                segment: 0,
+                offset: 0,
+                corrupt_reads: 0,
+                segments_read: Vec::new(),
+            }
+        }
+    }
+
+    impl DdcTransport for FakeDisplay {
+        type Error = &'static str;
+
+        fn write(&mut self, address: u8, data: &[u8]) -> Result<(), Self::Error> {
+            match (address, data) {
+                (SEGMENT_ADDRESS, [segment]) => self.segment = *segment,
+                (EDID_ADDRESS, [offset]) => self.offset = *offset,
+                _ => return Err("nack"),
+            }
+
+            // like a real display, the segment pointer resets after every
+            // transfer
+            self.segment = 0;
+            Ok(())
+        }
+
+        fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+            if address != EDID_ADDRESS {
+                return Err("nack");
+            }
+
+            let start = usize::from(self.segment) * 256 + usize::from(self.offset);
+            let bytes = self.edid.get(start..start + buffer.len()).ok_or("nack")?;
+            buffer.copy_from_slice(bytes);
+
+            if self.corrupt_reads > 0 {
+                self.corrupt_reads -= 1;
+                buffer[20] ^= 0xFF;
+            }
+
+            self.segments_read.push(self.segment);
+            self.segment = 0;
+            Ok(())
+        }
+    }
+
+    /// a fake display behind a transport that can do combined transfers
+    #[cfg(feature = "alloc")]
+    struct Combined(FakeDisplay);
+
+    #[cfg(feature = "alloc")]
+    impl DdcTransport for Combined {
+        type Error = &'static str;
+
+        fn write(&mut self, address: u8, data: &[u8]) -> Result<(), Self::Error> {
+            self.0.write(address, data)
+        }
+
+        fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+            self.0.read(address, buffer)
+        }
+
+        fn read_segment(
+            &mut self,
+            segment: u8,
+            offset: u8,
+            buffer: &mut [u8],
+        ) -> Result<(), DdcError<Self::Error>> {
+            // one transfer, so the pointer holds until the read
+            self.0.segment = segment;
+            self.0.offset = offset;
+            self.0
+                .read(EDID_ADDRESS, buffer)
+                .map_err(DdcError::Transport)
+        }
+    }
+
+    /// makes a four-block edid, so the last two need the segment pointer
+    #[cfg(feature = "alloc")]
+    fn four_blocks() -> Vec<u8> {
+        let mut edid = raw_edid_by_filename("dell_s2417dg.raw.input");
+        let extension = edid[BLOCK_LEN..].to_vec();
+        edid.extend_from_slice(&extension);
+        edid.extend_from_slice(&extension);
+
+        // fix up the count and the checksum
+        edid[126] = 3;
+        edid[127] = 0;
+        edid[127] = edid[..BLOCK_LEN]
+            .iter()
+            .fold(0_u8, |sum, b| sum.wrapping_sub(*b));
+        edid
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn single_block() {
+        logger();
+        let input = edid_by_filename("1.input");
+        let mut display = FakeDisplay::new(input.clone());
+
+        assert_eq!(fetch(&mut display).unwrap(), input);
+        assert_eq!(display.segments_read, [0]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn dell_s2417dg() {
+        logger();
+        let input = raw_edid_by_filename("dell_s2417dg.raw.input");
+        let mut display = FakeDisplay::new(input.clone());
+
+        let fetched = fetch(&mut display).unwrap();
+        assert_eq!(fetched, input);
+        assert_eq!(Edid::new(&fetched).unwrap().checksum, 0x51);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn segment_pointer() {
+        logger();
+        let input = four_blocks();
+        let mut display = Combined(FakeDisplay::new(input.clone()));
+
+        assert_eq!(fetch(&mut display).unwrap(), input);
+        assert_eq!(display.0.segments_read, [0, 0, 1, 1]);
+    }
+
+    /// separate transfers would lose the segment pointer, so they're refused
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn segment_pointer_needs_combined_transfer() {
+        logger();
+        let mut display = FakeDisplay::new(four_blocks());
+
+        assert_eq!(
+            fetch(&mut display),
+            Err(DdcError::UnsupportedCombinedTransfer { segment: 1 })
+        );
+        assert_eq!(display.segments_read, [0, 0]);
+
+        // even when done by hand
+        display.write(SEGMENT_ADDRESS, &[1]).unwrap();
+        display.write(EDID_ADDRESS, &[0]).unwrap();
+        let mut buffer = [0x00; BLOCK_LEN];
+        display.read(EDID_ADDRESS, &mut buffer).unwrap();
+        assert_eq!(display.segments_read, [0, 0, 0]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn retries_bad_checksums() {
+        logger();
+        let input = raw_edid_by_filename("dell_s2417dg.raw.input");
+
+        let mut flaky = FakeDisplay::new(input.clone());
+        flaky.corrupt_reads = 3;
+        assert_eq!(fetch(&mut flaky).unwrap(), input);
+
+        let mut broken = FakeDisplay::new(input);
+        broken.corrupt_reads = 4;
+        assert_eq!(fetch(&mut broken), Err(DdcError::BadChecksum { block: 0 }));
+
+        let mut no_retries = FakeDisplay::new(edid_by_filename("1.input"));
+        no_retries.corrupt_reads = 1;
+        assert_eq!(
+            fetch_with_retries(&mut no_retries, 0),
+            Err(DdcError::BadChecksum { block: 0 })
+        );
+    }
+
+    #[test]
+    fn fixed_capacity() {
+        logger();
+        let input = raw_edid_by_filename("dell_s2417dg.raw.input");
+
+        let mut display = FakeDisplay::new(input.clone());
+        let blocks = fetch_blocks::<_, 2>(&mut display, DEFAULT_RETRIES).unwrap();
+        assert_eq!(blocks.as_flattened(), input);
+
+        let mut display = FakeDisplay::new(input);
+        assert_eq!(
+            fetch_blocks::<_, 1>(&mut display, DEFAULT_RETRIES),
+            Err(DdcError::TooManyExtensions { extensions: 1 })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn transport_errors() {
+        logger();
+
+        // says it has an extension, but doesn't
+        let mut input = edid_by_filename("1.input");
+        input[126] = 1;
+        input[127] = input[127].wrapping_sub(1);
+
+        let mut display = FakeDisplay::new(input);
+        assert_eq!(fetch(&mut display), Err(DdcError::Transport("nack")));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn no_header() {
+        logger();
+        let mut display = FakeDisplay::new([0x00; BLOCK_LEN].to_vec());
+        assert_eq!(fetch(&mut display), Err(DdcError::NoHeader));
+    }
+}
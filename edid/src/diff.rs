@@ -0,0 +1,915 @@
+//! Compares two EDIDs, field by field.
+//!
+//! Firmware updates, KVMs, and docks all like to change a display's EDID.
+//! This finds out what they changed.
+//!
+//! ```edition2021
+//! use liboptic_edid::{diff, patch::EdidPatch, Edid};
+//!
+//! let original = std::fs::read("tests/assets/dell_s2417dg.raw.input")?;
+//! let patched = EdidPatch::new().product_name("Renamed").apply(&original)?;
+//!
+//! for difference in diff(&Edid::new(&original)?, &Edid::new(&patched)?) {
+//!     // like `descriptor 4: "Dell S2417DG" → "Renamed"`
+//!     println!("{difference}");
+//! }
+//! #
+//! # Ok::<(), Box<dyn core::error::Error>>(())
+//! ```
+
+extern crate alloc;
+
+use alloc::{
+    format,
+    string::{String, ToString as _},
+    vec::Vec,
+};
+use arrayvec::ArrayString;
+use core::fmt::{self, Debug};
+
+use crate::parser::header::BLOCK_LEN;
+use crate::prelude::internal::*;
+use crate::structures::{
+    basic_info::{
+        feature_support::{
+            ColorEncodingFormats, ColorSupport, ColorType, FeatureSupport, PowerManagement,
+        },
+        vsi::{
+            analog::{SignalLevelStandard, SyncTypes, VideoSetup},
+            digital::{ColorBitDepth, SupportedVideoInterface},
+            VideoSignalInterface,
+        },
+        SizeOrRatio,
+    },
+    color::{ColorCharacteristics, ColorCoordinate},
+    decimal::AsDecimal,
+    desc::{
+        color_point::WhitePoint,
+        cvt_3_byte_timing::{
+            CvtAspectRatio, CvtPreferredVerticalRate, SupportedVRates, TimingCodeDesc,
+        },
+        dcm::DcmCurve,
+    },
+    id::{Date, Manufacturer, VendorProductId},
+};
+
+/// One field that differs between two EDIDs.
+#[repr(C)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Difference {
+    /// Where the field is, like `descriptor 3.limits.max_v_rate_hz`.
+    ///
+    /// The four 18-byte blocks are `descriptor 1` to `descriptor 4`, and
+    /// extension blocks are `extension 1` and up. Everything else uses the
+    /// names of the fields in [`Edid`].
+    pub path: String,
+
+    /// The field's value in the first EDID.
+    pub old: Value,
+
+    /// The field's value in the second EDID.
+    pub new: Value,
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} → {}", self.path, self.old, self.new)
+    }
+}
+
+/// The value of a field in one of the EDIDs being compared.
+#[repr(C)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Value {
+    /// The field isn't in this EDID, like a standard timing that's unused.
+    Missing,
+    Bool(bool),
+    Integer(i64),
+
+    /// An exact fraction, like a gamma of 2.2.
+    Ratio(
+        #[cfg_attr(feature = "serde", serde(with = "crate::structures::decimal::string"))]
+        Ratio<i64>,
+    ),
+
+    Text(String),
+
+    /// The name of an enum variant.
+    ///
+    /// When a field changes from one variant to another, like a descriptor
+    /// going from `DataString` to `ProductName`, only the names are
+    /// reported.
+    Variant(String),
+
+    Bytes(Vec<u8>),
+
+    /// Anything else, in its `Debug` form.
+    Other(String),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing => f.write_str("none"),
+            Self::Bool(b) => write!(f, "{b}"),
+            Self::Integer(i) => write!(f, "{i}"),
+            Self::Ratio(r) => write!(f, "{}", AsDecimal(*r)),
+            Self::Text(s) => write!(f, "{s:?}"),
+            Self::Variant(name) | Self::Other(name) => f.write_str(name),
+            Self::Bytes(bytes) => {
+                for (i, b) in bytes.iter().enumerate() {
+                    if i != 0 {
+                        f.write_str(" ")?;
+                    }
+                    write!(f, "{b:02x}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Finds every field that differs between `old` and `new`.
+///
+/// `Edid` doesn't hold extension blocks yet, so this only compares the
+/// base block. Use [`diff_bytes`] to compare those, too.
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+pub fn diff(old: &Edid, new: &Edid) -> Vec<Difference> {
+    let mut differ = Differ::default();
+    old.walk(new, &mut differ);
+    differ.found
+}
+
+/// Parses both EDIDs, then compares their base blocks and their extension
+/// blocks.
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+pub fn diff_bytes(old: &[u8], new: &[u8]) -> Result<Vec<Difference>, EdidError> {
+    let mut found = diff(&Edid::new(old)?, &Edid::new(new)?);
+    found.extend(diff_extensions(old, new));
+    Ok(found)
+}
+
+/// Compares the extension blocks after the base block.
+///
+/// Extensions aren't decoded yet, so blocks with the same tag are compared
+/// byte by byte. Each run of changed bytes is one [`Difference`], with a
+/// path like `extension 1.bytes[0x04..0x06]`.
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+pub fn diff_extensions(old: &[u8], new: &[u8]) -> Vec<Difference> {
+    let extensions = |edid: &[u8]| -> Vec<Vec<u8>> {
+        edid.get(BLOCK_LEN..)
+            .unwrap_or_default()
+            .chunks(BLOCK_LEN)
+            .map(<[u8]>::to_vec)
+            .collect()
+    };
+    let (old, new) = (extensions(old), extensions(new));
+
+    let mut differ = Differ::default();
+    for i in 0..old.len().max(new.len()) {
+        differ.at(format!("extension {}", i + 1), |d| {
+            match (old.get(i), new.get(i)) {
+                (Some(a), Some(b)) if a[0] == b[0] => changed_bytes(a, b, d),
+                (a, b) => d.push(block_kind(a), block_kind(b)),
+            }
+        });
+    }
+    differ.found
+}
+
+/// Describes an extension block by its tag.
+fn block_kind(block: Option<&Vec<u8>>) -> Value {
+    match block {
+        Some(block) => Value::Variant(format!("block with tag {:#04x}", block[0])),
+        None => Value::Missing,
+    }
+}
+
+/// Reports each run of bytes that differ between two blocks.
+fn changed_bytes(old: &[u8], new: &[u8], d: &mut Differ) {
+    let len = old.len().max(new.len());
+    let differs = |i: usize| old.get(i) != new.get(i);
+
+    let mut i = 0;
+    while i < len {
+        if !differs(i) {
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        while i < len && differs(i) {
+            i += 1;
+        }
+
+        let bytes = |block: &[u8]| {
+            Value::Bytes(
+                block
+                    .get(start..i.min(block.len()))
+                    .unwrap_or_default()
+                    .to_vec(),
+            )
+        };
+        d.at(format!("bytes[{start:#04x}..{i:#04x}]"), |d| {
+            d.push(bytes(old), bytes(new));
+        });
+    }
+}
+
+/// Keeps track of where we are while walking two EDIDs.
+#[derive(Default)]
+struct Differ {
+    path: Vec<String>,
+    found: Vec<Difference>,
+}
+
+impl Differ {
+    /// Runs `f` with `segment` added to the path.
+    fn at(&mut self, segment: impl Into<String>, f: impl FnOnce(&mut Self)) {
+        self.path.push(segment.into());
+        f(self);
+        self.path.pop();
+    }
+
+    /// Runs `f` with `[i]` added to the end of the path, like `timings[2]`.
+    fn index(&mut self, i: usize, f: impl FnOnce(&mut Self)) {
+        let last = self.path.pop().unwrap_or_default();
+        self.path.push(format!("{last}[{i}]"));
+        f(self);
+        self.path.pop();
+        self.path.push(last);
+    }
+
+    /// Compares a field of two structures.
+    fn field<T: Walk + ?Sized>(&mut self, name: &str, old: &T, new: &T) {
+        self.at(name, |d| old.walk(new, d));
+    }
+
+    /// Records a difference at the current path.
+    fn push(&mut self, old: Value, new: Value) {
+        let difference = Difference {
+            path: self.path.join("."),
+            old,
+            new,
+        };
+        tracing::debug!("Found a difference: {difference}");
+        self.found.push(difference);
+    }
+}
+
+/// Something that can be compared with another of itself.
+///
+/// By default, types are compared as a whole. Structures override `walk` to
+/// compare each of their fields instead.
+trait Walk: PartialEq + Debug {
+    fn walk(&self, other: &Self, d: &mut Differ) {
+        if self != other {
+            d.push(self.value(), other.value());
+        }
+    }
+
+    fn value(&self) -> Value {
+        Value::Other(format!("{self:?}"))
+    }
+}
+
+/// Grabs the variant name from an enum's `Debug` output.
+fn variant_name(value: &impl Debug) -> Value {
+    let debug = format!("{value:?}");
+    let name = debug
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .next()
+        .unwrap_or_default();
+    Value::Variant(name.to_string())
+}
+
+impl<T: Walk> Walk for Option<T> {
+    fn walk(&self, other: &Self, d: &mut Differ) {
+        match (self, other) {
+            (Some(a), Some(b)) => a.walk(b, d),
+            (None, None) => (),
+            _ => d.push(self.value(), other.value()),
+        }
+    }
+
+    fn value(&self) -> Value {
+        self.as_ref().map_or(Value::Missing, Walk::value)
+    }
+}
+
+impl Walk for bool {
+    fn value(&self) -> Value {
+        Value::Bool(*self)
+    }
+}
+
+macro_rules! integers {
+    ($($ty:ty),*) => {
+        $(
+            impl Walk for $ty {
+                fn value(&self) -> Value {
+                    Value::Integer(i64::from(*self))
+                }
+            }
+        )*
+    };
+}
+
+integers!(u8, u16, u32);
+
+macro_rules! ratios {
+    ($($ty:ty),*) => {
+        $(
+            impl Walk for Ratio<$ty> {
+                fn value(&self) -> Value {
+                    Value::Ratio(AsDecimal::new(self).0)
+                }
+            }
+        )*
+    };
+}
+
+ratios!(u16, u32, i32);
+
+impl<const N: usize> Walk for ArrayString<N> {
+    fn value(&self) -> Value {
+        Value::Text(self.to_string())
+    }
+}
+
+impl<const N: usize> Walk for [u8; N] {
+    fn value(&self) -> Value {
+        Value::Bytes(self.to_vec())
+    }
+}
+
+impl<const N: usize> Walk for [Option<STiming>; N] {
+    fn walk(&self, other: &Self, d: &mut Differ) {
+        for (i, (a, b)) in self.iter().zip(other).enumerate() {
+            d.index(i, |d| a.walk(b, d));
+        }
+    }
+}
+
+impl Walk for Manufacturer {
+    fn value(&self) -> Value {
+        match self {
+            Self::Name(name) => Value::Text(name.to_string()),
+            Self::Id(id) => Value::Text(id.to_string()),
+        }
+    }
+}
+
+/// Enums without data are compared by name.
+macro_rules! unit_enums {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl Walk for $ty {
+                fn value(&self) -> Value {
+                    variant_name(self)
+                }
+            }
+        )*
+    };
+}
+
+unit_enums!(
+    Interpretation,
+    SignalLevelStandard,
+    VideoSetup,
+    ColorBitDepth,
+    SupportedVideoInterface,
+    ColorType,
+    ColorEncodingFormats,
+    StandardAspectRatio,
+    SignalInterfaceType,
+    StereoViewingSupport,
+    AnalogSyncOn,
+    DigitalSyncSignal,
+    CvtAspectRatio,
+    CvtPreferredVerticalRate,
+    VerticalOffset,
+    HorizontalOffset,
+    PreferredAspectRatio,
+);
+
+/// Structures are compared field by field.
+macro_rules! structs {
+    ($($ty:ty { $($field:ident),* $(,)? }),* $(,)?) => {
+        $(
+            impl Walk for $ty {
+                fn walk(&self, other: &Self, d: &mut Differ) {
+                    $(d.field(stringify!($field), &self.$field, &other.$field);)*
+                }
+            }
+        )*
+    };
+}
+
+structs!(
+    VendorProductId {
+        manufacturer_name,
+        manufacturer_id,
+        product_code,
+        serial_number,
+        date,
+    },
+    EdidVersion { version, revision },
+    BasicDisplayInfo {
+        input_definition,
+        screen_size_or_aspect_ratio,
+        reported_gamma,
+        feature_support,
+        interpretation,
+    },
+    SyncTypes {
+        separate_sync_h_and_v,
+        composite_sync_horizontal,
+        composite_sync_green_video,
+    },
+    FeatureSupport {
+        power_management,
+        color_support,
+        srgb_std,
+        says_pixel_format_and_refresh,
+        is_continuous_freq,
+        supports_default_gtf,
+    },
+    PowerManagement {
+        standby,
+        suspend,
+        active_off,
+    },
+    ColorCharacteristics {
+        red,
+        green,
+        blue,
+        white_point,
+    },
+    ColorCoordinate { x, y },
+    EstablishedTimings {
+        i,
+        ii,
+        manufacturer_timings,
+    },
+    EstablishedTimingsI {
+        _720x400_70hz,
+        _720x400_88hz,
+        _640x480_60hz,
+        _640x480_67hz,
+        _640x480_72hz,
+        _640x480_75hz,
+        _800x600_56hz,
+        _800x600_60hz,
+    },
+    EstablishedTimingsII {
+        _800x600_72hz,
+        _800x600_75hz,
+        _832x624_75hz,
+        _1024x768_87hz_interlaced,
+        _1024x768_60hz,
+        _1024x768_70hz,
+        _1024x768_75hz,
+        _1280x1024_75hz,
+    },
+    ManufacturerTimings {
+        _1152x870_75hz,
+        _6,
+        _5,
+        _4,
+        _3,
+        _2,
+        _1,
+        _0,
+    },
+    StandardTimings {
+        timings,
+        interpretation,
+    },
+    STiming {
+        horizontal_addr_pixel_ct,
+        aspect_ratio,
+        field_refresh_rate,
+    },
+    DetailedTimingDefinition {
+        pixel_clock_khz,
+        horizontal_addressable_video_px,
+        horizontal_blanking_px,
+        vertical_addressable_video_lines,
+        vertical_blanking_lines,
+        horizontal_front_porch,
+        horizontal_sync_pulse_width_px,
+        vertical_front_porch_lines,
+        vertical_sync_pulse_width_lines,
+        horizontal_addressable_video_size_mm,
+        vertical_addressable_video_size_mm,
+        horizontal_border_px,
+        vertical_border_lines,
+        signal_interface_type,
+        stereo_support,
+        sync_signal,
+    },
+    WhitePoint {
+        index_number,
+        coord,
+        gamma,
+    },
+    DcmCurve { a3, a2 },
+    TimingCodeDesc {
+        addressable_lines,
+        aspect_ratio,
+        preferred_vertical_rate,
+        supported_vertical_rates,
+    },
+    SupportedVRates {
+        _50_hz_standard,
+        _60_hz_standard,
+        _75_hz_standard,
+        _85_hz_standard,
+        _60_hz_reduced,
+    },
+    RangeLimits {
+        min_v_rate_hz,
+        max_v_rate_hz,
+        min_h_rate_khz,
+        max_h_rate_khz,
+        offsets,
+        max_pixel_clock_mhz,
+        interpretation,
+    },
+    Offsets {
+        vertical,
+        horizontal,
+    },
+    SupportedAspectRatios {
+        _4x3,
+        _16x9,
+        _16x10,
+        _5x4,
+        _15x9,
+    },
+);
+
+/// Enums with data are compared field by field when they're the same
+/// variant. Otherwise, only the variant names are reported.
+///
+/// Variants with named fields come first, then single-field tuple variants
+/// after a semicolon.
+macro_rules! enums {
+    ($(
+        $ty:ty {
+            $($named:ident { $($field:ident),* $(,)? }),* $(,)?
+            $(; $($tuple:ident),* $(,)?)?
+        }
+    ),* $(,)?) => {
+        $(
+            impl Walk for $ty {
+                fn walk(&self, other: &Self, d: &mut Differ) {
+                    if self == other {
+                        return;
+                    }
+
+                    $(
+                        if let (Self::$named { .. }, Self::$named { .. }) = (self, other) {
+                            $(
+                                let Self::$named { $field: a, .. } = self else { unreachable!() };
+                                let Self::$named { $field: b, .. } = other else { unreachable!() };
+                                d.field(stringify!($field), a, b);
+                            )*
+                            return;
+                        }
+                    )*
+
+                    $($(
+                        if let (Self::$tuple(a), Self::$tuple(b)) = (self, other) {
+                            a.walk(b, d);
+                            return;
+                        }
+                    )*)?
+
+                    d.push(variant_name(self), variant_name(other));
+                }
+            }
+        )*
+    };
+}
+
+enums!(
+    Date {
+        Manufacture { week, year },
+        ; ModelYear
+    },
+    VideoSignalInterface {
+        Analog {
+            signal_level_standard,
+            video_setup,
+            sync_types,
+            serrations,
+        },
+        Digital {
+            color_bit_depth,
+            supported_interface,
+        },
+        LegacyDigital { dfp_1x_compatible },
+    },
+    SizeOrRatio {
+        ScreenSize {
+            horizontal_cm,
+            vertical_cm,
+        },
+        AspectRatio {
+            horizontal,
+            vertical,
+        },
+    },
+    ColorSupport {
+        ; Type, EncodingFormats
+    },
+    EighteenByteBlock {
+        ; Timing, Display
+    },
+    SyncSignal {
+        Analog {
+            bipolar,
+            with_serrations,
+            sync_mode,
+        },
+        ; Digital
+    },
+    RangeLimitsDesc {
+        LimitsOnly { limits, flexible },
+        GtfSupported { limits },
+        GtfSecondaryCurveSupported {
+            limits,
+            start_break_freq,
+            c2,
+            m,
+            k,
+            j2,
+        },
+        CvtSupported {
+            limits,
+            enhanced_px_clk,
+            cvt_version,
+            maximum_active_pxls_per_line,
+            supported_aspect_ratios,
+            preferred_aspect_ratio,
+            supports_standard_cvt_blanking,
+            supports_reduced_cvt_blanking,
+            supports_h_shrink_scaling,
+            supports_h_stretch_scaling,
+            supports_v_shrink_scaling,
+            supports_v_stretch_scaling,
+            preferred_v_refresh_rate_hz,
+        },
+    },
+    DisplayDescriptor {
+        ColorPointData { w1, w2 },
+        StandardTimingIdentifications { timings },
+        DcmData {
+            version_number,
+            red,
+            green,
+            blue,
+        },
+        Cvt3ByteTimingCodes {
+            version_number,
+            first,
+            second,
+            third,
+            last,
+        },
+        EstablishedTimingsIII {
+            _640x350_85hz,
+            _640x400_85hz,
+            _720x400_85hz,
+            _640x480_85hz,
+            _848x480_60hz,
+            _800x600_85hz,
+            _1024x768_85hz,
+            _1152x864_75hz,
+            _1280x768_60hz_reduced,
+            _1280x768_60hz,
+            _1280x768_75hz,
+            _1280x768_85hz,
+            _1280x960_60hz,
+            _1280x960_85hz,
+            _1280x1024_60hz,
+            _1280x1024_85hz,
+            _1360x768_60hz,
+            _1440x900_60hz_reduced,
+            _1440x900_60hz,
+            _1440x900_75hz,
+            _1440x900_85hz,
+            _1400x1050_60hz_reduced,
+            _1400x1050_60hz,
+            _1400x1050_75hz,
+            _1400x1050_85hz,
+            _1680x1050_60hz_reduced,
+            _1680x1050_60hz,
+            _1680x1050_75hz,
+            _1680x1050_85hz,
+            _1600x1200_60hz,
+            _1600x1200_65hz,
+            _1600x1200_70hz,
+            _1600x1200_75hz,
+            _1600x1200_85hz,
+            _1792x1344_60hz,
+            _1792x1344_75hz,
+            _1856x1392_60hz,
+            _1856x1392_75hz,
+            _1920x1200_60hz_reduced,
+            _1920x1200_60hz,
+            _1920x1200_75hz,
+            _1920x1200_85hz,
+            _1920x1440_60hz,
+            _1920x1440_75hz,
+        },
+        Manufacturer { data },
+        ; ProductSerial, DataString, DisplayRangeLimits, ProductName
+    },
+);
+
+impl Walk for Edid {
+    fn walk(&self, other: &Self, d: &mut Differ) {
+        d.field(
+            "vendor_product_info",
+            &self.vendor_product_info,
+            &other.vendor_product_info,
+        );
+        d.field("version", &self.version, &other.version);
+        d.field(
+            "basic_display_info",
+            &self.basic_display_info,
+            &other.basic_display_info,
+        );
+        d.field(
+            "color_characteristics",
+            &self.color_characteristics,
+            &other.color_characteristics,
+        );
+        d.field(
+            "established_timings",
+            &self.established_timings,
+            &other.established_timings,
+        );
+        d.field(
+            "standard_timings",
+            &self.standard_timings,
+            &other.standard_timings,
+        );
+
+        // the 18-byte blocks are numbered like `edid-decode` does
+        let (a, b) = (
+            &self.eighteen_byte_data_blocks,
+            &other.eighteen_byte_data_blocks,
+        );
+        let a_blocks = core::iter::once(&a.preferred_timing_mode).chain(&a.blocks);
+        let b_blocks = core::iter::once(&b.preferred_timing_mode).chain(&b.blocks);
+        for (i, (a_block, b_block)) in a_blocks.zip(b_blocks).enumerate() {
+            d.at(format!("descriptor {}", i + 1), |d| {
+                a_block.walk(b_block, d);
+            });
+        }
+        d.at("eighteen_byte_data_blocks", |d| {
+            d.field("interpretation", &a.interpretation, &b.interpretation);
+        });
+
+        d.field(
+            "extension_info",
+            &self.extension_info,
+            &other.extension_info,
+        );
+        d.field("checksum", &self.checksum, &other.checksum);
+
+        // `checksum_statuses` is skipped, since the expected checksums
+        // change whenever any other byte does
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checksum::fix_checksums;
+    use crate::patch::EdidPatch;
+
+    fn dell() -> Vec<u8> {
+        raw_edid_by_filename("dell_s2417dg.raw.input")
+    }
+
+    #[test]
+    fn same_edid() {
+        logger();
+        let edid = Edid::new(dell()).unwrap();
+        assert!(diff(&edid, &edid).is_empty());
+        assert!(diff_bytes(&dell(), &dell()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn range_limits_changed() {
+        logger();
+        let mut changed = dell();
+        changed[0x5A + 6] = 75;
+        fix_checksums(&mut changed);
+
+        let found = diff_bytes(&dell(), &changed).unwrap();
+        assert_eq!(
+            found,
+            [
+                Difference {
+                    path: "descriptor 3.limits.max_v_rate_hz".into(),
+                    old: Value::Integer(144),
+                    new: Value::Integer(75),
+                },
+                Difference {
+                    path: "checksum".into(),
+                    old: Value::Integer(0x51),
+                    new: Value::Integer(0x96),
+                },
+            ]
+        );
+        assert_eq!(
+            found[0].to_string(),
+            "descriptor 3.limits.max_v_rate_hz: 144 → 75"
+        );
+    }
+
+    #[test]
+    fn descriptor_kind_changed() {
+        logger();
+        let original = edid_by_filename("1.input");
+        let mut changed = original.clone();
+        changed[0x5A + 3] = 0xFC; // data string -> product name
+        fix_checksums(&mut changed);
+
+        let found = diff(
+            &Edid::new(&original).unwrap(),
+            &Edid::new(&changed).unwrap(),
+        );
+        assert_eq!(
+            found[0],
+            Difference {
+                path: "descriptor 3".into(),
+                old: Value::Variant("DataString".into()),
+                new: Value::Variant("ProductName".into()),
+            }
+        );
+        assert_eq!(
+            found[0].to_string(),
+            "descriptor 3: DataString → ProductName"
+        );
+    }
+
+    #[test]
+    fn extensions() {
+        logger();
+        let stripped = EdidPatch::new().strip_extension(1).apply(&dell()).unwrap();
+
+        let found = diff_bytes(&dell(), &stripped).unwrap();
+        assert!(found.contains(&Difference {
+            path: "extension_info".into(),
+            old: Value::Integer(1),
+            new: Value::Integer(0),
+        }));
+        assert!(found.contains(&Difference {
+            path: "extension 1".into(),
+            old: Value::Variant("block with tag 0x02".into()),
+            new: Value::Missing,
+        }));
+
+        // and a byte inside the extension
+        let mut changed = dell();
+        changed[BLOCK_LEN + 4] ^= 0xFF;
+        fix_checksums(&mut changed);
+
+        let found = diff_extensions(&dell(), &changed);
+        assert_eq!(found.len(), 2, "the byte and the checksum should change");
+        assert_eq!(found[0].path, "extension 1.bytes[0x04..0x05]");
+        assert_eq!(found[1].path, "extension 1.bytes[0x7f..0x80]");
+    }
+
+    /// every structure should be walked, not just compared whole
+    #[test]
+    fn nested_fields() {
+        logger();
+        let a = Edid::new(edid_by_filename(
+            "linuxhw_edid_Digital_MSI_MSIAF82_4B2991D4299A.input",
+        ))
+        .unwrap();
+        let b = Edid::new(edid_by_filename(
+            "linuxhw_edid_Digital_Goldstar_GSM7666_FE91A60D5B6E.input",
+        ))
+        .unwrap();
+
+        let found = diff(&a, &b);
+        assert!(found
+            .iter()
+            .any(|d| d.path == "vendor_product_info.product_code"));
+        assert!(found
+            .iter()
+            .any(|d| d.path.starts_with("color_characteristics.red.")));
+        assert!(found.iter().all(|d| !matches!(d.old, Value::Other(_))));
+    }
+}
@@ -1,61 +1,514 @@
-extern crate alloc;
-use alloc::format;
+#[cfg(feature = "std")]
+extern crate std;
 
-use core::{array::TryFromSliceError, error::Error, fmt::Debug};
-use pisserror::Error;
+use core::{
+    array::TryFromSliceError,
+    error::Error,
+    fmt::{Debug, Display, Formatter},
+};
 
-/// An error that occurred while parsing EDID.
+// note: these implement `Display` by hand, since deriving it with `pisserror`
+// needs `alloc` for `format!`.
+
+/// An error that occurred while parsing EDID, along with where it happened.
+///
+/// When displayed, errors with a location read like `edid-decode`'s:
+///
+/// ```text
+/// Block 0, Base EDID: Descriptor #2 (offset 0x48): Descriptor used an unexpected value within the first five bytes. (bytes: [0, 0, 1, ff, 0])
+/// ```
 #[repr(C)]
 #[must_use]
+#[derive(Clone, Debug)]
+pub struct EdidError {
+    /// What went wrong.
+    pub kind: EdidErrorKind,
+
+    /// Where in the EDID it went wrong. This is empty for errors that aren't
+    /// about a specific part of an EDID, like bad hex.
+    pub context: ErrorContext,
+}
+
+impl EdidError {
+    /// Says the error was caused by the byte at `offset`, counting from the
+    /// start of its section.
+    ///
+    /// Section parsers use this to point at the exact byte. The section's
+    /// start is added on by [`EdidError::within`].
+    pub(crate) fn at(mut self, offset: u16) -> Self {
+        if self.context.section.is_none() && self.context.offset.is_none() {
+            self.context.offset = Some(offset);
+        }
+        self
+    }
+
+    /// Says the error happened in `section` of `block`, where the section
+    /// starts at `start`, counting from the start of the EDID.
+    ///
+    /// Errors keep the first (innermost) location they're given.
+    pub(crate) fn within(mut self, block: u8, section: Section, start: u16) -> Self {
+        if self.context.section.is_none() {
+            let offset = self.context.offset.unwrap_or(0);
+            self.context.block = block;
+            self.context.section = Some(section);
+            self.context.offset = Some(start.saturating_add(offset));
+        }
+        self
+    }
+
+    /// Says the error was caused by the byte at `offset` in `edid`, counting
+    /// from its start. The block and section are worked out from there.
+    pub(crate) fn at_byte(self, edid: &[u8], offset: usize) -> Self {
+        let block = offset / 128;
+        let section = match block {
+            0 => Section::in_base_block(offset as u8),
+            _ => Section::Extension {
+                tag: edid.get(block * 128).copied().unwrap_or_default(),
+            },
+        };
+
+        self.within(
+            u8::try_from(block).unwrap_or(u8::MAX),
+            section,
+            u16::try_from(offset).unwrap_or(u16::MAX),
+        )
+    }
+}
+
+impl Display for EdidError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let ErrorContext {
+            block,
+            section,
+            offset,
+        } = self.context;
+
+        if let Some(section) = section {
+            write!(f, "Block {block}, ")?;
+            match section {
+                // extension blocks are named after their tag
+                Section::Extension { tag } => write!(f, "{}", extension_name(tag))?,
+                _ if block == 0 => f.write_str("Base EDID")?,
+                _ => f.write_str("Extension Block")?,
+            }
+
+            if !matches!(section, Section::Extension { .. }) {
+                write!(f, ": {section}")?;
+            }
+            if let Some(offset) = offset {
+                write!(f, " (offset {offset:#04x})")?;
+            }
+            f.write_str(": ")?;
+        }
+
+        Display::fmt(&self.kind, f)
+    }
+}
+
+impl Error for EdidError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.kind.source()
+    }
+}
+
+impl From<EdidErrorKind> for EdidError {
+    fn from(kind: EdidErrorKind) -> Self {
+        Self {
+            kind,
+            context: ErrorContext::default(),
+        }
+    }
+}
+
+impl From<TryFromSliceError> for EdidError {
+    fn from(value: TryFromSliceError) -> Self {
+        EdidErrorKind::TryFromSlice(value).into()
+    }
+}
+
+/// Where in an EDID an error happened.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ErrorContext {
+    /// The 128-byte block with the error. The base block is `0`.
+    pub block: u8,
+
+    /// The part of the block that was being parsed.
+    pub section: Option<Section>,
+
+    /// The byte that caused the error, counting from the start of the EDID.
+    ///
+    /// When it's not one byte in particular, this is where the section
+    /// starts.
+    pub offset: Option<u16>,
+}
+
+/// A part of an EDID block.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Section {
+    Header,
+    VendorProduct,
+    Version,
+    BasicInfo,
+    Color,
+    EstablishedTimings,
+    StandardTimings,
+    ExtensionCount,
+    Checksum,
+
+    /// One of the four 18-byte descriptors, from `1` to `4`.
+    Descriptor(u8),
+
+    /// An extension block, with the tag in its first byte.
+    Extension {
+        tag: u8,
+    },
+}
+
+impl Display for Section {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        // these are the names `edid-decode` uses
+        match self {
+            Self::Header => f.write_str("Header"),
+            Self::VendorProduct => f.write_str("Vendor & Product Identification"),
+            Self::Version => f.write_str("EDID Structure Version & Revision"),
+            Self::BasicInfo => f.write_str("Basic Display Parameters & Features"),
+            Self::Color => f.write_str("Color Characteristics"),
+            Self::EstablishedTimings => f.write_str("Established Timings I & II"),
+            Self::StandardTimings => f.write_str("Standard Timings"),
+            Self::ExtensionCount => f.write_str("Extension Block Count"),
+            Self::Checksum => f.write_str("Checksum"),
+            Self::Descriptor(n) => write!(f, "Descriptor #{n}"),
+            Self::Extension { tag } => f.write_str(extension_name(*tag)),
+        }
+    }
+}
+
+impl Section {
+    /// The part of the base block that holds the byte at `offset`.
+    fn in_base_block(offset: u8) -> Self {
+        match offset {
+            0x00..=0x07 => Self::Header,
+            0x08..=0x11 => Self::VendorProduct,
+            0x12..=0x13 => Self::Version,
+            0x14..=0x18 => Self::BasicInfo,
+            0x19..=0x22 => Self::Color,
+            0x23..=0x25 => Self::EstablishedTimings,
+            0x26..=0x35 => Self::StandardTimings,
+            0x36..=0x7D => Self::Descriptor((offset - 0x36) / 18 + 1),
+            0x7E => Self::ExtensionCount,
+            _ => Self::Checksum,
+        }
+    }
+}
+
+/// Names an extension block by its tag.
+fn extension_name(tag: u8) -> &'static str {
+    match tag {
+        0x02 => "CTA-861 Extension Block",
+        0x10 => "Video Timing Extension Block",
+        0x20 => "EDID 2.0 Extension Block",
+        0x40 => "Display Information Extension Block",
+        0x50 => "Localized String Extension Block",
+        0x60 => "Microdisplay Interface Extension Block",
+        0x70 => "DisplayID Extension Block",
+        0xA7 | 0xAF | 0xBF => "Display Transfer Characteristics Data Block",
+        0xF0 => "Block Map Extension Block",
+        0xFF => "Manufacturer-Specific Extension Block",
+        _ => "Unknown Extension Block",
+    }
+}
+
+/// The kinds of errors that can happen while parsing EDID.
+#[repr(C)]
 #[non_exhaustive]
-#[derive(Clone, Debug, Error)]
-pub enum EdidError {
-    #[error("The given EDID data isn't long enough.")]
-    TooShort { got: u8, expected: u8 },
+#[derive(Clone, Debug)]
+pub enum EdidErrorKind {
+    TooShort {
+        got: u8,
+        expected: u8,
+    },
+    BadChecksum {
+        stored: u8,
+        expected: u8,
+    },
 
     // header
-    #[error("The EDID header is too short.")]
-    HeaderTooShort { real_len: u8, expected_len: u8 },
-    #[error("The given EDID does not contain the expected EDID header.")]
+    HeaderTooShort {
+        real_len: u8,
+        expected_len: u8,
+    },
     NoHeader,
 
     // id
-    #[error("Failed to parse the vendor ID values into ASCII: {_0:x?}")]
     IdBadValues([u8; 2]),
-    #[error("Failed to find suitable Rust character for given value: {_0}")]
     CharOutOfBounds(u8),
-    #[error("The parser was incorrectly given a `0x00` code.")]
     IdNoZeroesAllowed,
 
     // basic info
-    #[error("During basic info parsing, the given interface bits were not valid: {_0:?}")]
     BasicInfoBadInterface([bool; 4]),
 
     // 18 byte blocks
-    #[error("This EDID contained a reserved descriptor kind byte: `{kind_byte}`.")]
-    DescriptorUsedReservedKind { kind_byte: u8 },
-    #[error("Range limits descriptor found reserved bits set: `{input:x?}`.")]
+    DescriptorUsedReservedKind {
+        kind_byte: u8,
+    },
     DescriptorRangeLimitsUsedReservedBits {
         /// the input byte.
         input: u8,
     },
-    #[error("Range limits descriptor contained a reserved video timing support flag: `{flag}`.")]
-    DescriptorRangeLimitsUsedReservedVTSFlag { flag: u8 },
-    #[error("Range limits descriptor (CVT) contained reserved values.")]
+    DescriptorRangeLimitsUsedReservedVTSFlag {
+        flag: u8,
+    },
     DescriptorRangeLimitsCvtReservedBits,
-    #[error("Descriptor used an unexpected value within the first five bytes. (bytes: {_0:x?})")]
     DescriptorUnexpectedHeader([u8; 5]),
-    #[error("This EDID didn't provide the first CVT in its CVT descriptor.")]
     DescriptorNoFirstCvt,
 
+    // loading
+    LoadNotText,
+    LoadBadHex {
+        character: char,
+    },
+    LoadOddHexLength,
+    LoadNoEdid,
+
+    // patching
+    PatchNoFreeDescriptor,
+    PatchNoSuchExtension {
+        index: u8,
+    },
+    PatchBadProductName,
+    PatchValueOutOfRange {
+        field: &'static str,
+    },
+    PatchPreferredTimingTooFast,
+
+    // icc profiles
+    IccBadPrimaries,
+
+    // modelines
+    ModelineMissingField {
+        field: &'static str,
+    },
+    ModelineBadValue {
+        field: &'static str,
+    },
+
     // misc (logic errors that were noticed in other crates)
-    #[error("An ArrayString had an overflow. Please report this alongside any logs.")]
     ArrayStringError,
-    #[error("Failed to convert slice into array. (err: {_0})")]
-    TryFromSlice(#[from] TryFromSliceError),
-    #[error(
-        "Couldn't represent given number as binary-coded decimal. Please \
-    report this alongside any logs."
-    )]
+    TryFromSlice(TryFromSliceError),
     BcdError,
 }
+
+impl Display for EdidErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::TooShort { .. } => f.write_str("The given EDID data isn't long enough."),
+            Self::BadChecksum { stored, expected } => write!(
+                f,
+                "The block's checksum is {stored:#04x}, but should be {expected:#04x}."
+            ),
+
+            // header
+            Self::HeaderTooShort { .. } => f.write_str("The EDID header is too short."),
+            Self::NoHeader => {
+                f.write_str("The given EDID does not contain the expected EDID header.")
+            }
+
+            // id
+            Self::IdBadValues(values) => write!(
+                f,
+                "Failed to parse the vendor ID values into ASCII: {values:x?}"
+            ),
+            Self::CharOutOfBounds(value) => write!(
+                f,
+                "Failed to find suitable Rust character for given value: {value}"
+            ),
+            Self::IdNoZeroesAllowed => f.write_str("The parser was incorrectly given a `0x00` code."),
+
+            // basic info
+            Self::BasicInfoBadInterface(bits) => write!(
+                f,
+                "During basic info parsing, the given interface bits were not valid: {bits:?}"
+            ),
+
+            // 18 byte blocks
+            Self::DescriptorUsedReservedKind { kind_byte } => write!(
+                f,
+                "This EDID contained a reserved descriptor kind byte: `{kind_byte}`."
+            ),
+            Self::DescriptorRangeLimitsUsedReservedBits { input } => write!(
+                f,
+                "Range limits descriptor found reserved bits set: `{input:x?}`."
+            ),
+            Self::DescriptorRangeLimitsUsedReservedVTSFlag { flag } => write!(
+                f,
+                "Range limits descriptor contained a reserved video timing support flag: `{flag}`."
+            ),
+            Self::DescriptorRangeLimitsCvtReservedBits => {
+                f.write_str("Range limits descriptor (CVT) contained reserved values.")
+            }
+            Self::DescriptorUnexpectedHeader(bytes) => write!(
+                f,
+                "Descriptor used an unexpected value within the first five bytes. (bytes: {bytes:x?})"
+            ),
+            Self::DescriptorNoFirstCvt => {
+                f.write_str("This EDID didn't provide the first CVT in its CVT descriptor.")
+            }
+
+            // loading
+            Self::LoadNotText => f.write_str("The input isn't a raw EDID, and isn't text either."),
+            Self::LoadBadHex { character } => write!(
+                f,
+                "The input contained a character that isn't hex: `{character}`."
+            ),
+            Self::LoadOddHexLength => f.write_str("The input's hex had an odd number of digits."),
+            Self::LoadNoEdid => f.write_str("Couldn't find an EDID in the input."),
+
+            // patching
+            Self::PatchNoFreeDescriptor => {
+                f.write_str("The EDID has no unused descriptor left for the patch to fill.")
+            }
+            Self::PatchNoSuchExtension { index } => {
+                write!(f, "The EDID has no extension block at index {index}.")
+            }
+            Self::PatchBadProductName => f.write_str(
+                "Product names must be up to 13 ASCII characters, without newlines.",
+            ),
+            Self::PatchValueOutOfRange { field } => write!(
+                f,
+                "The patch's `{field}` value can't be represented in this EDID."
+            ),
+            Self::PatchPreferredTimingTooFast => {
+                f.write_str("The preferred timing is faster than the new maximum pixel clock.")
+            }
+
+            // icc profiles
+            Self::IccBadPrimaries => {
+                f.write_str("The EDID's color primaries can't describe a color space.")
+            }
+
+            // modelines
+            Self::ModelineMissingField { field } => {
+                write!(f, "The modeline ended before its `{field}`.")
+            }
+            Self::ModelineBadValue { field } => write!(
+                f,
+                "The modeline's `{field}` isn't valid, or can't be stored in a detailed timing."
+            ),
+
+            // misc
+            Self::ArrayStringError => f.write_str(
+                "An ArrayString had an overflow. Please report this alongside any logs.",
+            ),
+            Self::TryFromSlice(e) => write!(f, "Failed to convert slice into array. (err: {e})"),
+            Self::BcdError => f.write_str(
+                "Couldn't represent given number as binary-coded decimal. Please \
+                report this alongside any logs.",
+            ),
+        }
+    }
+}
+
+impl Error for EdidErrorKind {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::TryFromSlice(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// An error that occurred while loading an EDID from a file.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub enum LoadFileError {
+    Io(std::io::Error),
+    Edid(EdidError),
+}
+
+#[cfg(feature = "std")]
+impl Display for LoadFileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Failed to read the file. (err: {e})"),
+            Self::Edid(e) => write!(f, "Failed to load an EDID from the file. (err: {e})"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for LoadFileError {}
+
+/// An error that occurred while talking to a display over DDC.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DdcError<E: Debug + Display> {
+    Transport(E),
+    BadChecksum {
+        block: u8,
+    },
+    NoHeader,
+
+    /// The display has more extension blocks than the caller made room for.
+    TooManyExtensions {
+        extensions: u8,
+    },
+
+    /// Reading from a segment other than zero needs a combined transfer,
+    /// which the transport doesn't do.
+    UnsupportedCombinedTransfer {
+        segment: u8,
+    },
+
+    // ddc/ci
+    CiBadChecksum,
+    CiMalformedReply([u8; 4]),
+    CiNullReply,
+    CiUnsupportedVcp {
+        code: u8,
+    },
+    CiCapabilitiesTooLong,
+}
+
+impl<E: Debug + Display> Display for DdcError<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Transport(e) => write!(f, "The DDC transfer failed. (err: {e})"),
+            Self::BadChecksum { block } => write!(
+                f,
+                "EDID block {block} had a bad checksum, even after retrying."
+            ),
+            Self::NoHeader => {
+                f.write_str("The display's EDID does not contain the expected EDID header.")
+            }
+            Self::TooManyExtensions { extensions } => write!(
+                f,
+                "The display has {extensions} extension blocks, which is more than there's room for."
+            ),
+            Self::UnsupportedCombinedTransfer { segment } => write!(
+                f,
+                "Reading from EDID segment {segment} needs a combined transfer, \
+                which the DDC transport doesn't support."
+            ),
+
+            // ddc/ci
+            Self::CiBadChecksum => f.write_str("The display's DDC/CI reply had a bad checksum."),
+            Self::CiMalformedReply(bytes) => write!(
+                f,
+                "The display sent a malformed DDC/CI reply. (bytes: {bytes:x?})"
+            ),
+            Self::CiNullReply => {
+                f.write_str("The display replied with a null message. It might be busy.")
+            }
+            Self::CiUnsupportedVcp { code } => write!(
+                f,
+                "The display doesn't support VCP feature `{code:#04x}`."
+            ),
+            Self::CiCapabilitiesTooLong => {
+                f.write_str("The display's capabilities string was too long.")
+            }
+        }
+    }
+}
+
+impl<E: Debug + Display> Error for DdcError<E> {}
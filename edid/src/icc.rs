@@ -0,0 +1,442 @@
+//! Builds ICC display profiles from the EDID's colorimetry.
+//!
+//! These are simple matrix/TRC profiles: the display's primaries (adapted to
+//! D50) and a single gamma curve per channel. That's the same thing colord
+//! and GNOME make with `edid-to-icc`, and it's a decent default for displays
+//! that haven't been calibrated.
+//!
+//! ```edition2021
+//! use liboptic_edid::{icc::{self, IccVersion}, Edid};
+//!
+//! let data = std::fs::read("tests/assets/dell_s2417dg.raw.input")?;
+//! let profile = icc::profile(&Edid::new(&data)?, IccVersion::V4)?;
+//!
+//! assert_eq!(&profile[36..40], b"acsp");
+//! #
+//! # Ok::<(), Box<dyn core::error::Error>>(())
+//! ```
+
+extern crate alloc;
+use alloc::{format, string::String, vec::Vec};
+use num_traits::ToPrimitive as _;
+
+use crate::colorimetry::{self, Matrix3};
+use crate::prelude::internal::*;
+use crate::structures::id::Manufacturer;
+
+/// The gamma to use when the EDID doesn't give one.
+pub const DEFAULT_GAMMA: Decimal = dec!(2.2);
+
+/// The PCS illuminant, D50, in XYZ.
+const D50: [Decimal; 3] = [dec!(0.9642), dec!(1.0), dec!(0.8249)];
+
+/// The Bradford cone response matrix, for chromatic adaptation.
+const BRADFORD: Matrix3 = [
+    [dec!(0.8951), dec!(0.2664), dec!(-0.1614)],
+    [dec!(-0.7502), dec!(1.7135), dec!(0.0367)],
+    [dec!(0.0389), dec!(-0.0685), dec!(1.0296)],
+];
+
+/// Which version of the ICC spec to write.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum IccVersion {
+    /// ICC v2.4, for older software.
+    V2,
+
+    /// ICC v4.3.
+    V4,
+}
+
+/// Builds a display profile for `edid`.
+///
+/// The gamma comes from the basic display info. If that's in an extension
+/// block instead, the first color point descriptor with a gamma is used, and
+/// if there aren't any of those, [`DEFAULT_GAMMA`].
+///
+/// The header's creation date is left as zeroes, since we might not have a
+/// clock.
+#[cfg_attr(feature = "tracing", tracing::instrument(skip(edid)))]
+pub fn profile(edid: &Edid, version: IccVersion) -> Result<Vec<u8>, EdidError> {
+    let colors = &edid.color_characteristics;
+
+    // everything in the profile is relative to d50
+    let bad_primaries =
+        || EdidError::from(EdidErrorKind::IccBadPrimaries).within(0, Section::Color, 0x19);
+    let rgb_to_xyz = colors.rgb_to_xyz().ok_or_else(|| {
+        tracing::error!("Can't build a profile from these primaries: {colors:?}");
+        bad_primaries()
+    })?;
+    let white = colors.white_point.to_xyz().ok_or_else(bad_primaries)?;
+    let chad = adaptation(&white).ok_or_else(bad_primaries)?;
+    let adapted = colorimetry::multiply(&chad, &rgb_to_xyz);
+    let column = |c: usize| [adapted[0][c], adapted[1][c], adapted[2][c]];
+
+    let gamma = gamma(edid);
+    tracing::debug!("Using a gamma of {gamma}.");
+
+    let description = description(edid);
+    let copyright = "No copyright, use freely";
+
+    // v4 says the white point tag is always d50. v2 gives the display's own
+    let wtpt = match version {
+        IccVersion::V2 => white,
+        IccVersion::V4 => D50,
+    };
+
+    let trc = curv(gamma);
+    let tags: [(&[u8; 4], Vec<u8>); 9] = [
+        (b"desc", text(&description, version, true)),
+        (b"cprt", text(copyright, version, false)),
+        (b"wtpt", xyz(&wtpt)),
+        (b"rXYZ", xyz(&column(0))),
+        (b"gXYZ", xyz(&column(1))),
+        (b"bXYZ", xyz(&column(2))),
+        (b"rTRC", trc.clone()),
+        (b"gTRC", trc.clone()),
+        (b"bTRC", trc),
+    ];
+
+    Ok(assemble(&tags, &chad, version))
+}
+
+/// Puts the header, tag table, and tag data together.
+fn assemble(tags: &[(&[u8; 4], Vec<u8>)], chad: &Matrix3, version: IccVersion) -> Vec<u8> {
+    let mut sf32 = Vec::from(*b"sf32\0\0\0\0");
+    for row in chad {
+        for d in row {
+            sf32.extend_from_slice(&s15_fixed16(*d));
+        }
+    }
+
+    let chad_tag = (b"chad", sf32);
+    let all: Vec<_> = tags.iter().chain(core::iter::once(&chad_tag)).collect();
+
+    // the tag table comes right after the header
+    let table_len = 4 + 12 * all.len();
+    let mut data = Vec::new();
+    let mut table = Vec::with_capacity(table_len);
+    table.extend_from_slice(&(all.len() as u32).to_be_bytes());
+    for (signature, tag) in &all {
+        let offset = 128 + table_len + data.len();
+        table.extend_from_slice(*signature);
+        table.extend_from_slice(&(offset as u32).to_be_bytes());
+        table.extend_from_slice(&(tag.len() as u32).to_be_bytes());
+
+        // each tag starts on a four-byte boundary
+        data.extend_from_slice(tag);
+        data.resize(data.len().next_multiple_of(4), 0x00);
+    }
+
+    let size = 128 + table.len() + data.len();
+    let mut profile = Vec::with_capacity(size);
+
+    // header
+    profile.extend_from_slice(&(size as u32).to_be_bytes());
+    profile.extend_from_slice(&[0x00; 4]); // preferred cmm
+    profile.extend_from_slice(match version {
+        IccVersion::V2 => &[0x02, 0x40, 0x00, 0x00],
+        IccVersion::V4 => &[0x04, 0x30, 0x00, 0x00],
+    });
+    profile.extend_from_slice(b"mntr");
+    profile.extend_from_slice(b"RGB ");
+    profile.extend_from_slice(b"XYZ ");
+    profile.extend_from_slice(&[0x00; 12]); // creation date
+    profile.extend_from_slice(b"acsp");
+    profile.extend_from_slice(&[0x00; 4]); // platform
+    profile.extend_from_slice(&[0x00; 4]); // flags
+    profile.extend_from_slice(&[0x00; 4]); // device manufacturer
+    profile.extend_from_slice(&[0x00; 4]); // device model
+    profile.extend_from_slice(&[0x00; 8]); // device attributes
+    profile.extend_from_slice(&[0x00; 4]); // rendering intent: perceptual
+    for d in D50 {
+        profile.extend_from_slice(&s15_fixed16(d));
+    }
+    profile.extend_from_slice(&[0x00; 4]); // creator
+    profile.extend_from_slice(&[0x00; 16]); // profile id (not computed)
+    profile.resize(128, 0x00);
+
+    profile.extend_from_slice(&table);
+    profile.extend_from_slice(&data);
+    profile
+}
+
+/// The Bradford matrix that adapts from `white` to D50.
+fn adaptation(white: &[Decimal; 3]) -> Option<Matrix3> {
+    let source = colorimetry::multiply_vector(&BRADFORD, white);
+    let target = colorimetry::multiply_vector(&BRADFORD, &D50);
+    if source.iter().any(|d| d.is_zero()) {
+        return None;
+    }
+
+    let scale: Matrix3 = core::array::from_fn(|row| {
+        core::array::from_fn(|col| {
+            if row == col {
+                target[row] / source[row]
+            } else {
+                Decimal::ZERO
+            }
+        })
+    });
+
+    let inverse = colorimetry::invert(&BRADFORD)?;
+    Some(colorimetry::multiply(
+        &inverse,
+        &colorimetry::multiply(&scale, &BRADFORD),
+    ))
+}
+
+/// Picks the gamma to use, falling back from the basic display info to
+/// color point descriptors.
+fn gamma(edid: &Edid) -> Decimal {
+    if let Some(gamma) = edid.basic_display_info.reported_gamma {
+        return colorimetry::to_decimal(&gamma);
+    }
+
+    let blocks = &edid.eighteen_byte_data_blocks;
+    let from_white_point = core::iter::once(&blocks.preferred_timing_mode)
+        .chain(&blocks.blocks)
+        .find_map(|block| match block {
+            EighteenByteBlock::Display(DisplayDescriptor::ColorPointData { w1, w2 }) => {
+                w1.gamma.or(w2.gamma)
+            }
+            _ => None,
+        });
+
+    from_white_point.map_or_else(
+        || {
+            tracing::warn!("This EDID doesn't give a gamma. Using {DEFAULT_GAMMA}.");
+            DEFAULT_GAMMA
+        },
+        |gamma| colorimetry::to_decimal(&gamma),
+    )
+}
+
+/// Describes the display, like "Dell Inc. Dell S2417DG".
+fn description(edid: &Edid) -> String {
+    let id = &edid.vendor_product_info;
+    let vendor = match &id.manufacturer_name {
+        Manufacturer::Name(name) => name.as_str(),
+        Manufacturer::Id(id) => id.as_str(),
+    };
+
+    match edid.identity().product_name {
+        Some(name) => format!("{vendor} {name}"),
+        None => format!("{vendor} {:04X}", id.product_code),
+    }
+}
+
+/// Encodes a number as `s15Fixed16Number`.
+fn s15_fixed16(d: Decimal) -> [u8; 4] {
+    let fixed = (d * dec!(65536)).round().to_i32().unwrap_or_default();
+    fixed.to_be_bytes()
+}
+
+/// An `XYZType` tag.
+fn xyz(values: &[Decimal; 3]) -> Vec<u8> {
+    let mut tag = Vec::from(*b"XYZ \0\0\0\0");
+    for d in values {
+        tag.extend_from_slice(&s15_fixed16(*d));
+    }
+    tag
+}
+
+/// A `curveType` tag with a single gamma, as `u8Fixed8Number`.
+fn curv(gamma: Decimal) -> Vec<u8> {
+    let fixed = (gamma * dec!(256)).round().to_u16().unwrap_or(0x0233);
+
+    let mut tag = Vec::from(*b"curv\0\0\0\0");
+    tag.extend_from_slice(&1_u32.to_be_bytes());
+    tag.extend_from_slice(&fixed.to_be_bytes());
+    tag
+}
+
+/// A text tag. v4 uses `multiLocalizedUnicodeType` for everything, but v2
+/// has a special type for descriptions.
+fn text(s: &str, version: IccVersion, is_description: bool) -> Vec<u8> {
+    match version {
+        IccVersion::V4 => {
+            let utf16: Vec<u8> = s.encode_utf16().flat_map(u16::to_be_bytes).collect();
+
+            let mut tag = Vec::from(*b"mluc\0\0\0\0");
+            tag.extend_from_slice(&1_u32.to_be_bytes()); // record count
+            tag.extend_from_slice(&12_u32.to_be_bytes()); // record size
+            tag.extend_from_slice(b"enUS");
+            tag.extend_from_slice(&(utf16.len() as u32).to_be_bytes());
+            tag.extend_from_slice(&28_u32.to_be_bytes()); // offset of the string
+            tag.extend_from_slice(&utf16);
+            tag
+        }
+
+        IccVersion::V2 if is_description => {
+            let mut tag = Vec::from(*b"desc\0\0\0\0");
+            tag.extend_from_slice(&(s.len() as u32 + 1).to_be_bytes());
+            tag.extend_from_slice(s.as_bytes());
+            tag.push(0x00);
+
+            // no unicode or scriptcode versions
+            tag.extend_from_slice(&[0x00; 8]);
+            tag.extend_from_slice(&[0x00; 3]);
+            tag.extend_from_slice(&[0x00; 67]);
+            tag
+        }
+
+        IccVersion::V2 => {
+            let mut tag = Vec::from(*b"text\0\0\0\0");
+            tag.extend_from_slice(s.as_bytes());
+            tag.push(0x00);
+            tag
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// just enough of an icc parser to check our work
+    struct Parsed<'a> {
+        bytes: &'a [u8],
+    }
+
+    impl<'a> Parsed<'a> {
+        fn new(bytes: &'a [u8]) -> Self {
+            let size = u32::from_be_bytes(bytes[0..4].try_into().unwrap());
+            assert_eq!(size as usize, bytes.len(), "the header has the wrong size");
+            assert_eq!(&bytes[36..40], b"acsp");
+            assert_eq!(&bytes[12..24], b"mntrRGB XYZ ");
+            Self { bytes }
+        }
+
+        fn tag(&self, signature: &[u8; 4]) -> &'a [u8] {
+            let count = u32::from_be_bytes(self.bytes[128..132].try_into().unwrap());
+            for i in 0..count as usize {
+                let entry = &self.bytes[132 + i * 12..144 + i * 12];
+                if &entry[0..4] == signature {
+                    let offset = u32::from_be_bytes(entry[4..8].try_into().unwrap()) as usize;
+                    let size = u32::from_be_bytes(entry[8..12].try_into().unwrap()) as usize;
+                    assert_eq!(offset % 4, 0, "tags must be aligned");
+                    return &self.bytes[offset..offset + size];
+                }
+            }
+            panic!("missing tag `{}`", core::str::from_utf8(signature).unwrap());
+        }
+
+        fn xyz(&self, signature: &[u8; 4]) -> [Decimal; 3] {
+            let tag = self.tag(signature);
+            assert_eq!(&tag[0..4], b"XYZ ");
+            core::array::from_fn(|i| {
+                let raw = i32::from_be_bytes(tag[8 + i * 4..12 + i * 4].try_into().unwrap());
+                Decimal::from(raw) / dec!(65536)
+            })
+        }
+    }
+
+    fn close(a: Decimal, b: Decimal) -> bool {
+        (a - b).abs() < dec!(0.0001)
+    }
+
+    #[test]
+    fn dell_s2417dg_v4() {
+        logger();
+        let edid = Edid::new(raw_edid_by_filename("dell_s2417dg.raw.input")).unwrap();
+        let bytes = profile(&edid, IccVersion::V4).unwrap();
+        let icc = Parsed::new(&bytes);
+
+        assert_eq!(&bytes[8..12], &[0x04, 0x30, 0x00, 0x00]);
+        for tag in [
+            b"desc", b"cprt", b"wtpt", b"rXYZ", b"gXYZ", b"bXYZ", b"rTRC", b"gTRC", b"bTRC",
+            b"chad",
+        ] {
+            icc.tag(tag);
+        }
+
+        // the columns should match our matrix, adapted to d50
+        let white = edid.color_characteristics.white_point.to_xyz().unwrap();
+        let expected = colorimetry::multiply(
+            &adaptation(&white).unwrap(),
+            &edid.color_characteristics.rgb_to_xyz().unwrap(),
+        );
+        for (c, tag) in [b"rXYZ", b"gXYZ", b"bXYZ"].iter().enumerate() {
+            let got = icc.xyz(tag);
+            for row in 0..3 {
+                assert!(close(got[row], expected[row][c]), "{tag:?} row {row}");
+            }
+        }
+
+        // and they add up to d50
+        let (r, g, b) = (icc.xyz(b"rXYZ"), icc.xyz(b"gXYZ"), icc.xyz(b"bXYZ"));
+        for i in 0..3 {
+            assert!(close(r[i] + g[i] + b[i], D50[i]));
+        }
+        assert_eq!(icc.xyz(b"wtpt").map(|d| d.round_dp(4)), D50);
+
+        // gamma 2.2 is 0x0233 in u8Fixed8
+        assert_eq!(icc.tag(b"rTRC"), b"curv\0\0\0\0\0\0\0\x01\x02\x33");
+
+        // the description is utf-16
+        let desc = icc.tag(b"desc");
+        assert_eq!(&desc[0..4], b"mluc");
+        let text: Vec<u16> = desc[28..]
+            .chunks(2)
+            .map(|c| u16::from_be_bytes([c[0], c[1]]))
+            .collect();
+        assert_eq!(String::from_utf16(&text).unwrap(), "Dell Inc. Dell S2417DG");
+    }
+
+    #[test]
+    fn v2() {
+        logger();
+        let edid = Edid::new(edid_by_filename("1.input")).unwrap();
+        let bytes = profile(&edid, IccVersion::V2).unwrap();
+        let icc = Parsed::new(&bytes);
+
+        assert_eq!(bytes[8], 0x02);
+        assert_eq!(&icc.tag(b"desc")[0..4], b"desc");
+        assert_eq!(&icc.tag(b"cprt")[0..4], b"text");
+
+        // v2 keeps the display's own white point
+        let white = edid.color_characteristics.white_point.to_xyz().unwrap();
+        let wtpt = icc.xyz(b"wtpt");
+        for i in 0..3 {
+            assert!(close(wtpt[i], white[i]));
+        }
+    }
+
+    /// without a reported gamma, we should go looking in the color points
+    #[test]
+    fn gamma_fallback() {
+        logger();
+        let mut edid = Edid::new(raw_edid_by_filename("dell_s2417dg.raw.input")).unwrap();
+        edid.basic_display_info.reported_gamma = None;
+        assert_eq!(gamma(&edid), DEFAULT_GAMMA);
+
+        let point = crate::structures::desc::color_point::WhitePoint {
+            index_number: 1,
+            coord: edid.color_characteristics.white_point.clone(),
+            gamma: Some(Ratio::new(240, 100)),
+        };
+        edid.eighteen_byte_data_blocks.blocks[2] =
+            EighteenByteBlock::Display(DisplayDescriptor::ColorPointData {
+                w1: point.clone(),
+                w2: point,
+            });
+        assert_eq!(gamma(&edid), dec!(2.4));
+    }
+
+    #[test]
+    fn bad_primaries() {
+        logger();
+        let mut edid = Edid::new(raw_edid_by_filename("dell_s2417dg.raw.input")).unwrap();
+        edid.color_characteristics.green = edid.color_characteristics.red.clone();
+
+        assert!(matches!(
+            profile(&edid, IccVersion::V4),
+            Err(EdidError {
+                kind: EdidErrorKind::IccBadPrimaries,
+                ..
+            })
+        ));
+    }
+}
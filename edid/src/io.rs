@@ -0,0 +1,411 @@
+//! Loads EDIDs from the many formats people tend to store them in.
+//!
+//! Most tools don't hand you the raw bytes of an EDID. Instead, you'll
+//! usually find it as hex text, maybe buried in the output of another
+//! program. `load` figures out which one it was given and digs the EDID out.
+//!
+//! ```edition2021
+//! use liboptic_edid::{io, Edid};
+//!
+//! // this file is the log from an X server. the EDID is somewhere inside!
+//! let log = std::fs::read("tests/assets/formats/Xorg.0.log")?;
+//! assert_eq!(io::detect(&log), Some(io::Format::XorgLog));
+//!
+//! let edid = Edid::new(io::load(&log)?)?;
+//! assert_eq!(edid.checksum, 0x43);
+//! #
+//! # Ok::<(), Box<dyn core::error::Error>>(())
+//! ```
+
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+use alloc::vec::Vec;
+
+use crate::parser::header::EDID_HEADER;
+use crate::prelude::internal::*;
+
+/// The title `edid-decode` places above its hex dump.
+const EDID_DECODE_TITLE: &str = "edid-decode (hex):";
+
+/// The line `edid-decode` uses to split its hex dump from the rest.
+const EDID_DECODE_SEPARATOR: &str = "----------------";
+
+/// Xorg prints this right before dumping an EDID into its log.
+const XORG_TITLE: &str = "EDID (in hex):";
+
+/// `xrandr --verbose` prints this property above each output's EDID.
+const XRANDR_TITLE: &str = "EDID:";
+
+/// A format that an EDID can be loaded from.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Format {
+    /// The raw bytes, like those in the Linux `sysfs`.
+    Raw,
+
+    /// Hex text. Bytes may be split by whitespace or commas, and may each
+    /// have a `0x` prefix.
+    Hex,
+
+    /// The output of `edid-decode`, which begins with a hex dump.
+    ///
+    /// The `.input` files from the `linuxhw/EDID` repo also look like this.
+    EdidDecode,
+
+    /// The output of `xrandr --verbose`, which has an `EDID:` property for
+    /// each connected output.
+    Xrandr,
+
+    /// An X server log (`Xorg.0.log`), where each EDID is dumped after an
+    /// `EDID (in hex):` line.
+    XorgLog,
+}
+
+/// Guesses the format of the given `input`.
+///
+/// Returns `None` when the input is neither a raw EDID nor text.
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+pub fn detect(input: &[u8]) -> Option<Format> {
+    if input.starts_with(&EDID_HEADER) {
+        return Some(Format::Raw);
+    }
+
+    let text = core::str::from_utf8(input).ok()?;
+
+    let format = if text.contains(EDID_DECODE_TITLE) {
+        Format::EdidDecode
+    } else if text.contains(XORG_TITLE) {
+        Format::XorgLog
+    } else if text.lines().any(|line| line.trim() == XRANDR_TITLE) {
+        Format::Xrandr
+    } else {
+        Format::Hex
+    };
+
+    tracing::debug!("detected input format: {format:?}");
+    Some(format)
+}
+
+/// Loads the first EDID found in the given `input`, detecting its format.
+///
+/// For formats that may hold more than one EDID, like `xrandr --verbose`
+/// output, use `load_all` instead.
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+pub fn load(input: &[u8]) -> Result<Vec<u8>, EdidError> {
+    load_all(input)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| EdidErrorKind::LoadNoEdid.into())
+}
+
+/// Reads the file at `path`, then loads the first EDID inside it.
+///
+/// This is `load` for files on disk. It's only available with the `std`
+/// feature.
+#[cfg(feature = "std")]
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+pub fn load_file<P: AsRef<std::path::Path>>(
+    path: P,
+) -> Result<Vec<u8>, crate::error::LoadFileError> {
+    let bytes = std::fs::read(path).map_err(crate::error::LoadFileError::Io)?;
+    load(&bytes).map_err(crate::error::LoadFileError::Edid)
+}
+
+/// Loads every EDID found in the given `input`, detecting its format.
+///
+/// The EDIDs are returned in the order they appeared. Each of them starts
+/// with the EDID header, but hasn't been parsed yet.
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+pub fn load_all(input: &[u8]) -> Result<Vec<Vec<u8>>, EdidError> {
+    let (format, edids) = extract(input)?;
+
+    if edids.is_empty() {
+        tracing::error!("the input was {format:?}, but it didn't contain any EDIDs.");
+        return Err(EdidErrorKind::LoadNoEdid.into());
+    }
+
+    // everything we found should at least look like an edid
+    if let Some(bad) = edids.iter().find(|edid| !edid.starts_with(&EDID_HEADER)) {
+        tracing::error!(
+            "found hex that doesn't start with an EDID header. (start: {:x?})",
+            &bad[..bad.len().min(EDID_HEADER.len())]
+        );
+        return Err(EdidErrorKind::LoadNoEdid.into());
+    }
+
+    Ok(edids)
+}
+
+/// Pulls the bytes out of `input` without checking that they're EDIDs.
+///
+/// The tests use this directly, since some of our assets are broken on
+/// purpose.
+pub(crate) fn extract(input: &[u8]) -> Result<(Format, Vec<Vec<u8>>), EdidError> {
+    let format = detect(input).ok_or(EdidErrorKind::LoadNotText)?;
+
+    // raw input doesn't need any work
+    if format == Format::Raw {
+        return Ok((format, alloc::vec![input.to_vec()]));
+    }
+
+    // we checked this is text while detecting it
+    let text = core::str::from_utf8(input).map_err(|_| EdidErrorKind::LoadNotText)?;
+
+    let edids = match format {
+        Format::Raw => unreachable!("raw input returns above"),
+        Format::Hex => alloc::vec![decode_hex(text)?],
+        Format::EdidDecode => edid_decode_sections(text)
+            .map(decode_hex)
+            .collect::<Result<Vec<_>, _>>()?,
+        Format::Xrandr => titled_sections(text, |line| line.trim() == XRANDR_TITLE, str::trim)
+            .iter()
+            .map(|hex| decode_hex(hex))
+            .collect::<Result<Vec<_>, _>>()?,
+        Format::XorgLog => titled_sections(text, |line| line.contains(XORG_TITLE), xorg_message)
+            .iter()
+            .map(|hex| decode_hex(hex))
+            .collect::<Result<Vec<_>, _>>()?,
+    };
+
+    Ok((format, edids))
+}
+
+/// Finds the hex dumps at the top of each `edid-decode` output in `text`.
+fn edid_decode_sections(text: &str) -> impl Iterator<Item = &str> {
+    text.split(EDID_DECODE_TITLE)
+        .skip(1)
+        .map(|rest| rest.split(EDID_DECODE_SEPARATOR).next().unwrap_or_default())
+}
+
+/// Collects the hex lines following each line where `is_title` is true.
+///
+/// `message` grabs the part of a line that might be hex. A section ends at
+/// the first line that isn't.
+fn titled_sections(
+    text: &str,
+    is_title: impl Fn(&str) -> bool,
+    message: impl Fn(&str) -> &str,
+) -> Vec<alloc::string::String> {
+    let mut sections = Vec::new();
+    let mut lines = text.lines();
+
+    while lines.by_ref().any(&is_title) {
+        let section = lines
+            .clone()
+            .map(&message)
+            .take_while(|msg| !msg.is_empty() && msg.bytes().all(|b| b.is_ascii_hexdigit()))
+            .collect::<alloc::string::String>();
+
+        sections.push(section);
+    }
+
+    sections
+}
+
+/// Removes the timestamp and module prefix from an Xorg log line.
+///
+/// These look like `[    21.368] (II) modeset(0): 00ffffffffffff00`.
+fn xorg_message(line: &str) -> &str {
+    line.rsplit_once(':')
+        .map_or(line, |(_prefix, message)| message)
+        .trim()
+}
+
+/// Decodes hex text into bytes.
+///
+/// Whitespace, commas, and `0x` prefixes are all ignored.
+fn decode_hex(text: &str) -> Result<Vec<u8>, EdidError> {
+    let chars = text
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .map(|word| {
+            word.strip_prefix("0x")
+                .or_else(|| word.strip_prefix("0X"))
+                .unwrap_or(word)
+        })
+        .flat_map(str::chars);
+
+    // errors point at the edid byte that couldn't be decoded
+    let mut bytes = Vec::new();
+    let mut high = None;
+    for c in chars {
+        let Some(digit) = c.to_digit(16).map(|d| d as u8) else {
+            tracing::error!("found a character that isn't hex: `{c}`");
+            return Err(EdidError::from(EdidErrorKind::LoadBadHex { character: c })
+                .at_byte(&bytes, bytes.len()));
+        };
+
+        match high.take() {
+            Some(high) => bytes.push((high << 4) | digit),
+            None => high = Some(digit),
+        }
+    }
+
+    if high.is_some() {
+        tracing::error!(
+            "the hex had an odd number of digits. (len: {})",
+            bytes.len() * 2 + 1
+        );
+        return Err(EdidError::from(EdidErrorKind::LoadOddHexLength).at_byte(&bytes, bytes.len()));
+    }
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use alloc::{format, string::String};
+
+    use super::*;
+
+    fn asset(name: &str) -> Vec<u8> {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/assets/");
+        std::fs::read(format!("{path}{name}")).unwrap()
+    }
+
+    #[test]
+    fn raw() {
+        logger();
+        let raw = raw_edid_by_filename("dell_s2417dg.raw.input");
+
+        assert_eq!(detect(&raw), Some(Format::Raw));
+        assert_eq!(load(&raw).unwrap(), raw);
+    }
+
+    #[test]
+    fn hex() {
+        logger();
+        let raw = raw_edid_by_filename("dell_s2417dg.raw.input");
+
+        let plain = raw.iter().map(|b| format!("{b:02x}")).collect::<String>();
+        let spaced = raw.iter().map(|b| format!("{b:02X} ")).collect::<String>();
+        let c_array = raw
+            .iter()
+            .map(|b| format!("0x{b:02x}, "))
+            .collect::<String>();
+
+        for text in [plain, spaced, c_array] {
+            assert_eq!(detect(text.as_bytes()), Some(Format::Hex));
+            assert_eq!(load(text.as_bytes()).unwrap(), raw);
+        }
+    }
+
+    #[test]
+    fn linuxhw_input() {
+        logger();
+        let text = asset("linuxhw_edid_Digital_MSI_MSIAF82_4B2991D4299A.input");
+
+        assert_eq!(detect(&text), Some(Format::Hex));
+        assert_eq!(
+            load(&text).unwrap(),
+            edid_by_filename("linuxhw_edid_Digital_MSI_MSIAF82_4B2991D4299A.input")
+        );
+    }
+
+    #[test]
+    fn edid_decode() {
+        logger();
+        let text = asset("formats/edid_decode.txt");
+
+        assert_eq!(detect(&text), Some(Format::EdidDecode));
+        assert_eq!(
+            load(&text).unwrap(),
+            raw_edid_by_filename("dell_s2417dg.raw.input")
+        );
+    }
+
+    #[test]
+    fn xrandr() {
+        logger();
+        let text = asset("formats/xrandr_verbose.txt");
+        assert_eq!(detect(&text), Some(Format::Xrandr));
+
+        // there are two connected outputs, each with an edid
+        let edids = load_all(&text).unwrap();
+        assert_eq!(
+            edids,
+            [
+                edid_by_filename("1.input"),
+                raw_edid_by_filename("dell_s2417dg.raw.input")
+            ]
+        );
+        assert_eq!(load(&text).unwrap(), edids[0]);
+    }
+
+    #[test]
+    fn xorg_log() {
+        logger();
+        let text = asset("formats/Xorg.0.log");
+
+        assert_eq!(detect(&text), Some(Format::XorgLog));
+        assert_eq!(load(&text).unwrap(), edid_by_filename("1.input"));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn file() {
+        logger();
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/assets/formats/Xorg.0.log"
+        );
+        assert_eq!(load_file(path).unwrap(), edid_by_filename("1.input"));
+
+        assert!(matches!(
+            load_file("/this/path/does/not/exist"),
+            Err(crate::error::LoadFileError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn not_an_edid() {
+        logger();
+        assert!(matches!(
+            load(b"hello there"),
+            Err(EdidError {
+                kind: EdidErrorKind::LoadBadHex { character: 'h' },
+                ..
+            })
+        ));
+        assert!(matches!(
+            load(b"00 11 2"),
+            Err(EdidError {
+                kind: EdidErrorKind::LoadOddHexLength,
+                ..
+            })
+        ));
+        // the error points at the byte that wasn't hex
+        let e = load(b"00 ff ff ff ff ff ff 00 1g").unwrap_err();
+        assert!(matches!(
+            e.kind,
+            EdidErrorKind::LoadBadHex { character: 'g' }
+        ));
+        assert_eq!(e.context.section, Some(Section::VendorProduct));
+        assert_eq!(e.context.offset, Some(0x08));
+
+        assert!(matches!(
+            load(b"00 11 22 33"),
+            Err(EdidError {
+                kind: EdidErrorKind::LoadNoEdid,
+                ..
+            })
+        ));
+        assert!(matches!(
+            load(b"\tEDID:\n"),
+            Err(EdidError {
+                kind: EdidErrorKind::LoadNoEdid,
+                ..
+            })
+        ));
+        assert!(matches!(
+            load(&[0xff, 0xfe]),
+            Err(EdidError {
+                kind: EdidErrorKind::LoadNotText,
+                ..
+            })
+        ));
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! A library crate to parse EDID information.
 //!
-//! Currently, this uses EDID v1.4 and does not account for any incompatbilities with earlier versions. However, after running against around 100k EDIDs, there are only about 5% failing to parse, and no panics.
+//! This targets EDID v1.4, but also reads v1.0 to v1.3 using the rules of their own revision where those differ. Structures affected by this record which `Interpretation` was applied. After running against around 100k EDIDs, there are only about 5% failing to parse, and no panics.
 //!
 //! ## Usage
 //!
@@ -21,16 +21,112 @@
 //! # Ok::<(), Box<dyn core::error::Error>>(())
 //! ```
 //!
+//! To print it, `Edid` and each of its sections implement `Display` with a short summary, like `1920x1080@60Hz (16:9)` for a standard timing. Printing the whole `Edid` gives one line per section.
+//!
+//! If your EDID is stuck in some text, like hex or the output of `edid-decode`, `xrandr --verbose`, or an Xorg log, the `io` module can dig it out for you:
+//!
+//! ```edition2021
+//! # #[cfg(feature = "alloc")] {
+//! use liboptic_edid::{io, Edid};
+//!
+//! let text = std::fs::read("tests/assets/formats/xrandr_verbose.txt")?;
+//! let parsed_edid = Edid::new(io::load(&text)?)?;
+//! # }
+//! #
+//! # Ok::<(), Box<dyn core::error::Error>>(())
+//! ```
+//!
+//! With the `std` feature, `io::load_file` does the same for files on disk. And on Linux, the `linux` feature adds a module that lists the machine's display connectors alongside their EDIDs:
+//!
+//! ```edition2021,ignore
+//! use liboptic_edid::linux;
+//!
+//! for connector in linux::connectors()? {
+//!     println!("{}: {:?}", connector.name, connector.edid);
+//! }
+//! ```
+//!
+//! Parsing doesn't fail on a bad checksum, since plenty of displays ship with one. `Edid::checksum_valid()` tells you if the base block's was wrong, `Edid::checksum_statuses` has every block's, and the `checksum` module can fix them. To refuse them instead, pass `ParseOptions { reject_bad_checksum: true }` to `Edid::new_with_options()`.
+//!
+//! In hot-plug paths, `EdidView` skips building the whole tree. It borrows the bytes and only parses the sections you ask for, like the manufacturer and product code. Its `validate()` only checks the structure, like the header, checksums, and descriptor tags, so it's cheap enough to sweep through large EDID collections.
+//!
+//! On boards without a DRM driver, the `ddc` module reads the EDID straight from the display's I²C bus instead. It works over any `ddc::DdcTransport`, and the `linux` feature provides one for `/dev/i2c-N` devices. Over the same bus, `ddc::ci` speaks DDC/CI to change settings like brightness, contrast, and the input source.
+//!
+//! `Edid::all_standard_timings()` walks every standard timing, both the eight in their own section and the six in each 0xFA descriptor. Their heights depend on the EDID revision, since the 16:10 bits meant 1:1 before v1.3, so `STiming::vertical_active()` takes the revision's `Interpretation`.
+//!
+//! Each `DetailedTimingDefinition` can also work out its own totals, back porches, sync polarities, and aspect ratio. Its `refresh_rate()` and `h_freq_khz()` are exact `num_rational::Ratio`s, so a 59.94 Hz mode doesn't get rounded into a 60 Hz one.
+//!
+//! For X11 and KMS, the `modeline` module turns detailed timings into `xorg.conf` modelines (optionally with a `cvt`-style comment) and `DrmModeInfo`s, which match the kernel's `drm_mode_modeinfo`. Both convert back into detailed timings, too.
+//!
+//! To fix a broken EDID, `patch::EdidPatch` can swap out its timings, range limits, and product name, or strip its extension blocks. The result has correct checksums, so Linux can load it with `drm.edid_firmware=`.
+//!
+//! To remember settings for a specific display, key them on `Edid::identity()`. Its `hash()` stays the same across runs and machines, and the identity's docs explain when two displays can't be told apart.
+//!
+//! For color management, the `colorimetry` module turns the display's chromaticities into RGB/XYZ matrices, finds its white point's color temperature, and measures how much of sRGB, DCI-P3, Adobe RGB, or BT.2020 it covers.
+//!
+//! The `icc` module goes a step further, building an ICC v4 (or v2) display profile from those chromaticities and the reported gamma, like `edid-to-icc` does.
+//!
+//! For HiDPI setups, `Edid::physical_size()` works out the display's size from the (often contradictory) places it's given, then suggests a DPI and scale factor for each mode.
+//!
+//! When an EDID changes (after a firmware update, or behind a KVM), `diff()` lists each field that's different between the two, like `descriptor 3.limits.max_v_rate_hz: 144 → 75`.
+//!
+//! Before sharing an EDID in a bug report, run it through `anonymize()`. It removes the display's serial numbers, and `AnonymizeOptions` can also remove its week of manufacture, data strings, and container IDs.
+//!
+//! ## Serialization
+//!
+//! With the `serde` feature, `Edid` and every type under `structures` implement `Serialize` and `Deserialize`. The JSON shape is stable:
+//!
+//! - Structs are objects whose keys are the Rust field names.
+//! - Enums are externally tagged. Unit variants are strings (`"V1_4"`), and others are single-key objects (`{ "Name": "Dell Inc." }`).
+//! - `Option`s are `null` when absent.
+//! - Fractions, like the gamma and chromaticity coordinates, are `num_rational::Ratio`s in Rust. They're strings holding their exact decimal value (`"2.2"`), so they don't lose precision.
+//! - Strings like the product name and serial are plain JSON strings.
+//! - Standard timings are arrays in Rust, but each slot keeps its own key: `st1` to `st8`, and `_9` to `_14` in a Standard Timing Identifications descriptor.
+//!
 //! ## Compatibility
 //!
-//! This crate is `#![no_std]` but still depends on `alloc` while `pisserror` does. When I get around to fixing that, that requirement will be dropped. :)
+//! This crate is `#![no_std]`. Anything that needs an allocator, like `io`, `patch`, `diff`, and `anonymize`, sits behind the `alloc` feature, which is on by default. The `std` and `serde` features turn it on, too.
+//!
+//! The color math in `colorimetry`, `icc`, `Edid::physical_size`, and `DcmCurve::evaluate` uses `rust_decimal`, so it sits behind the `decimal` feature (which needs `alloc`). Logging through `tracing` is behind the `tracing` feature. Both are on by default.
+//!
+//! With `--no-default-features`, nothing links the `alloc` crate, so the library builds for bare-metal targets like `thumbv7em-none-eabihf` without a global allocator. Parsing doesn't allocate, and `ddc::fetch_blocks` reads an EDID into a fixed number of blocks.
 
 #![no_std]
 
+#[cfg(feature = "alloc")]
+mod anonymize;
+pub mod checksum;
+#[cfg(feature = "decimal")]
+pub mod colorimetry;
+pub mod ddc;
+#[cfg(feature = "alloc")]
+pub mod diff;
 pub mod error;
+#[cfg(feature = "decimal")]
+pub mod icc;
+// the tests load their hex assets with this, even without `alloc`
+#[cfg(any(feature = "alloc", test))]
+pub mod io;
+#[cfg(feature = "linux")]
+pub mod linux;
+#[cfg(not(feature = "tracing"))]
+mod log;
+pub mod modeline;
 mod parser;
+#[cfg(feature = "alloc")]
+pub mod patch;
 mod prelude;
 pub mod structures;
+mod view;
+
+#[cfg(feature = "alloc")]
+pub use anonymize::{anonymize, AnonymizeOptions};
+pub use checksum::ChecksumStatus;
+#[cfg(feature = "alloc")]
+pub use diff::diff;
+pub use view::{EdidView, ExtensionBlock};
+
+use arrayvec::ArrayVec;
 
 use crate::prelude::internal::*;
 
@@ -44,6 +140,7 @@ pub const LATEST_SUPPORTED_REVISION: u8 = 0x04;
 /// The base EDID structure.
 #[repr(C)]
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Edid {
     /// Info about the product vendor.
     pub vendor_product_info: id::VendorProductId,
@@ -75,7 +172,28 @@ pub struct Edid {
     pub extension_info: u8,
 
     /// Some value that makes the EDID's checksum be 0x00.
+    ///
+    /// This is the byte that was stored, even when it's wrong. See
+    /// [`Edid::checksum_valid`].
     pub checksum: u8,
+
+    /// The checksum of the base block and each extension block, in order.
+    ///
+    /// A trailing partial block is skipped.
+    pub checksum_statuses: ArrayVec<ChecksumStatus, { checksum::MAX_BLOCKS }>,
+}
+
+/// Settings for [`Edid::new_with_options`].
+#[repr(C)]
+#[derive(Clone, Debug, Default, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParseOptions {
+    /// Fails with [`EdidErrorKind::BadChecksum`] when the base block or any
+    /// extension block has the wrong checksum.
+    ///
+    /// This is off by default, since many displays ship with broken
+    /// checksums and are otherwise fine.
+    pub reject_bad_checksum: bool,
 }
 
 impl Edid {
@@ -100,4 +218,298 @@ impl Edid {
     pub fn new<Slice: AsRef<[u8]>>(edid_data: Slice) -> Result<Self, EdidError> {
         parser::parse(edid_data.as_ref())
     }
+
+    /// Like [`Edid::new`], but with the given [`ParseOptions`].
+    ///
+    /// ```edition2021
+    /// use liboptic_edid::{Edid, ParseOptions};
+    ///
+    /// let mut data = std::fs::read("tests/assets/dell_s2417dg.raw.input")?;
+    /// data[0x7F] ^= 0xFF;
+    ///
+    /// let options = ParseOptions {
+    ///     reject_bad_checksum: true,
+    /// };
+    /// assert!(Edid::new(&data).is_ok());
+    /// assert!(Edid::new_with_options(&data, options).is_err());
+    /// #
+    /// # Ok::<(), Box<dyn core::error::Error>>(())
+    /// ```
+    pub fn new_with_options<Slice: AsRef<[u8]>>(
+        edid_data: Slice,
+        options: ParseOptions,
+    ) -> Result<Self, EdidError> {
+        parser::parse_with_options(edid_data.as_ref(), &options)
+    }
+
+    /// Whether the base block's checksum is correct.
+    ///
+    /// Use [`Edid::checksum_statuses`] to check the extension blocks too.
+    pub fn checksum_valid(&self) -> bool {
+        self.checksum_status().is_valid()
+    }
+
+    /// The base block's stored and expected checksums.
+    ///
+    /// An `Edid` built by hand, without any statuses, trusts its stored
+    /// checksum.
+    pub fn checksum_status(&self) -> ChecksumStatus {
+        self.checksum_statuses
+            .first()
+            .copied()
+            .unwrap_or(ChecksumStatus {
+                stored: self.checksum,
+                expected: self.checksum,
+            })
+    }
+
+    /// Collects what this EDID says about which physical display it came
+    /// from.
+    ///
+    /// See [`DisplayIdentity`](identity::DisplayIdentity) for when two
+    /// displays can end up with the same identity.
+    ///
+    /// ```edition2021
+    /// use liboptic_edid::Edid;
+    ///
+    /// let data = std::fs::read("tests/assets/dell_s2417dg.raw.input")?;
+    /// let identity = Edid::new(&data)?.identity();
+    ///
+    /// assert_eq!(identity.manufacturer_id.as_str(), "DEL");
+    /// assert!(identity.has_serial());
+    /// #
+    /// # Ok::<(), Box<dyn core::error::Error>>(())
+    /// ```
+    pub fn identity(&self) -> identity::DisplayIdentity {
+        let id = &self.vendor_product_info;
+
+        // the serial and name can each be in any of the descriptors
+        let text = |want: fn(&DisplayDescriptor) -> Option<&desc::ByteStr13>| {
+            core::iter::once(&self.eighteen_byte_data_blocks.preferred_timing_mode)
+                .chain(&self.eighteen_byte_data_blocks.blocks)
+                .find_map(|block| match block {
+                    EighteenByteBlock::Display(d) => want(d),
+                    EighteenByteBlock::Timing(_) => None,
+                })
+                .map(|s| s.trim())
+                .filter(|s| !s.is_empty())
+                .and_then(|s| desc::ByteStr13::from(s).ok())
+        };
+
+        identity::DisplayIdentity {
+            manufacturer_id: id.manufacturer_id,
+            product_code: id.product_code,
+            serial_number: id.serial_number,
+            serial_string: text(|d| match d {
+                DisplayDescriptor::ProductSerial(s) => Some(s),
+                _ => None,
+            }),
+            product_name: text(|d| match d {
+                DisplayDescriptor::ProductName(s) => Some(s),
+                _ => None,
+            }),
+            date: id.date.clone(),
+        }
+    }
+
+    /// Every standard timing the base block lists, in order.
+    ///
+    /// That's the eight in their own section, then up to six more in each
+    /// Standard Timing Identifications (0xFA) descriptor. Unused slots are
+    /// skipped. To find their heights, pass
+    /// [`self.standard_timings.interpretation`](std_timings::StandardTimings::interpretation)
+    /// to [`STiming::vertical_active`](std_timings::STiming::vertical_active).
+    pub fn all_standard_timings(&self) -> impl Iterator<Item = &std_timings::STiming> + '_ {
+        let blocks = &self.eighteen_byte_data_blocks;
+        let descriptors = core::iter::once(&blocks.preferred_timing_mode)
+            .chain(&blocks.blocks)
+            .filter_map(|block| match block {
+                EighteenByteBlock::Display(DisplayDescriptor::StandardTimingIdentifications {
+                    timings,
+                }) => Some(timings.iter().flatten()),
+                _ => None,
+            })
+            .flatten();
+
+        self.standard_timings.iter().chain(descriptors)
+    }
+
+    /// Finds the display's physical size, along with the DPI and a
+    /// suggested scale factor for each of its detailed timings.
+    ///
+    /// EDIDs give the size in a few places, and they're often wrong. See
+    /// [`PhysicalSize::rejected`](size::PhysicalSize::rejected) for any that
+    /// were ignored.
+    ///
+    /// ```edition2021
+    /// use liboptic_edid::Edid;
+    ///
+    /// let data = std::fs::read("tests/assets/dell_s2417dg.raw.input")?;
+    /// let size = Edid::new(&data)?.physical_size().unwrap();
+    ///
+    /// assert_eq!(size.diagonal_in.to_string(), "23.8");
+    /// assert_eq!(size.modes[0].fractional_scale.to_string(), "1.25");
+    /// #
+    /// # Ok::<(), Box<dyn core::error::Error>>(())
+    /// ```
+    #[cfg(feature = "decimal")]
+    pub fn physical_size(&self) -> Option<size::PhysicalSize> {
+        size::PhysicalSize::from_edid(self)
+    }
+}
+
+impl core::fmt::Display for Edid {
+    /// Writes one line per section, named like `edid-decode` names them:
+    ///
+    /// ```text
+    /// Vendor & Product Identification: DEL (Dell Inc.), product 41191, serial 1, week 28 of 2018
+    /// EDID Structure Version & Revision: 1.4
+    /// ...
+    /// Checksum: 0x51
+    /// ```
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        writeln!(
+            f,
+            "{}: {}",
+            Section::VendorProduct,
+            self.vendor_product_info
+        )?;
+        writeln!(f, "{}: {}", Section::Version, self.version)?;
+        writeln!(f, "{}: {}", Section::BasicInfo, self.basic_display_info)?;
+        writeln!(f, "{}: {}", Section::Color, self.color_characteristics)?;
+        writeln!(
+            f,
+            "{}: {}",
+            Section::EstablishedTimings,
+            self.established_timings
+        )?;
+        writeln!(f, "{}: {}", Section::StandardTimings, self.standard_timings)?;
+        writeln!(f, "{}", self.eighteen_byte_data_blocks)?;
+        writeln!(f, "Extension blocks: {}", self.extension_info)?;
+
+        write!(f, "{}: {:#04x}", Section::Checksum, self.checksum)?;
+        let status = self.checksum_status();
+        if !status.is_valid() {
+            write!(f, " (should be {:#04x})", status.expected)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    extern crate std;
+
+    use std::string::ToString as _;
+
+    use crate::prelude::internal::*;
+
+    /// every asset should come back from json unchanged
+    #[test]
+    fn serde_round_trip_assets() {
+        logger();
+        let assets = [
+            raw_edid_by_filename("dell_s2417dg.raw.input"),
+            edid_by_filename("1.input"),
+            edid_by_filename("linuxhw_edid_Analog_AOC_AOC0320_455954E7CA14.input"),
+            edid_by_filename("linuxhw_edid_Digital_BOE_BOE07AF_BD22D8FDF96B.input"),
+            edid_by_filename("linuxhw_edid_Digital_Goldstar_GSM7666_FE91A60D5B6E.input"),
+            edid_by_filename("linuxhw_edid_Digital_Lenovo_LEN0017_3AF8B597ECB9.input"),
+            edid_by_filename("linuxhw_edid_Digital_MSI_MSIAF82_4B2991D4299A.input"),
+            edid_by_filename("linuxhw_edid_Digital_Others_HJW0000_F67302F2ED4C.input"),
+            edid_by_filename("linuxhw_edid_EDID_Digital_Samsung_SAM02E3_2C47316EFF13.input"),
+            edid_by_filename("linuxhw_edid_EDID_Digital_Sony_SNY05FA_93D328459FF6.input"),
+        ];
+
+        for asset in assets {
+            let edid = Edid::new(&asset).unwrap();
+            let json = serde_json::to_string(&edid).unwrap();
+            let back: Edid = serde_json::from_str(&json).unwrap();
+
+            assert_eq!(edid, back);
+        }
+    }
+
+    /// make sure the documented json shape doesn't change under us
+    #[test]
+    fn serde_json_shape() {
+        logger();
+        let input = raw_edid_by_filename("dell_s2417dg.raw.input");
+        let edid = Edid::new(&input).unwrap();
+        let json = serde_json::to_value(&edid).unwrap();
+
+        // enums are externally tagged
+        assert_eq!(
+            json["vendor_product_info"]["manufacturer_name"],
+            serde_json::json!({ "Name": "Dell Inc." })
+        );
+        assert_eq!(
+            json["basic_display_info"]["screen_size_or_aspect_ratio"],
+            serde_json::json!({ "ScreenSize": { "horizontal_cm": 53, "vertical_cm": 30 } })
+        );
+
+        // ratios are exact decimal strings
+        assert_eq!(json["basic_display_info"]["reported_gamma"], "2.2");
+        assert_eq!(json["color_characteristics"]["white_point"]["x"], "0.3125");
+        assert_eq!(json["color_characteristics"]["red"]["x"], "0.6396484375");
+
+        // missing values are `null`
+        assert!(json["standard_timings"]["st1"].is_null());
+
+        // and unit variants are just their names
+        assert_eq!(json["basic_display_info"]["interpretation"], "V1_4");
+        assert_eq!(json["version"]["revision"].to_string(), "4");
+    }
+
+    /// older json wrote some ratios with trailing zeros, like `"2.20"`
+    #[test]
+    fn serde_ratio_strings() {
+        logger();
+        let edid = Edid::new(raw_edid_by_filename("dell_s2417dg.raw.input")).unwrap();
+
+        let mut json = serde_json::to_value(&edid.basic_display_info).unwrap();
+        json["reported_gamma"] = "2.20".into();
+        let back: BasicDisplayInfo = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(back, edid.basic_display_info);
+
+        // but not anything that won't fit
+        json["reported_gamma"] = "70000".into();
+        assert!(serde_json::from_value::<BasicDisplayInfo>(json).is_err());
+    }
+
+    /// standard timings keep one key per slot, even though they're arrays
+    #[test]
+    fn serde_standard_timing_slots() {
+        logger();
+        let edid = Edid::new(edid_by_filename(
+            "linuxhw_edid_Digital_Others_HJW0000_F67302F2ED4C.input",
+        ))
+        .unwrap();
+        let json = serde_json::to_value(&edid).unwrap();
+
+        let slot = |refresh: u8| {
+            serde_json::json!({
+                "horizontal_addr_pixel_ct": 1152,
+                "aspect_ratio": "_4_3",
+                "field_refresh_rate": refresh,
+            })
+        };
+        assert_eq!(json["standard_timings"]["st1"], slot(75));
+        assert_eq!(json["standard_timings"]["interpretation"], "V1_4");
+        assert_eq!(
+            json["eighteen_byte_data_blocks"]["blocks"][2]["Display"]
+                ["StandardTimingIdentifications"]["_9"],
+            slot(60)
+        );
+        assert!(json["eighteen_byte_data_blocks"]["blocks"][2]["Display"]
+            ["StandardTimingIdentifications"]["_10"]
+            .is_object());
+
+        // json from before `interpretation` was added still reads
+        let mut old = json["standard_timings"].clone();
+        old.as_object_mut().unwrap().remove("interpretation");
+        let back: std_timings::StandardTimings = serde_json::from_value(old).unwrap();
+        assert_eq!(back, edid.standard_timings);
+    }
 }
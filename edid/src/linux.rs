@@ -0,0 +1,251 @@
+//! Finds the displays connected to a Linux machine through the DRM `sysfs`.
+//!
+//! Each connector on each graphics card gets a directory like
+//! `/sys/class/drm/card1-DP-3`, holding its status and a copy of the EDID
+//! that the kernel read from the display.
+//!
+//! ```edition2021,no_run
+//! use liboptic_edid::linux;
+//!
+//! for connector in linux::connectors()? {
+//!     if let Some(edid) = connector.edid {
+//!         println!("{}: {:?}", connector.name, edid.vendor_product_info);
+//!     }
+//! }
+//! #
+//! # Ok::<(), Box<dyn core::error::Error>>(())
+//! ```
+
+extern crate alloc;
+extern crate std;
+
+use alloc::{string::String, vec::Vec};
+use std::path::Path;
+
+use crate::prelude::internal::*;
+
+/// Where the kernel places DRM devices in the `sysfs`.
+pub const SYSFS_DRM: &str = "/sys/class/drm";
+
+/// A display connector on one of the machine's graphics cards.
+#[repr(C)]
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Connector {
+    /// The connector's directory name, like `card1-DP-3`.
+    pub name: String,
+
+    /// Whether a display is plugged in.
+    pub status: ConnectorStatus,
+
+    /// Whether the connector is currently driving a display.
+    pub enabled: bool,
+
+    /// The power state of the display.
+    pub dpms: Dpms,
+
+    /// The modes the kernel found for this connector, like `2560x1440`.
+    ///
+    /// These come in the kernel's order, so the preferred mode is usually
+    /// first. A mode can appear more than once when it has several refresh
+    /// rates.
+    pub modes: Vec<String>,
+
+    /// The bytes of the connector's `edid` file.
+    ///
+    /// This is empty when there's no display, or if the file is missing.
+    pub raw_edid: Vec<u8>,
+
+    /// The parsed EDID, if the connector had one and it parsed successfully.
+    pub edid: Option<Edid>,
+}
+
+/// Whether a display is plugged into a connector.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ConnectorStatus {
+    Connected,
+    Disconnected,
+    /// The driver couldn't tell, or the `status` file was missing.
+    Unknown,
+}
+
+/// The Display Power Management Signaling state of a connector.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Dpms {
+    On,
+    Standby,
+    Suspend,
+    Off,
+    /// The `dpms` file was missing or had an unexpected value.
+    Unknown,
+}
+
+/// Finds every connector in the `sysfs`, at `/sys/class/drm`.
+///
+/// Connectors are sorted by name.
+#[cfg_attr(feature = "tracing", tracing::instrument)]
+pub fn connectors() -> std::io::Result<Vec<Connector>> {
+    connectors_in(SYSFS_DRM)
+}
+
+/// Finds every connector in the given `root`, which is laid out like
+/// `/sys/class/drm`.
+///
+/// Connectors are sorted by name. Other entries in `root`, like the cards
+/// themselves (`card1`) or render nodes (`renderD128`), are skipped.
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+pub fn connectors_in<P: AsRef<Path>>(root: P) -> std::io::Result<Vec<Connector>> {
+    let mut connectors = std::fs::read_dir(root.as_ref())?
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            is_connector(&name).then(|| connector(&entry.path(), name))
+        })
+        .collect::<Vec<_>>();
+
+    connectors.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(connectors)
+}
+
+/// Checks if the given directory name is a connector, like `card1-DP-3`.
+fn is_connector(name: &str) -> bool {
+    name.strip_prefix("card")
+        .and_then(|rest| rest.split_once('-'))
+        .is_some_and(|(card, port)| {
+            !card.is_empty() && card.bytes().all(|b| b.is_ascii_digit()) && !port.is_empty()
+        })
+}
+
+/// Reads the connector at `path`.
+#[cfg_attr(feature = "tracing", tracing::instrument(skip(path)))]
+fn connector(path: &Path, name: String) -> Connector {
+    let read = |file: &str| std::fs::read_to_string(path.join(file)).unwrap_or_default();
+
+    let status = match read("status").trim() {
+        "connected" => ConnectorStatus::Connected,
+        "disconnected" => ConnectorStatus::Disconnected,
+        _ => ConnectorStatus::Unknown,
+    };
+
+    let dpms = match read("dpms").trim() {
+        "On" => Dpms::On,
+        "Standby" => Dpms::Standby,
+        "Suspend" => Dpms::Suspend,
+        "Off" => Dpms::Off,
+        other => {
+            tracing::debug!("unexpected dpms state: `{other}`");
+            Dpms::Unknown
+        }
+    };
+
+    let modes = read("modes").lines().map(String::from).collect();
+
+    // the kernel reports a size of zero for these, so we just read it all.
+    // connectors without a display have an empty file (or none at all)
+    let raw_edid = std::fs::read(path.join("edid")).unwrap_or_default();
+    let edid = if raw_edid.is_empty() {
+        None
+    } else {
+        Edid::new(&raw_edid)
+            .inspect_err(|e| tracing::warn!("failed to parse the EDID: {e}"))
+            .ok()
+    };
+
+    Connector {
+        name,
+        status,
+        enabled: read("enabled").trim() == "enabled",
+        dpms,
+        modes,
+        raw_edid,
+        edid,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+
+    const FAKE_SYSFS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/assets/sysfs/drm");
+
+    #[test]
+    fn fake_sysfs() {
+        logger();
+        let connectors = connectors_in(FAKE_SYSFS).unwrap();
+
+        // the card and render node aren't connectors
+        let names = connectors
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                "card1-DP-3",
+                "card1-HDMI-A-1",
+                "card1-Writeback-1",
+                "card1-eDP-1"
+            ]
+        );
+
+        let dp = &connectors[0];
+        assert_eq!(dp.status, ConnectorStatus::Connected);
+        assert!(dp.enabled);
+        assert_eq!(dp.dpms, Dpms::On);
+        assert_eq!(dp.modes.len(), 7);
+        assert_eq!(dp.modes[0], "2560x1440");
+        assert_eq!(dp.raw_edid, raw_edid_by_filename("dell_s2417dg.raw.input"));
+        assert_eq!(dp.edid.as_ref().unwrap().checksum, 0x51);
+
+        let edp = &connectors[3];
+        assert_eq!(edp.dpms, Dpms::Standby);
+        assert_eq!(edp.modes, vec![String::from("1920x1080")]);
+        assert_eq!(
+            edp.edid,
+            Some(Edid::new(edid_by_filename("1.input")).unwrap())
+        );
+    }
+
+    /// disconnected connectors have empty edid files, and some have none
+    #[test]
+    fn no_edid() {
+        logger();
+        let connectors = connectors_in(FAKE_SYSFS).unwrap();
+
+        let hdmi = &connectors[1];
+        assert_eq!(hdmi.status, ConnectorStatus::Disconnected);
+        assert!(!hdmi.enabled);
+        assert_eq!(hdmi.dpms, Dpms::Off);
+        assert!(hdmi.modes.is_empty());
+        assert!(hdmi.raw_edid.is_empty());
+        assert_eq!(hdmi.edid, None);
+
+        let writeback = &connectors[2];
+        assert_eq!(writeback.status, ConnectorStatus::Unknown);
+        assert!(writeback.raw_edid.is_empty());
+        assert_eq!(writeback.edid, None);
+    }
+
+    #[test]
+    fn missing_root() {
+        logger();
+        assert!(connectors_in("/this/path/does/not/exist").is_err());
+    }
+
+    #[test]
+    fn connector_names() {
+        assert!(is_connector("card0-DP-1"));
+        assert!(is_connector("card12-HDMI-A-2"));
+        assert!(!is_connector("card0"));
+        assert!(!is_connector("card-DP-1"));
+        assert!(!is_connector("cardX-DP-1"));
+        assert!(!is_connector("renderD128"));
+        assert!(!is_connector("version"));
+    }
+}
@@ -0,0 +1,37 @@
+//! Stand-ins for the `tracing` macros, for when that feature is off.
+//!
+//! They compile to nothing, but still type-check their arguments, so turning
+//! the feature off doesn't leave any variables unused.
+
+/// Checks the message's format, then throws it away.
+macro_rules! discard {
+    ($($arg:tt)*) => {
+        if false {
+            _ = ::core::format_args!($($arg)*);
+        }
+    };
+}
+
+macro_rules! trace {
+    ($($arg:tt)*) => { $crate::log::discard!($($arg)*) };
+}
+
+macro_rules! debug {
+    ($($arg:tt)*) => { $crate::log::discard!($($arg)*) };
+}
+
+macro_rules! info {
+    ($($arg:tt)*) => { $crate::log::discard!($($arg)*) };
+}
+
+// this one can't be named `warn` here, since that's also a built-in
+// attribute. it's renamed on the way out instead
+macro_rules! warning {
+    ($($arg:tt)*) => { $crate::log::discard!($($arg)*) };
+}
+
+macro_rules! error {
+    ($($arg:tt)*) => { $crate::log::discard!($($arg)*) };
+}
+
+pub(crate) use {debug, discard, error, info, trace, warning as warn};
@@ -0,0 +1,769 @@
+//! X11 modelines and Linux KMS modes for detailed timings.
+//!
+//! A [`Modeline`] holds a timing the way Xorg and DRM think of it, as the
+//! pixel positions where each part of a line (or frame) starts. Detailed
+//! timings convert into one, and it prints as an `xorg.conf` modeline:
+//!
+//! ```edition2021
+//! use liboptic_edid::{modeline::Modeline, structures::_18bytes::EighteenByteBlock, Edid};
+//!
+//! let data = std::fs::read("tests/assets/dell_s2417dg.raw.input")?;
+//! let edid = Edid::new(&data)?;
+//! let EighteenByteBlock::Timing(preferred) = edid.eighteen_byte_data_blocks.preferred_timing_mode
+//! else {
+//!     unreachable!("the dell has a preferred timing");
+//! };
+//!
+//! let modeline = Modeline::from(&preferred);
+//! assert_eq!(
+//!     modeline.to_string(),
+//!     r#"Modeline "2560x1440" 241.50 2560 2608 2640 2720 1440 1443 1448 1481 +HSync -VSync"#
+//! );
+//! #
+//! # Ok::<(), Box<dyn core::error::Error>>(())
+//! ```
+//!
+//! The alternate form (`{:#}`) adds a comment with the refresh rate and
+//! frequencies on top, like the `cvt` and `gtf` tools print.
+//!
+//! Modelines also parse from text, and convert back into a detailed timing
+//! for patching. For KMS, [`DrmModeInfo`] has the same layout as the
+//! kernel's `struct drm_mode_modeinfo`.
+
+use core::{ffi::c_char, fmt, str::FromStr};
+
+use arrayvec::ArrayString;
+use num_traits::CheckedMul as _;
+
+use crate::prelude::internal::*;
+use crate::structures::decimal;
+
+/// The longest mode name DRM allows, including its nul terminator.
+pub const DRM_DISPLAY_MODE_LEN: usize = 32;
+
+// `drm_mode_modeinfo.flags`, from the kernel's `drm_mode.h`
+pub const DRM_MODE_FLAG_PHSYNC: u32 = 1 << 0;
+pub const DRM_MODE_FLAG_NHSYNC: u32 = 1 << 1;
+pub const DRM_MODE_FLAG_PVSYNC: u32 = 1 << 2;
+pub const DRM_MODE_FLAG_NVSYNC: u32 = 1 << 3;
+pub const DRM_MODE_FLAG_INTERLACE: u32 = 1 << 4;
+pub const DRM_MODE_FLAG_CSYNC: u32 = 1 << 6;
+
+/// A display mode, as Xorg and DRM describe it.
+///
+/// For interlaced modes, the vertical values count the lines in a whole
+/// frame, not in each field. This is the opposite of detailed timings.
+#[repr(C)]
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Modeline {
+    /// The mode's name, like `1920x1080`.
+    pub name: ArrayString<DRM_DISPLAY_MODE_LEN>,
+
+    /// The pixel clock in kHz.
+    pub clock_khz: u32,
+
+    pub hdisplay: u16,
+    pub hsync_start: u16,
+    pub hsync_end: u16,
+    pub htotal: u16,
+
+    pub vdisplay: u16,
+    pub vsync_start: u16,
+    pub vsync_end: u16,
+    pub vtotal: u16,
+
+    /// The horizontal sync's polarity, if it has its own.
+    pub hsync: Option<SyncPolarity>,
+
+    /// The vertical sync's polarity, if it has its own.
+    pub vsync: Option<SyncPolarity>,
+
+    /// Whether both syncs are sent together.
+    pub composite_sync: bool,
+
+    pub interlace: bool,
+}
+
+impl Modeline {
+    /// Names a mode the way the kernel does, like `1920x1080` or `1920x1080i`.
+    fn default_name(
+        hdisplay: u16,
+        vdisplay: u16,
+        interlace: bool,
+    ) -> ArrayString<DRM_DISPLAY_MODE_LEN> {
+        let mut name = ArrayString::new();
+        let suffix = if interlace { "i" } else { "" };
+
+        // two five-digit numbers and two characters always fit
+        _ = fmt::write(&mut name, format_args!("{hdisplay}x{vdisplay}{suffix}"));
+        name
+    }
+
+    /// The refresh rate in hundredths of a Hz, rounded.
+    ///
+    /// For interlaced modes, this is the field rate.
+    fn refresh_centihz(&self) -> u64 {
+        let mut num = u64::from(self.clock_khz) * 1000 * 100;
+        let den = u64::from(self.htotal) * u64::from(self.vtotal);
+        if self.interlace {
+            num *= 2;
+        }
+        (num + den / 2).checked_div(den).unwrap_or(0)
+    }
+
+    /// Writes everything after the `Modeline` keyword.
+    pub(crate) fn write_body(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "\"{}\" ", self.name)?;
+
+        // dtds only have 10 kHz steps, so most clocks fit in two digits
+        let (mhz, khz) = (self.clock_khz / 1000, self.clock_khz % 1000);
+        if khz % 10 == 0 {
+            write!(f, "{mhz}.{:02}", khz / 10)?;
+        } else {
+            write!(f, "{mhz}.{khz:03}")?;
+        }
+
+        write!(
+            f,
+            " {} {} {} {} {} {} {} {}",
+            self.hdisplay,
+            self.hsync_start,
+            self.hsync_end,
+            self.htotal,
+            self.vdisplay,
+            self.vsync_start,
+            self.vsync_end,
+            self.vtotal
+        )?;
+
+        let sign = |p| match p {
+            SyncPolarity::Positive => '+',
+            SyncPolarity::Negative => '-',
+        };
+        if let Some(p) = self.hsync {
+            write!(f, " {}HSync", sign(p))?;
+        }
+        if let Some(p) = self.vsync {
+            write!(f, " {}VSync", sign(p))?;
+        }
+        if self.composite_sync {
+            f.write_str(" Composite")?;
+        }
+        if self.interlace {
+            f.write_str(" Interlace")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Modeline {
+    /// Writes an `xorg.conf` modeline.
+    ///
+    /// With `{:#}`, a `cvt`-style comment comes first:
+    ///
+    /// ```text
+    /// # 2560x1440 59.95 Hz hsync: 88.79 kHz; pclk: 241.50 MHz
+    /// Modeline "2560x1440" 241.50 2560 2608 2640 2720 1440 1443 1448 1481 +HSync -VSync
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            let refresh = self.refresh_centihz();
+            let htotal = u64::from(self.htotal);
+            let hsync = (u64::from(self.clock_khz) * 100 + htotal / 2)
+                .checked_div(htotal)
+                .unwrap_or(0);
+            let clock = (u64::from(self.clock_khz) + 5) / 10;
+
+            writeln!(
+                f,
+                "# {}x{} {}.{:02} Hz hsync: {}.{:02} kHz; pclk: {}.{:02} MHz",
+                self.hdisplay,
+                self.vdisplay,
+                refresh / 100,
+                refresh % 100,
+                hsync / 100,
+                hsync % 100,
+                clock / 100,
+                clock % 100,
+            )?;
+        }
+
+        f.write_str("Modeline ")?;
+        self.write_body(f)
+    }
+}
+
+impl FromStr for Modeline {
+    type Err = EdidError;
+
+    /// Reads a modeline, like those in `xorg.conf` or from `cvt`.
+    ///
+    /// The `Modeline` keyword is optional, and flags are case-insensitive.
+    /// Only the sync, `Composite`, and `Interlace` flags are understood.
+    #[cfg_attr(feature = "tracing", tracing::instrument)]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let s = match s.get(..8) {
+            Some(keyword) if keyword.eq_ignore_ascii_case("modeline") => s[8..].trim_start(),
+            _ => s,
+        };
+
+        // the name is quoted, so it can have spaces
+        let rest = s.strip_prefix('"').ok_or_else(|| {
+            tracing::error!("the modeline's name wasn't quoted.");
+            EdidError::from(EdidErrorKind::ModelineMissingField { field: "name" })
+        })?;
+        let (name, rest) = rest.split_once('"').ok_or_else(|| {
+            tracing::error!("the modeline's name never ended.");
+            EdidError::from(EdidErrorKind::ModelineBadValue { field: "name" })
+        })?;
+        let name = ArrayString::from(name).map_err(|_| {
+            tracing::error!("the modeline's name is too long. (name: `{name}`)");
+            EdidError::from(EdidErrorKind::ModelineBadValue { field: "name" })
+        })?;
+
+        let mut words = rest.split_whitespace();
+        let mut next = |field| {
+            words.next().ok_or_else(|| {
+                tracing::error!("the modeline has no `{field}`.");
+                EdidError::from(EdidErrorKind::ModelineMissingField { field })
+            })
+        };
+
+        // the clock is a decimal number of MHz
+        let clock = next("clock")?;
+        let clock_khz = decimal::parse(clock)
+            .and_then(|mhz| mhz.checked_mul(&Ratio::from_integer(1000)))
+            .and_then(|khz| khz.round().to_integer().try_into().ok())
+            .ok_or_else(|| {
+                tracing::error!("the modeline's clock isn't a number. (clock: `{clock}`)");
+                EdidError::from(EdidErrorKind::ModelineBadValue { field: "clock" })
+            })?;
+
+        let mut number = |field| {
+            next(field)?.parse::<u16>().map_err(|_| {
+                tracing::error!("the modeline's `{field}` isn't a number.");
+                EdidError::from(EdidErrorKind::ModelineBadValue { field })
+            })
+        };
+        let mut modeline = Self {
+            name,
+            clock_khz,
+            hdisplay: number("hdisplay")?,
+            hsync_start: number("hsync_start")?,
+            hsync_end: number("hsync_end")?,
+            htotal: number("htotal")?,
+            vdisplay: number("vdisplay")?,
+            vsync_start: number("vsync_start")?,
+            vsync_end: number("vsync_end")?,
+            vtotal: number("vtotal")?,
+            hsync: None,
+            vsync: None,
+            composite_sync: false,
+            interlace: false,
+        };
+
+        for flag in words {
+            let is = |name: &str| flag.eq_ignore_ascii_case(name);
+            if is("+hsync") {
+                modeline.hsync = Some(SyncPolarity::Positive);
+            } else if is("-hsync") {
+                modeline.hsync = Some(SyncPolarity::Negative);
+            } else if is("+vsync") {
+                modeline.vsync = Some(SyncPolarity::Positive);
+            } else if is("-vsync") {
+                modeline.vsync = Some(SyncPolarity::Negative);
+            } else if is("composite") || is("+csync") || is("-csync") {
+                modeline.composite_sync = true;
+            } else if is("interlace") {
+                modeline.interlace = true;
+            } else {
+                tracing::error!("the modeline has an unknown flag. (flag: `{flag}`)");
+                return Err(EdidErrorKind::ModelineBadValue { field: "flags" }.into());
+            }
+        }
+
+        Ok(modeline)
+    }
+}
+
+impl From<&DetailedTimingDefinition> for Modeline {
+    fn from(t: &DetailedTimingDefinition) -> Self {
+        let interlace = t.is_interlaced();
+
+        // dtds count the lines in each field, but modelines want the frame
+        let lines = |n: u16| if interlace { n.saturating_mul(2) } else { n };
+
+        let hdisplay = t.horizontal_addressable_video_px;
+        let hsync_start = hdisplay + t.horizontal_front_porch;
+        let vdisplay = t.vertical_addressable_video_lines;
+        let vsync_start = vdisplay + u16::from(t.vertical_front_porch_lines);
+        let vtotal = vdisplay + t.vertical_blanking_lines;
+
+        let (hsync, vsync) = (t.h_sync_polarity(), t.v_sync_polarity());
+
+        Self {
+            name: Self::default_name(hdisplay, lines(vdisplay), interlace),
+
+            // the dtd's clock is in units of 10 kHz
+            clock_khz: u32::from(t.pixel_clock_khz) * 10,
+
+            hdisplay,
+            hsync_start,
+            hsync_end: hsync_start + t.horizontal_sync_pulse_width_px,
+            htotal: hdisplay + t.horizontal_blanking_px,
+
+            vdisplay: lines(vdisplay),
+            vsync_start: lines(vsync_start),
+            vsync_end: lines(vsync_start + u16::from(t.vertical_sync_pulse_width_lines)),
+
+            // like the kernel, interlaced modes get an odd number of lines
+            vtotal: if interlace { lines(vtotal) | 1 } else { vtotal },
+
+            hsync,
+            vsync,
+            composite_sync: hsync.is_none(),
+            interlace,
+        }
+    }
+}
+
+impl TryFrom<&Modeline> for DetailedTimingDefinition {
+    type Error = EdidError;
+
+    /// Makes a detailed timing from the modeline.
+    ///
+    /// Each value has to fit in the timing's bit fields, and the clock is
+    /// rounded to the nearest 10 kHz. Modes with no sync flags get negative
+    /// separate syncs, and the image size is left out.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    fn try_from(m: &Modeline) -> Result<Self, Self::Error> {
+        // makes sure that `end` comes after `start`, and the gap fits in `bits`
+        let span = |start: u16, end: u16, bits: u32, field: &'static str| {
+            end.checked_sub(start)
+                .filter(|gap| u32::from(*gap) >> bits == 0)
+                .ok_or_else(|| {
+                    tracing::error!(
+                        "`{field}` doesn't fit in a detailed timing. (start: {start}, end: {end})"
+                    );
+                    EdidError::from(EdidErrorKind::ModelineBadValue { field })
+                })
+        };
+
+        // dtds count the lines in each field
+        let field = |n: u16| if m.interlace { n / 2 } else { n };
+
+        // dtds store the clock in units of 10 kHz
+        let pixel_clock = u16::try_from((m.clock_khz + 5) / 10)
+            .ok()
+            .filter(|clock| *clock != 0)
+            .ok_or_else(|| {
+                tracing::error!(
+                    "the clock doesn't fit in a detailed timing. (clock: {} kHz)",
+                    m.clock_khz
+                );
+                EdidError::from(EdidErrorKind::ModelineBadValue { field: "clock" })
+            })?;
+
+        let vdisplay = field(m.vdisplay);
+        let vsync_start = field(m.vsync_start);
+        let vsync_end = field(m.vsync_end);
+        let vtotal = field(m.vtotal);
+
+        let sync_signal = match (m.hsync, m.vsync, m.composite_sync) {
+            (None, None, true) => SyncSignal::Digital(DigitalSyncSignal::Composite),
+            (h, v, _) => SyncSignal::Digital(
+                match (
+                    h.unwrap_or(SyncPolarity::Negative),
+                    v.unwrap_or(SyncPolarity::Negative),
+                ) {
+                    (SyncPolarity::Negative, SyncPolarity::Negative) => {
+                        DigitalSyncSignal::SeparateNegVNegH
+                    }
+                    (SyncPolarity::Positive, SyncPolarity::Negative) => {
+                        DigitalSyncSignal::SeparateNegVPosH
+                    }
+                    (SyncPolarity::Negative, SyncPolarity::Positive) => {
+                        DigitalSyncSignal::SeparatePosVNegH
+                    }
+                    (SyncPolarity::Positive, SyncPolarity::Positive) => {
+                        DigitalSyncSignal::SeparatePosVPosH
+                    }
+                },
+            ),
+        };
+
+        Ok(Self {
+            pixel_clock_khz: pixel_clock,
+            horizontal_addressable_video_px: span(0, m.hdisplay, 12, "hdisplay")?,
+            horizontal_blanking_px: span(m.hdisplay, m.htotal, 12, "htotal")?,
+            vertical_addressable_video_lines: span(0, vdisplay, 12, "vdisplay")?,
+            vertical_blanking_lines: span(vdisplay, vtotal, 12, "vtotal")?,
+            horizontal_front_porch: span(m.hdisplay, m.hsync_start, 10, "hsync_start")?,
+            horizontal_sync_pulse_width_px: span(m.hsync_start, m.hsync_end, 10, "hsync_end")?,
+            vertical_front_porch_lines: span(vdisplay, vsync_start, 6, "vsync_start")? as u8,
+            vertical_sync_pulse_width_lines: span(vsync_start, vsync_end, 6, "vsync_end")? as u8,
+            horizontal_addressable_video_size_mm: None,
+            vertical_addressable_video_size_mm: None,
+            horizontal_border_px: 0,
+            vertical_border_lines: 0,
+            signal_interface_type: if m.interlace {
+                SignalInterfaceType::Interlaced
+            } else {
+                SignalInterfaceType::NonInterlaced
+            },
+            stereo_support: StereoViewingSupport::NormalDisplay,
+            sync_signal,
+        })
+    }
+}
+
+/// A mode with the same layout as the kernel's `struct drm_mode_modeinfo`.
+///
+/// Pass these to the `DRM_IOCTL_MODE_*` ioctls, or read them from
+/// `drmModeGetConnector`. `type_` is left at zero, so set the
+/// `DRM_MODE_TYPE_*` bits yourself.
+#[repr(C)]
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DrmModeInfo {
+    /// The pixel clock in kHz.
+    pub clock: u32,
+
+    pub hdisplay: u16,
+    pub hsync_start: u16,
+    pub hsync_end: u16,
+    pub htotal: u16,
+    pub hskew: u16,
+
+    pub vdisplay: u16,
+    pub vsync_start: u16,
+    pub vsync_end: u16,
+    pub vtotal: u16,
+    pub vscan: u16,
+
+    /// The refresh rate in Hz, rounded.
+    pub vrefresh: u32,
+
+    /// The `DRM_MODE_FLAG_*` bits.
+    pub flags: u32,
+
+    /// The `DRM_MODE_TYPE_*` bits.
+    pub type_: u32,
+
+    /// The mode's name, ending in a nul.
+    pub name: [c_char; DRM_DISPLAY_MODE_LEN],
+}
+
+impl From<&Modeline> for DrmModeInfo {
+    fn from(m: &Modeline) -> Self {
+        let mut flags = 0;
+        flags |= match m.hsync {
+            Some(SyncPolarity::Positive) => DRM_MODE_FLAG_PHSYNC,
+            Some(SyncPolarity::Negative) => DRM_MODE_FLAG_NHSYNC,
+            None => 0,
+        };
+        flags |= match m.vsync {
+            Some(SyncPolarity::Positive) => DRM_MODE_FLAG_PVSYNC,
+            Some(SyncPolarity::Negative) => DRM_MODE_FLAG_NVSYNC,
+            None => 0,
+        };
+        if m.composite_sync {
+            flags |= DRM_MODE_FLAG_CSYNC;
+        }
+        if m.interlace {
+            flags |= DRM_MODE_FLAG_INTERLACE;
+        }
+
+        // leave room for the nul
+        let mut name = [0; DRM_DISPLAY_MODE_LEN];
+        for (c, b) in name[..DRM_DISPLAY_MODE_LEN - 1]
+            .iter_mut()
+            .zip(m.name.bytes())
+        {
+            *c = c_char::from_ne_bytes([b]);
+        }
+
+        Self {
+            clock: m.clock_khz,
+            hdisplay: m.hdisplay,
+            hsync_start: m.hsync_start,
+            hsync_end: m.hsync_end,
+            htotal: m.htotal,
+            hskew: 0,
+            vdisplay: m.vdisplay,
+            vsync_start: m.vsync_start,
+            vsync_end: m.vsync_end,
+            vtotal: m.vtotal,
+            vscan: 0,
+            vrefresh: ((m.refresh_centihz() + 50) / 100) as u32,
+            flags,
+            type_: 0,
+            name,
+        }
+    }
+}
+
+impl From<&DrmModeInfo> for Modeline {
+    /// Reads a mode from the kernel.
+    ///
+    /// If its name isn't valid UTF-8, one is made up from its size.
+    fn from(d: &DrmModeInfo) -> Self {
+        let polarity = |pos, neg| {
+            if d.flags & pos != 0 {
+                Some(SyncPolarity::Positive)
+            } else if d.flags & neg != 0 {
+                Some(SyncPolarity::Negative)
+            } else {
+                None
+            }
+        };
+        let interlace = d.flags & DRM_MODE_FLAG_INTERLACE != 0;
+
+        let bytes = d.name.map(|c| c.to_ne_bytes()[0]);
+        let len = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+        let name = core::str::from_utf8(&bytes[..len])
+            .ok()
+            .and_then(|s| ArrayString::from(s).ok())
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| Self::default_name(d.hdisplay, d.vdisplay, interlace));
+
+        Self {
+            name,
+            clock_khz: d.clock,
+            hdisplay: d.hdisplay,
+            hsync_start: d.hsync_start,
+            hsync_end: d.hsync_end,
+            htotal: d.htotal,
+            vdisplay: d.vdisplay,
+            vsync_start: d.vsync_start,
+            vsync_end: d.vsync_end,
+            vtotal: d.vtotal,
+            hsync: polarity(DRM_MODE_FLAG_PHSYNC, DRM_MODE_FLAG_NHSYNC),
+            vsync: polarity(DRM_MODE_FLAG_PVSYNC, DRM_MODE_FLAG_NVSYNC),
+            composite_sync: d.flags & DRM_MODE_FLAG_CSYNC != 0,
+            interlace,
+        }
+    }
+}
+
+impl From<&DetailedTimingDefinition> for DrmModeInfo {
+    fn from(t: &DetailedTimingDefinition) -> Self {
+        Self::from(&Modeline::from(t))
+    }
+}
+
+impl TryFrom<&DrmModeInfo> for DetailedTimingDefinition {
+    type Error = EdidError;
+
+    fn try_from(d: &DrmModeInfo) -> Result<Self, Self::Error> {
+        Self::try_from(&Modeline::from(d))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use std::string::ToString as _;
+
+    use core::str::FromStr as _;
+
+    use crate::prelude::internal::*;
+
+    use super::{DrmModeInfo, Modeline, DRM_MODE_FLAG_NVSYNC, DRM_MODE_FLAG_PHSYNC};
+
+    fn dell_preferred() -> DetailedTimingDefinition {
+        let edid = Edid::new(raw_edid_by_filename("dell_s2417dg.raw.input")).unwrap();
+        let EighteenByteBlock::Timing(dtd) = edid.eighteen_byte_data_blocks.preferred_timing_mode
+        else {
+            panic!("dell has a preferred timing");
+        };
+        dtd
+    }
+
+    #[test]
+    fn dell_s2417dg() {
+        logger();
+        let modeline = Modeline::from(&dell_preferred());
+
+        assert_eq!(
+            modeline.to_string(),
+            r#"Modeline "2560x1440" 241.50 2560 2608 2640 2720 1440 1443 1448 1481 +HSync -VSync"#
+        );
+        assert_eq!(
+            std::format!("{modeline:#}"),
+            "# 2560x1440 59.95 Hz hsync: 88.79 kHz; pclk: 241.50 MHz\n\
+            Modeline \"2560x1440\" 241.50 2560 2608 2640 2720 1440 1443 1448 1481 +HSync -VSync"
+        );
+    }
+
+    /// every detailed timing should survive the trip through text
+    #[test]
+    fn round_trip_assets() {
+        logger();
+        let assets = [
+            raw_edid_by_filename("dell_s2417dg.raw.input"),
+            edid_by_filename("1.input"),
+            edid_by_filename("linuxhw_edid_Analog_AOC_AOC0320_455954E7CA14.input"),
+            edid_by_filename("linuxhw_edid_Digital_BOE_BOE07AF_BD22D8FDF96B.input"),
+            edid_by_filename("linuxhw_edid_Digital_Lenovo_LEN0017_3AF8B597ECB9.input"),
+            edid_by_filename("linuxhw_edid_EDID_Digital_Samsung_SAM02E3_2C47316EFF13.input"),
+        ];
+
+        for asset in assets {
+            let edid = Edid::new(&asset).unwrap();
+            let b = &edid.eighteen_byte_data_blocks;
+            let dtds = core::iter::once(&b.preferred_timing_mode)
+                .chain(&b.blocks)
+                .filter_map(|block| match block {
+                    EighteenByteBlock::Timing(t) => Some(t),
+                    EighteenByteBlock::Display(_) => None,
+                });
+
+            for dtd in dtds {
+                let modeline = Modeline::from(dtd);
+                let parsed = Modeline::from_str(&modeline.to_string()).unwrap();
+                assert_eq!(parsed, modeline);
+
+                // only the image size, borders, and stereo mode are lost
+                let back = DetailedTimingDefinition::try_from(&parsed).unwrap();
+                assert_eq!(
+                    back,
+                    DetailedTimingDefinition {
+                        horizontal_addressable_video_size_mm: None,
+                        vertical_addressable_video_size_mm: None,
+                        horizontal_border_px: 0,
+                        vertical_border_lines: 0,
+                        stereo_support: StereoViewingSupport::NormalDisplay,
+                        ..dtd.clone()
+                    }
+                );
+            }
+        }
+    }
+
+    /// dtds count lines per field, but modelines count the whole frame
+    #[test]
+    fn interlaced() {
+        logger();
+
+        // cta-861's 1080i60
+        let dtd = DetailedTimingDefinition {
+            pixel_clock_khz: 7425,
+            horizontal_addressable_video_px: 1920,
+            horizontal_blanking_px: 280,
+            vertical_addressable_video_lines: 540,
+            vertical_blanking_lines: 22,
+            horizontal_front_porch: 88,
+            horizontal_sync_pulse_width_px: 44,
+            vertical_front_porch_lines: 2,
+            vertical_sync_pulse_width_lines: 5,
+            horizontal_addressable_video_size_mm: None,
+            vertical_addressable_video_size_mm: None,
+            horizontal_border_px: 0,
+            vertical_border_lines: 0,
+            signal_interface_type: SignalInterfaceType::Interlaced,
+            stereo_support: StereoViewingSupport::NormalDisplay,
+            sync_signal: SyncSignal::Digital(DigitalSyncSignal::SeparatePosVPosH),
+        };
+
+        let modeline = Modeline::from(&dtd);
+        assert_eq!(
+            modeline.to_string(),
+            r#"Modeline "1920x1080i" 74.25 1920 2008 2052 2200 1080 1084 1094 1125 +HSync +VSync Interlace"#
+        );
+        assert_eq!(DetailedTimingDefinition::try_from(&modeline).unwrap(), dtd);
+        assert_eq!(DrmModeInfo::from(&modeline).vrefresh, 60);
+    }
+
+    #[test]
+    fn drm_mode_info() {
+        logger();
+        let dtd = dell_preferred();
+        let mode = DrmModeInfo::from(&dtd);
+
+        // same size as the kernel's struct
+        assert_eq!(core::mem::size_of::<DrmModeInfo>(), 68);
+
+        assert_eq!(mode.clock, 241_500);
+        assert_eq!(
+            (mode.hdisplay, mode.hsync_start, mode.hsync_end, mode.htotal),
+            (2560, 2608, 2640, 2720)
+        );
+        assert_eq!(
+            (mode.vdisplay, mode.vsync_start, mode.vsync_end, mode.vtotal),
+            (1440, 1443, 1448, 1481)
+        );
+        assert_eq!(mode.vrefresh, 60);
+        assert_eq!(mode.flags, DRM_MODE_FLAG_PHSYNC | DRM_MODE_FLAG_NVSYNC);
+        assert_eq!(
+            mode.name[..10],
+            b"2560x1440\0".map(|b| core::ffi::c_char::from_ne_bytes([b]))
+        );
+
+        // and back again
+        assert_eq!(Modeline::from(&mode), Modeline::from(&dtd));
+        assert_eq!(DetailedTimingDefinition::try_from(&mode).unwrap(), {
+            let mut dtd = dtd.clone();
+            dtd.horizontal_addressable_video_size_mm = None;
+            dtd.vertical_addressable_video_size_mm = None;
+            dtd
+        });
+    }
+
+    /// like what `cvt 1920 1080` prints
+    #[test]
+    fn parse_cvt() {
+        logger();
+        let modeline = Modeline::from_str(
+            r#"Modeline "1920x1080_60.00"  173.00  1920 2048 2248 2576  1080 1083 1088 1120 -hsync +vsync"#,
+        )
+        .unwrap();
+
+        assert_eq!(modeline.name.as_str(), "1920x1080_60.00");
+        assert_eq!(modeline.clock_khz, 173_000);
+        assert_eq!(modeline.htotal, 2576);
+        assert_eq!(modeline.vsync_end, 1088);
+        assert_eq!(modeline.hsync, Some(SyncPolarity::Negative));
+        assert_eq!(modeline.vsync, Some(SyncPolarity::Positive));
+
+        let dtd = DetailedTimingDefinition::try_from(&modeline).unwrap();
+        assert_eq!(dtd.pixel_clock_khz, 17300);
+        assert_eq!(dtd.horizontal_blanking_px, 656);
+        assert_eq!(
+            dtd.sync_signal,
+            SyncSignal::Digital(DigitalSyncSignal::SeparatePosVNegH)
+        );
+    }
+
+    #[test]
+    fn parse_bad() {
+        logger();
+        let field = |s: &str| match Modeline::from_str(s) {
+            Err(EdidError {
+                kind:
+                    EdidErrorKind::ModelineMissingField { field }
+                    | EdidErrorKind::ModelineBadValue { field },
+                ..
+            }) => field,
+            other => panic!("expected an error, got {other:?}"),
+        };
+
+        assert_eq!(field("1920x1080 173.00"), "name");
+        assert_eq!(field(r#""1920x1080" 173.00 1920 2048"#), "hsync_end");
+        assert_eq!(field(r#""a" fast 1 2 3 4 5 6 7 8"#), "clock");
+        assert_eq!(field(r#""a" 1.00 1 2 3 4 5 6 7 8 +wobble"#), "flags");
+
+        // parses, but can't be a dtd
+        let wide =
+            Modeline::from_str(r#""a" 100.00 1920 1930 3000 3100 1080 1083 1088 1120"#).unwrap();
+        assert!(matches!(
+            DetailedTimingDefinition::try_from(&wide),
+            Err(EdidError {
+                kind: EdidErrorKind::ModelineBadValue { field: "hsync_end" },
+                ..
+            })
+        ));
+    }
+}
@@ -8,7 +8,7 @@ use crate::prelude::internal::*;
 // TODO: ensure VESA LS-EXT (localization) compliance
 
 /// Parses out a 13-byte-long string from the given display descriptor bytes.
-#[tracing::instrument(skip_all)]
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
 pub(crate) fn parse(input: &[u8; 18]) -> Result<ArrayString<13>, EdidError> {
     // make an arraystring (string on the stack w/ static size).
     //
@@ -36,7 +36,7 @@ pub(crate) fn parse(input: &[u8; 18]) -> Result<ArrayString<13>, EdidError> {
         tracing::error!(
             "Failed to make string from given 13-byte values: (err: {e}, values: {bytes:?})"
         );
-        EdidError::ArrayStringError
+        EdidError::from(EdidErrorKind::ArrayStringError).at(5)
     })
 }
 
@@ -1,15 +1,14 @@
 use bitvec::{order::Lsb0, view::BitView};
 use color::ColorCoordinate;
-use rust_decimal::Decimal;
 
 use crate::{
-    parser::color::{into_decimal, make_u10},
+    parser::color::{into_ratio, make_u10},
     prelude::internal::*,
     structures::desc::color_point::WhitePoint,
 };
 
 /// Parses out chromaticity coordinates for the given color point descriptor.
-#[tracing::instrument(skip_all)]
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
 pub(crate) fn parse(input: &[u8; 18]) -> DisplayDescriptor {
     let w1 = make_white_point(1, input);
     let w2 = make_white_point(2, input);
@@ -17,14 +16,14 @@ pub(crate) fn parse(input: &[u8; 18]) -> DisplayDescriptor {
     DisplayDescriptor::ColorPointData { w1, w2 }
 }
 
-/// Creates a gamma decimal from the given raw value.
-#[tracing::instrument]
-fn gamma(raw: u8) -> Decimal {
-    (Decimal::from(raw) + Decimal::from(100)) / Decimal::from(100)
+/// Creates a gamma ratio from the given raw value.
+#[cfg_attr(feature = "tracing", tracing::instrument)]
+fn gamma(raw: u8) -> Ratio<u16> {
+    Ratio::new(u16::from(raw) + 100, 100)
 }
 
 /// Makes a white point given its `wi`, which is either 1 or 2.
-#[tracing::instrument(skip(input))]
+#[cfg_attr(feature = "tracing", tracing::instrument(skip(input)))]
 fn make_white_point(wi: u8, input: &[u8; 18]) -> WhitePoint {
     // convert index to usize.
     //
@@ -51,7 +50,7 @@ fn make_white_point(wi: u8, input: &[u8; 18]) -> WhitePoint {
 
         let coord_u10 = make_u10(lower[1], lower[0], upper);
         // let coord_u10 = make_u10(lower[0], lower[1], upper);
-        into_decimal(coord_u10)
+        into_ratio(coord_u10)
     };
 
     let wy = {
@@ -60,7 +59,7 @@ fn make_white_point(wi: u8, input: &[u8; 18]) -> WhitePoint {
 
         let coord_u10 = make_u10(lower[0], lower[1], upper);
         // let coord_u10 = make_u10(lower[0], lower[1], upper);
-        into_decimal(coord_u10)
+        into_ratio(coord_u10)
     };
 
     let coord = ColorCoordinate { x: wx, y: wy };
@@ -130,11 +129,8 @@ mod tests {
         let expected = DisplayDescriptor::ColorPointData {
             w1: WhitePoint {
                 index_number: 1,
-                coord: ColorCoordinate::new(
-                    Decimal::from(63) / Decimal::from(1024),
-                    Decimal::from(1) / Decimal::from(2),
-                ),
-                gamma: Some(rust_decimal_macros::dec!(3.54)),
+                coord: ColorCoordinate::new(Ratio::new(63, 1024), Ratio::new(1, 2)),
+                gamma: Some(Ratio::new(354, 100)),
             },
             w2: WhitePoint {
                 index_number: 2,
@@ -157,11 +153,8 @@ mod tests {
                 <BIGGEST>
 
                 */
-                coord: ColorCoordinate::new(
-                    Decimal::from(1) / Decimal::from(4),
-                    Decimal::from(965) / Decimal::from(1024),
-                ),
-                gamma: Some(dec!(1.23)),
+                coord: ColorCoordinate::new(Ratio::new(1, 4), Ratio::new(965, 1024)),
+                gamma: Some(Ratio::new(123, 100)),
             },
         };
         tracing::warn!("EXPECTED: \n{expected:#?}");
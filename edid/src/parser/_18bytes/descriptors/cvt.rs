@@ -7,6 +7,7 @@
 use bitvec::{field::BitField, order::Lsb0, view::BitView};
 
 use crate::{
+    parser::le,
     prelude::internal::*,
     structures::desc::cvt_3_byte_timing::{
         CvtAspectRatio, CvtPreferredVerticalRate, SupportedVRates, TimingCodeDesc,
@@ -14,7 +15,7 @@ use crate::{
 };
 
 /// Parses out a CVT descriptor from the given input.
-#[tracing::instrument]
+#[cfg_attr(feature = "tracing", tracing::instrument)]
 pub(crate) fn parse(input: &[u8; 18]) -> Result<DisplayDescriptor, EdidError> {
     let version_number = input[5];
 
@@ -27,7 +28,7 @@ pub(crate) fn parse(input: &[u8; 18]) -> Result<DisplayDescriptor, EdidError> {
 
     let first = one(&input[6..9].try_into()?).ok_or_else(|| {
         tracing::error!("The first CVT code must be defined, but this descriptor didn't do that.");
-        EdidError::DescriptorNoFirstCvt
+        EdidError::from(EdidErrorKind::DescriptorNoFirstCvt).at(6)
     })?;
 
     Ok(DisplayDescriptor::Cvt3ByteTimingCodes {
@@ -40,7 +41,7 @@ pub(crate) fn parse(input: &[u8; 18]) -> Result<DisplayDescriptor, EdidError> {
 }
 
 /// Parses one of the four TimingCodeDescs fields.
-#[tracing::instrument]
+#[cfg_attr(feature = "tracing", tracing::instrument)]
 fn one(input: &[u8; 3]) -> Option<TimingCodeDesc> {
     // if the values are all zero, it's unused
     if input == &[0x00, 0x00, 0x00] {
@@ -54,8 +55,7 @@ fn one(input: &[u8; 3]) -> Option<TimingCodeDesc> {
         let upper = bits1[0x04..=0x07].load::<u8>();
 
         // and the byte
-        let c: u16 = bytemuck::must_cast([input[0], upper]);
-        c
+        le::read_u12(input[0], upper)
     };
 
     // grab the ar
@@ -2,31 +2,102 @@
 //!
 //! Note that this is called DCM because of the VESA DCM standard, which
 //! stands for "Display Color Management".
-//!
-//! Also, please note that this module (currently) does not implement DCM,
-//! but instead just returns the raw values.
 
 use crate::prelude::internal::*;
+use crate::structures::desc::dcm::DcmCurve;
 
 /// Parses out a DCM descriptor from the given bytes.
-#[tracing::instrument]
+#[cfg_attr(feature = "tracing", tracing::instrument)]
 pub(crate) fn parse(input: &[u8; 18]) -> DisplayDescriptor {
     let version_number = input[5];
 
     if version_number != 0x03 {
         tracing::warn!(
-            "DCM version number was 0x00, but values except for 0x03 \
-        are reserved."
+            "DCM version number was {version_number:#04x}, but values except \
+        for 0x03 are reserved."
         );
     }
 
     DisplayDescriptor::DcmData {
         version_number,
-        red_a3: bytemuck::must_cast([input[6], input[7]]),
-        red_a2: bytemuck::must_cast([input[8], input[9]]),
-        green_a3: bytemuck::must_cast([input[10], input[11]]),
-        green_a2: bytemuck::must_cast([input[12], input[13]]),
-        blue_a3: bytemuck::must_cast([input[14], input[15]]),
-        blue_a2: bytemuck::must_cast([input[16], input[17]]),
+        red: curve(&input[6..10]),
+        green: curve(&input[10..14]),
+        blue: curve(&input[14..18]),
+    }
+}
+
+/// Each coefficient is a signed little-endian number, in hundredths.
+fn curve(input: &[u8]) -> DcmCurve {
+    let coefficient = |lsb: u8, msb: u8| Ratio::new(i16::from_le_bytes([lsb, msb]).into(), 100);
+
+    DcmCurve {
+        a3: coefficient(input[0], input[1]),
+        a2: coefficient(input[2], input[3]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[rustfmt::skip]
+    const INPUT: [u8; 18] = [
+        0x00, 0x00, 0x00, 0xF9, 0x00, 0x03,
+        0x0F, 0x00, 0xEC, 0xFF, // red: 0.15, -0.20
+        0x00, 0x00, 0x00, 0x00, // green: 0, 0
+        0x2C, 0x01, 0x9C, 0xFF, // blue: 3.00, -1.00
+    ];
+
+    fn curves() -> (u8, [DcmCurve; 3]) {
+        let DisplayDescriptor::DcmData {
+            version_number,
+            red,
+            green,
+            blue,
+        } = parse(&INPUT)
+        else {
+            panic!("not a dcm descriptor");
+        };
+        (version_number, [red, green, blue])
+    }
+
+    #[test]
+    fn parse_dcm() {
+        logger();
+        let (version_number, [red, _, blue]) = curves();
+
+        assert_eq!(version_number, 3);
+        assert_eq!(
+            (red.a3, red.a2),
+            (Ratio::new(15, 100), Ratio::new(-20, 100))
+        );
+        assert_eq!(red.a1(), Ratio::new(105, 100));
+        assert_eq!(
+            (blue.a3, blue.a2),
+            (Ratio::from_integer(3), Ratio::from_integer(-1))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "decimal")]
+    fn evaluate_dcm() {
+        logger();
+        let (_, [red, green, blue]) = curves();
+
+        // all zeroes is a straight line
+        assert_eq!(green.evaluate(dec!(0.5), Decimal::ONE), dec!(0.5));
+
+        // the curve always runs from zero to one
+        for curve in [&red, &green, &blue] {
+            assert_eq!(curve.evaluate(Decimal::ZERO, dec!(2.2)), Decimal::ZERO);
+            assert_eq!(curve.evaluate(Decimal::ONE, dec!(2.2)), Decimal::ONE);
+        }
+
+        // 0.15(0.125) - 0.2(0.25) + 1.05(0.5)
+        assert_eq!(red.evaluate(dec!(0.5), Decimal::ONE), dec!(0.49375));
+
+        // 0.5^2 = 0.25, then 0.15(0.015625) - 0.2(0.0625) + 1.05(0.25)
+        let squared = red.evaluate(dec!(0.5), dec!(2));
+        assert!((squared - dec!(0.25234375)).abs() < dec!(0.000001));
     }
 }
@@ -5,7 +5,7 @@ use bitvec::{order::Lsb0, slice::BitSlice, view::BitView as _};
 use crate::prelude::internal::*;
 
 /// Parses the Established Timings II descriptor.
-#[tracing::instrument(skip_all)]
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
 pub(crate) fn parse(input: &[u8; 18]) -> Result<DisplayDescriptor, EdidError> {
     // view bits of each byte
     let b6 = input[6].view_bits::<Lsb0>();
@@ -77,7 +77,7 @@ pub(crate) fn parse(input: &[u8; 18]) -> Result<DisplayDescriptor, EdidError> {
 }
 
 /// Checks for use of reserved fields.
-#[tracing::instrument]
+#[cfg_attr(feature = "tracing", tracing::instrument)]
 fn warn(input: &[u8; 18], b11: &BitSlice<u8>) -> Result<(), EdidError> {
     let b11_reserved = [b11[3], b11[2], b11[1], b11[0]];
     if b11_reserved != [false, false, false, false] {
@@ -4,13 +4,21 @@ use crate::parser::std_timings::one;
 
 /// Parses... six more standard timings. In case your display is using all
 /// those.
-#[tracing::instrument(skip(input))]
+#[cfg_attr(feature = "tracing", tracing::instrument(skip(input)))]
 pub(crate) fn parse(input: &[u8; 18]) -> Result<DisplayDescriptor, EdidError> {
     // error if weird header value
     let header = &input[0..5];
     if header != [0x00, 0x00, 0x00, 0xFA, 0x00] {
         tracing::error!("Given descriptor data had a malformed header: {header:x?}");
-        return Err(EdidError::DescriptorUnexpectedHeader(header.try_into()?));
+        let wrong = header
+            .iter()
+            .zip([0x00, 0x00, 0x00, 0xFA, 0x00])
+            .position(|(got, expected)| *got != expected)
+            .unwrap_or_default();
+        return Err(EdidError::from(EdidErrorKind::DescriptorUnexpectedHeader(
+            header.try_into()?,
+        ))
+        .at(wrong as u16));
     }
 
     // check if the last value matches the expected one. i'll let this one fly
@@ -22,13 +30,9 @@ pub(crate) fn parse(input: &[u8; 18]) -> Result<DisplayDescriptor, EdidError> {
         }
     }
 
+    // each timing is two bytes, from byte 5 to byte 16
     Ok(DisplayDescriptor::StandardTimingIdentifications {
-        _9: one(&input[5..7]),
-        _10: one(&input[7..9]),
-        _11: one(&input[9..11]),
-        _12: one(&input[11..13]),
-        _13: one(&input[13..15]),
-        _14: one(&input[15..17]),
+        timings: core::array::from_fn(|i| one(&input[5 + i * 2..][..2])),
     })
 }
 
@@ -50,12 +54,7 @@ mod tests {
         tracing::info!("GOT: {got:#?}");
 
         let expected = DisplayDescriptor::StandardTimingIdentifications {
-            _9: None,
-            _10: None,
-            _11: None,
-            _12: None,
-            _13: None,
-            _14: None,
+            timings: [None, None, None, None, None, None],
         };
         tracing::warn!("EXPECTED: {expected:#?}");
 
@@ -72,20 +71,22 @@ mod tests {
         tracing::info!("GOT: {got:#?}");
 
         let expected = DisplayDescriptor::StandardTimingIdentifications {
-            _9: Some(STiming {
-                horizontal_addr_pixel_ct: 296,
-                aspect_ratio: StandardAspectRatio::_16_10,
-                field_refresh_rate: 76,
-            }),
-            _10: Some(STiming {
-                horizontal_addr_pixel_ct: 632,
-                aspect_ratio: StandardAspectRatio::_16_10,
-                field_refresh_rate: 77,
-            }),
-            _11: None,
-            _12: None,
-            _13: None,
-            _14: None,
+            timings: [
+                Some(STiming {
+                    horizontal_addr_pixel_ct: 296,
+                    aspect_ratio: StandardAspectRatio::_16_10,
+                    field_refresh_rate: 76,
+                }),
+                Some(STiming {
+                    horizontal_addr_pixel_ct: 632,
+                    aspect_ratio: StandardAspectRatio::_16_10,
+                    field_refresh_rate: 77,
+                }),
+                None,
+                None,
+                None,
+                None,
+            ],
         };
         tracing::warn!("EXPECTED: {expected:#?}");
 
@@ -103,16 +104,18 @@ mod tests {
         tracing::info!("GOT: {got:#?}");
 
         let expected = DisplayDescriptor::StandardTimingIdentifications {
-            _9: Some(STiming {
-                horizontal_addr_pixel_ct: 1600,
-                aspect_ratio: StandardAspectRatio::_16_9,
-                field_refresh_rate: 60,
-            }),
-            _10: None,
-            _11: None,
-            _12: None,
-            _13: None,
-            _14: None,
+            timings: [
+                Some(STiming {
+                    horizontal_addr_pixel_ct: 1600,
+                    aspect_ratio: StandardAspectRatio::_16_9,
+                    field_refresh_rate: 60,
+                }),
+                None,
+                None,
+                None,
+                None,
+                None,
+            ],
         };
         tracing::warn!("EXPECTED: {expected:#?}");
 
@@ -130,36 +133,38 @@ mod tests {
         tracing::info!("GOT: {got:#?}");
 
         let expected = DisplayDescriptor::StandardTimingIdentifications {
-            _9: Some(STiming {
-                horizontal_addr_pixel_ct: 1152,
-                aspect_ratio: StandardAspectRatio::_4_3,
-                field_refresh_rate: 60,
-            }),
-            _10: Some(STiming {
-                horizontal_addr_pixel_ct: 1280,
-                aspect_ratio: StandardAspectRatio::_16_10,
-                field_refresh_rate: 75,
-            }),
-            _11: Some(STiming {
-                horizontal_addr_pixel_ct: 1360,
-                aspect_ratio: StandardAspectRatio::_16_9,
-                field_refresh_rate: 60,
-            }),
-            _12: Some(STiming {
-                horizontal_addr_pixel_ct: 1360,
-                aspect_ratio: StandardAspectRatio::_16_9,
-                field_refresh_rate: 60,
-            }),
-            _13: Some(STiming {
-                horizontal_addr_pixel_ct: 1400,
-                aspect_ratio: StandardAspectRatio::_4_3,
-                field_refresh_rate: 60,
-            }),
-            _14: Some(STiming {
-                horizontal_addr_pixel_ct: 1600,
-                aspect_ratio: StandardAspectRatio::_16_9,
-                field_refresh_rate: 60,
-            }),
+            timings: [
+                Some(STiming {
+                    horizontal_addr_pixel_ct: 1152,
+                    aspect_ratio: StandardAspectRatio::_4_3,
+                    field_refresh_rate: 60,
+                }),
+                Some(STiming {
+                    horizontal_addr_pixel_ct: 1280,
+                    aspect_ratio: StandardAspectRatio::_16_10,
+                    field_refresh_rate: 75,
+                }),
+                Some(STiming {
+                    horizontal_addr_pixel_ct: 1360,
+                    aspect_ratio: StandardAspectRatio::_16_9,
+                    field_refresh_rate: 60,
+                }),
+                Some(STiming {
+                    horizontal_addr_pixel_ct: 1360,
+                    aspect_ratio: StandardAspectRatio::_16_9,
+                    field_refresh_rate: 60,
+                }),
+                Some(STiming {
+                    horizontal_addr_pixel_ct: 1400,
+                    aspect_ratio: StandardAspectRatio::_4_3,
+                    field_refresh_rate: 60,
+                }),
+                Some(STiming {
+                    horizontal_addr_pixel_ct: 1600,
+                    aspect_ratio: StandardAspectRatio::_16_9,
+                    field_refresh_rate: 60,
+                }),
+            ],
         };
         tracing::warn!("EXPECTED: {expected:#?}");
 
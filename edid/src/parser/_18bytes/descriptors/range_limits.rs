@@ -1,17 +1,23 @@
 use bitvec::{field::BitField as _, order::Lsb0, view::BitView};
 use nobcd::BcdNumber;
 
-use crate::prelude::internal::*;
+use crate::{parser::le, prelude::internal::*};
 
 /// Parses the given 18-byte array for a range limits descriptor block.
 ///
 /// Note that the `edid` input needs to be a full EDID.
-#[tracing::instrument(skip_all)]
-pub(crate) fn parse(input: &[u8; 18], edid: &[u8]) -> Result<RangeLimitsDesc, EdidError> {
-    let limits = just_limits(input)?;
+#[cfg_attr(feature = "tracing", tracing::instrument(skip(input, edid)))]
+pub(crate) fn parse(
+    input: &[u8; 18],
+    edid: &[u8],
+    interpretation: Interpretation,
+) -> Result<RangeLimitsDesc, EdidError> {
+    let limits = just_limits(input, interpretation)?;
 
     // all gtf-complaint displays are continuous frequency.
     // therefore, if something reports to use gtf, check that this is true!
+    //
+    // (before v1.4, this same bit says that default gtf is supported)
     let cf = edid[0x18].view_bits::<Lsb0>()[0];
     let check_supports_cont_freq = || {
         if !cf {
@@ -19,6 +25,20 @@ pub(crate) fn parse(input: &[u8; 18], edid: &[u8]) -> Result<RangeLimitsDesc, Ed
         }
     };
 
+    // only gtf flags exist before v1.4. the rest are reserved, but the limits
+    // themselves are still useful
+    if !interpretation.is_v1_4() && !matches!(input[10], 0x00 | 0x02) {
+        tracing::warn!(
+            "Range limits used video timing support flag `{:x}`, which isn't \
+        defined before EDID v1.4. Treating it as range limits only.",
+            input[10]
+        );
+        return Ok(RangeLimitsDesc::LimitsOnly {
+            limits,
+            flexible: false,
+        });
+    }
+
     Ok(match input[10] {
         // gtf
         0x00 => {
@@ -45,13 +65,18 @@ pub(crate) fn parse(input: &[u8; 18], edid: &[u8]) -> Result<RangeLimitsDesc, Ed
             tracing::error!(
                 "The given descriptor used a reserved video timing support flag! (`{reserved:x?}`)"
             );
-            return Err(EdidError::DescriptorRangeLimitsUsedReservedVTSFlag { flag: reserved });
+            return Err(
+                EdidError::from(EdidErrorKind::DescriptorRangeLimitsUsedReservedVTSFlag {
+                    flag: reserved,
+                })
+                .at(10),
+            );
         }
     })
 }
 
 /// For when byte 10 == 0x02.
-#[tracing::instrument]
+#[cfg_attr(feature = "tracing", tracing::instrument)]
 fn gtf_secondary_curve(limits: RangeLimits, input: &[u8; 18]) -> RangeLimitsDesc {
     if input[11] != 0x00 {
         tracing::warn!(
@@ -62,7 +87,7 @@ fn gtf_secondary_curve(limits: RangeLimits, input: &[u8; 18]) -> RangeLimitsDesc
 
     let start_break_freq = input[12] as u16 * 2;
     let c2 = input[13];
-    let m: u16 = [input[14], input[15]].view_bits::<Lsb0>().load();
+    let m = le::read_u16([input[14], input[15]]);
     let k = input[16];
     let j2 = input[17];
 
@@ -77,13 +102,14 @@ fn gtf_secondary_curve(limits: RangeLimits, input: &[u8; 18]) -> RangeLimitsDesc
 }
 
 /// For when byte 10 == 0x04.
-#[tracing::instrument]
+#[cfg_attr(feature = "tracing", tracing::instrument)]
 fn cvt(limits: RangeLimits, input: &[u8; 18]) -> Result<RangeLimitsDesc, EdidError> {
     let cvt_version = input[11];
     let enhanced_px_clk = {
         let to_sub: u8 = input[12].view_bits::<Lsb0>()[2..=7].load();
-        let decimal = Decimal::from(limits.max_pixel_clock_mhz);
-        decimal - (Decimal::from(to_sub) / Decimal::from(4))
+        // a clock can't go negative, even if the display says so
+        let quarters = (limits.max_pixel_clock_mhz * 4).saturating_sub(to_sub.into());
+        Ratio::new(quarters, 4)
     };
 
     let maximum_active_pxls_per_line = {
@@ -110,7 +136,7 @@ fn cvt(limits: RangeLimits, input: &[u8; 18]) -> Result<RangeLimitsDesc, EdidErr
         SupportedAspectRatios {
             _4x3: bits[7],
             _16x9: bits[6],
-            _16x10: bits[7],
+            _16x10: bits[5],
             _5x4: bits[4],
             _15x9: bits[3],
         }
@@ -128,7 +154,9 @@ fn cvt(limits: RangeLimits, input: &[u8; 18]) -> Result<RangeLimitsDesc, EdidErr
                 tracing::error!(
                     "Used reserved combination of pref. aspect ratio bits. ({other:x?})"
                 );
-                return Err(EdidError::DescriptorRangeLimitsCvtReservedBits);
+                return Err(
+                    EdidError::from(EdidErrorKind::DescriptorRangeLimitsCvtReservedBits).at(15),
+                );
             }
         }
     };
@@ -167,13 +195,30 @@ fn cvt(limits: RangeLimits, input: &[u8; 18]) -> Result<RangeLimitsDesc, EdidErr
     })
 }
 
-#[tracing::instrument(skip_all)]
-fn just_limits(input: &[u8; 18]) -> Result<RangeLimits, EdidError> {
-    // we need these to calculate the min/max rates
-    let offsets = limit_offsets(input[4])?;
+#[cfg_attr(feature = "tracing", tracing::instrument(skip(input)))]
+fn just_limits(input: &[u8; 18], interpretation: Interpretation) -> Result<RangeLimits, EdidError> {
+    // we need these to calculate the min/max rates.
+    //
+    // offsets are new in v1.4, so that byte is reserved on earlier versions
+    let offsets = if interpretation.is_v1_4() {
+        limit_offsets(input[4]).map_err(|e| e.at(4))?
+    } else {
+        if input[4] != 0x00 {
+            tracing::warn!(
+                "Range limits used offset flags, but these aren't defined before \
+            EDID v1.4. Ignoring them... (byte: `{:x}`)",
+                input[4]
+            );
+        }
+
+        Offsets {
+            vertical: VerticalOffset::Zero,
+            horizontal: HorizontalOffset::Zero,
+        }
+    };
 
     let get_rate = |idx| -> Result<u16, EdidError> {
-        let val = from_bcd(input[idx])?;
+        let val = from_bcd(input[idx]).map_err(|e| e.at(idx as u16))?;
         if val == 0x0 {
             tracing::warn!("a min/max rate incorrectly has a value of zero! (at `input[{idx}]`)");
         }
@@ -212,7 +257,7 @@ fn just_limits(input: &[u8; 18]) -> Result<RangeLimits, EdidError> {
         val
     };
 
-    let pixel_clock = from_bcd(input[9])? * 10;
+    let pixel_clock = from_bcd(input[9]).map_err(|e| e.at(9))? * 10;
 
     Ok(RangeLimits {
         min_v_rate_hz: min_vt,
@@ -221,10 +266,11 @@ fn just_limits(input: &[u8; 18]) -> Result<RangeLimits, EdidError> {
         max_h_rate_khz: max_hz,
         offsets,
         max_pixel_clock_mhz: pixel_clock,
+        interpretation,
     })
 }
 
-#[tracing::instrument]
+#[cfg_attr(feature = "tracing", tracing::instrument)]
 fn limit_offsets(byte: u8) -> Result<Offsets, EdidError> {
     let bits = byte.view_bits::<Lsb0>();
 
@@ -232,7 +278,7 @@ fn limit_offsets(byte: u8) -> Result<Offsets, EdidError> {
     let rev_bits = &bits[4..=7];
     if !rev_bits.iter().all(|b| b == false) {
         tracing::error!("Reserved display range limit offset bits were used: {rev_bits:x?}");
-        return Err(EdidError::DescriptorRangeLimitsUsedReservedBits { input: byte });
+        return Err(EdidErrorKind::DescriptorRangeLimitsUsedReservedBits { input: byte }.into());
     }
 
     // otherwise, we got some offsets
@@ -245,7 +291,9 @@ fn limit_offsets(byte: u8) -> Result<Offsets, EdidError> {
 
         _ => {
             tracing::error!("Horizontal bytes were weird: {hz_bits:#?}");
-            return Err(EdidError::DescriptorRangeLimitsUsedReservedBits { input: byte });
+            return Err(
+                EdidErrorKind::DescriptorRangeLimitsUsedReservedBits { input: byte }.into(),
+            );
         }
     };
 
@@ -257,7 +305,9 @@ fn limit_offsets(byte: u8) -> Result<Offsets, EdidError> {
 
         _ => {
             tracing::error!("Horizontal bytes were weird: {hz_bits:#?}");
-            return Err(EdidError::DescriptorRangeLimitsUsedReservedBits { input: byte });
+            return Err(
+                EdidErrorKind::DescriptorRangeLimitsUsedReservedBits { input: byte }.into(),
+            );
         }
     };
 
@@ -268,18 +318,18 @@ fn limit_offsets(byte: u8) -> Result<Offsets, EdidError> {
 }
 
 /// Wraps the BCD-encoded number in a helpful type.
-#[tracing::instrument]
+#[cfg_attr(feature = "tracing", tracing::instrument)]
 fn typed_bcd(input: u8) -> Result<BcdNumber<2>, EdidError> {
     let bcd = BcdNumber::new(input).map_err(|e| {
         tracing::error!("Failed to create BcdNumber. (err: {e:?})");
-        EdidError::BcdError
+        EdidErrorKind::BcdError.into()
     });
     tracing::debug!("made typed bcd (from: `{input}`, to: `{bcd:?}`)");
     bcd
 }
 
 /// Makes a typical number from the given BCD-encoded one.
-#[tracing::instrument]
+#[cfg_attr(feature = "tracing", tracing::instrument)]
 fn from_bcd(input: u8) -> Result<u16, EdidError> {
     let val = typed_bcd(input)?.value();
     tracing::debug!("converted bcd (from: `{input}`, to: `{val}`)");
@@ -299,7 +349,7 @@ mod tests {
 
         let _t: i32 = 0b0000_0000_0010_1000;
 
-        let got = parse(&bytes, &input).unwrap();
+        let got = parse(&bytes, &input, Interpretation::V1_4).unwrap();
 
         let expected = RangeLimitsDesc::GtfSupported {
             limits: RangeLimits {
@@ -312,6 +362,7 @@ mod tests {
                     horizontal: HorizontalOffset::Zero,
                 },
                 max_pixel_clock_mhz: 140,
+                interpretation: Interpretation::V1_4,
             },
         };
 
@@ -338,7 +389,7 @@ mod tests {
         let input = edid_by_filename(path);
         let bytes: [u8; 18] = input[0x48..0x5A].try_into().unwrap();
 
-        let got = parse(&bytes, &input).unwrap();
+        let got = parse(&bytes, &input, Interpretation::V1_4).unwrap();
         tracing::info!("{:#?}", got);
 
         let expected = RangeLimitsDesc::CvtSupported {
@@ -352,9 +403,10 @@ mod tests {
                     horizontal: HorizontalOffset::Zero,
                 },
                 max_pixel_clock_mhz: 330,
+                interpretation: Interpretation::V1_4,
             },
 
-            enhanced_px_clk: Decimal::from(328) + (Decimal::from(3) / Decimal::from(4)),
+            enhanced_px_clk: Ratio::new(1315, 4),
             cvt_version: 10,
             maximum_active_pxls_per_line: Some(160),
             supported_aspect_ratios: SupportedAspectRatios {
@@ -377,4 +429,63 @@ mod tests {
 
         assert_eq!(got, expected);
     }
+
+    /// each cvt aspect ratio has its own bit
+    #[test]
+    fn cvt_aspect_ratios() {
+        logger();
+        let path = "bad/linuxhw_edid_EDID_Digital_LG Display_LGD0555_7D17E3014129.input";
+        let input = edid_by_filename(path);
+        let mut bytes: [u8; 18] = input[0x48..0x5A].try_into().unwrap();
+
+        let ratios = |bytes: &[u8; 18]| match parse(bytes, &input, Interpretation::V1_4) {
+            Ok(RangeLimitsDesc::CvtSupported {
+                supported_aspect_ratios,
+                ..
+            }) => supported_aspect_ratios,
+            other => panic!("expected cvt range limits, got {other:?}"),
+        };
+
+        // just 16:10
+        bytes[14] = 0b0010_0000;
+        let got = ratios(&bytes);
+        assert!(got._16x10);
+        assert!(!got._4x3 && !got._16x9 && !got._5x4 && !got._15x9);
+
+        // and just 4:3
+        bytes[14] = 0b1000_0000;
+        let got = ratios(&bytes);
+        assert!(got._4x3);
+        assert!(!got._16x10 && !got._16x9 && !got._5x4 && !got._15x9);
+    }
+
+    /// offsets and cvt support are both ignored before v1.4
+    #[test]
+    fn v1_3_range_limits() {
+        logger();
+        let path = "bad/linuxhw_edid_EDID_Digital_LG Display_LGD0555_7D17E3014129.input";
+        let input = edid_by_filename(path);
+        let mut bytes: [u8; 18] = input[0x48..0x5A].try_into().unwrap();
+        bytes[4] = 0b0000_0011; // max + min vertical offsets
+
+        let got = parse(&bytes, &input, Interpretation::V1_3).unwrap();
+
+        let expected = RangeLimitsDesc::LimitsOnly {
+            limits: RangeLimits {
+                min_v_rate_hz: 48,
+                max_v_rate_hz: 60,
+                min_h_rate_khz: 0,
+                max_h_rate_khz: 0,
+                offsets: Offsets {
+                    vertical: VerticalOffset::Zero,
+                    horizontal: HorizontalOffset::Zero,
+                },
+                max_pixel_clock_mhz: 330,
+                interpretation: Interpretation::V1_3,
+            },
+            flexible: false,
+        };
+
+        assert_eq!(got, expected);
+    }
 }
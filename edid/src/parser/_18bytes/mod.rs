@@ -5,54 +5,77 @@ mod descriptors;
 mod preferred_tm;
 
 /// Parses out the four 18-byte descriptors from the user's provided EDID input.
-#[tracing::instrument(skip_all)]
-pub(crate) fn parse(input: &[u8]) -> Result<EighteenByteDescriptors, EdidError> {
-    let first = &input[0x36..=0x47];
-    let second = &input[0x48..=0x59];
-    let third = &input[0x5A..=0x6B];
-    let fourth = &input[0x6C..=0x7D];
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+pub(crate) fn parse(
+    input: &[u8],
+    version: &EdidVersion,
+) -> Result<EighteenByteDescriptors, EdidError> {
+    let interpretation = version.interpretation();
+
+    let descriptor = |n: u8| -> Result<EighteenByteBlock, EdidError> {
+        let (offset, bytes) = nth(input, n)?;
+        one(bytes, input, interpretation).map_err(|e| e.within(0, Section::Descriptor(n), offset))
+    };
 
     // in EDID v1.3 and v1.4, the first 18-byte block will have the display's
     // preferred timings.
     //
     // however, this isn't always the case on earlier versions, so the name may
     // not match the type.
-    let preferred_timing_mode = one(first.try_into()?, input)?;
+    let preferred_timing_mode = descriptor(1)?;
     if matches!(preferred_timing_mode, EighteenByteBlock::Display(_)) {
-        tracing::warn!(
-            "The first 18-byte block was not a preferred timing descriptor. \
-        In EDID v1.3 and v1.4, this is not conformant with the standard."
-        );
+        if interpretation >= Interpretation::V1_3 {
+            tracing::warn!(
+                "The first 18-byte block was not a preferred timing descriptor. \
+            In EDID v1.3 and v1.4, this is not conformant with the standard."
+            );
+        } else {
+            tracing::debug!("This pre-v1.3 EDID has no preferred timing descriptor.");
+        }
     }
 
-    let blocks = [
-        one(second.try_into()?, input)?,
-        one(third.try_into()?, input)?,
-        one(fourth.try_into()?, input)?,
-    ];
+    let blocks = [descriptor(2)?, descriptor(3)?, descriptor(4)?];
 
     Ok(EighteenByteDescriptors {
         preferred_timing_mode,
         blocks,
+        interpretation,
     })
 }
 
-/// Parses the given eighteen-byte block.
-#[tracing::instrument(skip_all)]
-fn one(input: &[u8; 18], edid: &[u8]) -> Result<EighteenByteBlock, EdidError> {
-    // if the first two bytes aren't both zero, it's a timing definition
-    if input[0] != 0x00 && input[1] != 0x00 {
-        return Ok(EighteenByteBlock::Timing(preferred_tm::parse(input)?));
+/// Checks the header and tag of each of the four 18-byte descriptors,
+/// without parsing what's inside them.
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+pub(crate) fn check_headers(input: &[u8], version: &EdidVersion) -> Result<(), EdidError> {
+    let interpretation = version.interpretation();
+
+    for n in 1..=4 {
+        let (offset, bytes) = nth(input, n)?;
+        check_header(bytes, interpretation)
+            .map_err(|e| e.within(0, Section::Descriptor(n), offset))?;
     }
 
-    // otherwise, we're making a display descriptor.
-    {
-        // let's check the reserved bytes
-        let header = &input[0..5];
-        if !matches!(header, [0x00, 0x00, 0x00, _, 0x00]) {
-            tracing::error!("Given descriptor data had a malformed header: {header:x?}");
-            return Err(EdidError::DescriptorUnexpectedHeader(header.try_into()?));
-        }
+    Ok(())
+}
+
+/// Finds the offset and bytes of descriptor `n`, counting from one.
+fn nth(input: &[u8], n: u8) -> Result<(u16, &[u8; 18]), EdidError> {
+    // the four descriptors start at 0x36, one after another
+    let offset = 0x36 + 18 * u16::from(n - 1);
+    let bytes = input[usize::from(offset)..usize::from(offset) + 18].try_into()?;
+    Ok((offset, bytes))
+}
+
+/// Parses the given eighteen-byte block.
+#[cfg_attr(feature = "tracing", tracing::instrument(skip(input, edid)))]
+fn one(
+    input: &[u8; 18],
+    edid: &[u8],
+    interpretation: Interpretation,
+) -> Result<EighteenByteBlock, EdidError> {
+    // this also refuses bad headers and reserved tags
+    if !check_header(input, interpretation)? {
+        return Ok(EighteenByteBlock::Timing(preferred_tm::parse(input)?));
     }
 
     // okay! we've confirmed that it's a valid descriptor.
@@ -67,7 +90,9 @@ fn one(input: &[u8; 18], edid: &[u8]) -> Result<EighteenByteBlock, EdidError> {
         0xFC => DisplayDescriptor::ProductName(_13_byte_string::parse(input)?),
 
         // others
-        0xFD => DisplayDescriptor::DisplayRangeLimits(range_limits::parse(input, edid)?),
+        0xFD => {
+            DisplayDescriptor::DisplayRangeLimits(range_limits::parse(input, edid, interpretation)?)
+        }
         0xFB => descriptors::color_point::parse(input),
         0xFA => descriptors::more_std_timings::parse(input)?,
         0xF9 => descriptors::dcm::parse(input),
@@ -88,13 +113,124 @@ fn one(input: &[u8; 18], edid: &[u8]) -> Result<EighteenByteBlock, EdidError> {
             DisplayDescriptor::Manufacturer { data: *input }
         }
 
-        // errors
-        tag if (0x11..=0xF6).contains(&tag) => {
-            tracing::error!("EDID supplied an 18-byte descriptor that used a reserved tag. (tag: ");
-            return Err(EdidError::DescriptorUsedReservedKind { kind_byte });
+        // `check_header` already refused the reserved tags
+        _ => {
+            return Err(
+                EdidError::from(EdidErrorKind::DescriptorUsedReservedKind { kind_byte }).at(3),
+            )
         }
-        ty => todo!("this descriptor type (`{ty:x}`) is unimplemented!"),
     };
 
     Ok(EighteenByteBlock::Display(desc))
 }
+
+/// Checks an 18-byte block's header and tag.
+///
+/// Returns whether it's a display descriptor, rather than a detailed timing.
+fn check_header(input: &[u8; 18], interpretation: Interpretation) -> Result<bool, EdidError> {
+    // if the first two bytes aren't both zero, it's a timing definition
+    if input[0] != 0x00 || input[1] != 0x00 {
+        return Ok(false);
+    }
+
+    // otherwise, it's a display descriptor.
+    {
+        // let's check the reserved bytes.
+        //
+        // range limits keep their offset flags in the last one, though
+        let header = &input[0..5];
+        if !matches!(
+            header,
+            [0x00, 0x00, 0x00, _, 0x00] | [0x00, 0x00, 0x00, 0xFD, _]
+        ) {
+            tracing::error!("Given descriptor data had a malformed header: {header:x?}");
+
+            // range limits can use byte 4, but nothing else can
+            let wrong = (0..header.len())
+                .find(|&i| header[i] != 0x00 && i != 3 && !(i == 4 && header[3] == 0xFD))
+                .unwrap_or_default();
+            return Err(EdidError::from(EdidErrorKind::DescriptorUnexpectedHeader(
+                header.try_into()?,
+            ))
+            .at(wrong as u16));
+        }
+    }
+
+    // these tags were reserved until v1.4
+    let kind_byte = input[3];
+    if (0xF7..=0xF9).contains(&kind_byte) && !interpretation.is_v1_4() {
+        tracing::error!(
+            "This EDID used descriptor tag `{kind_byte:x}`, which is reserved \
+        before EDID v1.4."
+        );
+        return Err(EdidError::from(EdidErrorKind::DescriptorUsedReservedKind { kind_byte }).at(3));
+    }
+
+    if (0x11..=0xF6).contains(&kind_byte) {
+        tracing::error!(
+            "EDID supplied an 18-byte descriptor that used a reserved tag. (tag: `{kind_byte:x}`)"
+        );
+        return Err(EdidError::from(EdidErrorKind::DescriptorUsedReservedKind { kind_byte }).at(3));
+    }
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a timing is only a descriptor when both clock bytes are zero, so
+    /// either one alone being zero is still a timing
+    #[test]
+    fn timing_with_a_zero_clock_byte() {
+        logger();
+        let input = raw_edid_by_filename("dell_s2417dg.raw.input");
+        let version = crate::parser::version::parse(&input).unwrap();
+
+        for (clock, khz) in [([0x00, 0x5E], 0x5E00), ([0x56, 0x00], 0x0056)] {
+            let mut broken = input.clone();
+            broken[0x36..0x38].copy_from_slice(&clock);
+
+            let blocks = parse(&broken, &version).unwrap();
+            let EighteenByteBlock::Timing(t) = blocks.preferred_timing_mode else {
+                panic!("{clock:x?} should still be a timing");
+            };
+            assert_eq!(t.pixel_clock_khz, khz);
+            assert_eq!(t.horizontal_addressable_video_px, 2560);
+        }
+    }
+
+    /// range limits keep their offset flags in byte 4, which is reserved in
+    /// every other descriptor
+    #[test]
+    fn range_limit_offset_header() {
+        logger();
+        let mut input = raw_edid_by_filename("dell_s2417dg.raw.input");
+        let version = crate::parser::version::parse(&input).unwrap();
+
+        // the max vertical rate is offset by 255
+        input[0x5A + 4] = 0b0000_0010;
+        let blocks = parse(&input, &version).unwrap();
+        let EighteenByteBlock::Display(DisplayDescriptor::DisplayRangeLimits(ref desc)) =
+            blocks.blocks[1]
+        else {
+            panic!("the dell has range limits");
+        };
+        assert_eq!(desc.limits().max_v_rate_hz, 144 + 255);
+        assert_eq!(
+            desc.limits().offsets.vertical,
+            VerticalOffset::Max255Hz_MinNotOffset
+        );
+
+        // but the product name can't use it
+        input[0x6C + 4] = 0b0000_0010;
+        let e = parse(&input, &version).unwrap_err();
+        assert!(matches!(
+            e.kind,
+            EdidErrorKind::DescriptorUnexpectedHeader([0x00, 0x00, 0x00, 0xFC, 0x02])
+        ));
+        assert_eq!(e.context.section, Some(Section::Descriptor(4)));
+        assert_eq!(e.context.offset, Some(0x70));
+    }
+}
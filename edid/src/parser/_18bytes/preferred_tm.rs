@@ -1,24 +1,17 @@
-use bitvec::{
-    array::BitArray,
-    field::BitField,
-    order::{Lsb0, Msb0},
-    view::BitView,
-};
+use bitvec::{field::BitField, order::Lsb0, view::BitView};
 
-use crate::prelude::internal::*;
+use crate::{parser::le, prelude::internal::*};
 
 /// Parses out a Detailed Timing Definition from the given 18 bytes.
 ///
 /// This must only ever be called when at least one of the first two bytes are
 /// non-zero.
-#[tracing::instrument(skip_all)]
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
 pub(super) fn parse(bytes: &[u8; 18]) -> Result<DetailedTimingDefinition, EdidError> {
     // ensure the first two bytes are >= [0x00, 0x01]
     if [bytes[0], bytes[1]] == [0x00, 0x00] {
         tracing::error!("passed wrong 18 byte desc. please report this with logs.");
-        return Err(EdidError::DescriptorUnexpectedHeader(
-            bytes[0..5].try_into()?,
-        ));
+        return Err(EdidErrorKind::DescriptorUnexpectedHeader(bytes[0..5].try_into()?).into());
     }
 
     // grab various components of the def
@@ -35,45 +28,23 @@ pub(super) fn parse(bytes: &[u8; 18]) -> Result<DetailedTimingDefinition, EdidEr
     let horizontal_front_porch: u16 = {
         let upper = shared_bits[6..=7].load::<u8>();
         let lower = bytes[8];
-        bytemuck::must_cast([lower, upper])
+        le::read_u16([lower, upper])
     }; // u12
     let horizontal_sync_pulse_width_px: u16 = {
         let upper = shared_bits[4..=5].load::<u8>();
         let lower = bytes[9];
-        bytemuck::must_cast([lower, upper])
+        le::read_u16([lower, upper])
     }; // u12
     let vertical_front_porch_lines: u8 = {
-        let upper_half = bytes[10].view_bits::<Lsb0>();
-        let mut arr: BitArray<u8, Msb0> = BitArray::ZERO;
-
-        // lower u4
-        arr.set(0x05, upper_half[7]);
-        arr.set(0x04, upper_half[6]);
-        arr.set(0x03, upper_half[5]);
-        arr.set(0x02, upper_half[4]);
-
-        // upper u2
-        arr.set(0x01, shared_bits[2]);
-        arr.set(0x00, shared_bits[3]);
-
-        arr.load::<u8>()
+        let upper = shared_bits[2..=3].load::<u8>();
+        let lower = bytes[10].view_bits::<Lsb0>()[4..=7].load::<u8>();
+        (upper << 4) | lower
     }; // really a u6
     let vertical_sync_pulse_width_lines: u8 = {
-        let lower_half = bytes[0x10].view_bits::<Lsb0>();
-        let mut arr: BitArray<u8, Msb0> = BitArray::ZERO;
-
-        // lower u4
-        arr.set(5, lower_half[3]);
-        arr.set(4, lower_half[2]);
-        arr.set(3, lower_half[1]);
-        arr.set(2, lower_half[0]);
-
-        // upper u2
-        arr.set(1, shared_bits[0]);
-        arr.set(0, shared_bits[1]);
-
-        arr.load::<u8>()
-    };
+        let upper = shared_bits[0..=1].load::<u8>();
+        let lower = bytes[10].view_bits::<Lsb0>()[0..=3].load::<u8>();
+        (upper << 4) | lower
+    }; // also a u6
 
     // video image size/border defs
     //
@@ -120,35 +91,33 @@ pub(super) fn parse(bytes: &[u8; 18]) -> Result<DetailedTimingDefinition, EdidEr
 }
 
 /// Calcluates the pixel clock for the [0x00, 0x01] bytes
-#[tracing::instrument]
+#[cfg_attr(feature = "tracing", tracing::instrument)]
 fn pixel_clock(bytes: &[u8; 2]) -> u16 {
-    bytes.view_bits::<Msb0>().load::<u16>()
+    le::read_u16(*bytes)
 }
 
 /// Calculates the combined `u12` value for a field that uses the upper nibble.
-#[tracing::instrument]
+#[cfg_attr(feature = "tracing", tracing::instrument)]
 fn upper_nibble(shared_byte: u8, byte: u8) -> u16 {
     // grab the upper four bits. that's the upper u4 part
     let upper = shared_byte.view_bits::<Lsb0>()[0x04..=0x07].load::<u8>();
 
     // combine it with the real byte
-    let combined: u16 = bytemuck::must_cast([byte, upper]);
-    combined
+    le::read_u12(byte, upper)
 }
 
 /// Calculates the combined `u12` value for a field that uses the lower nibble.
-#[tracing::instrument]
+#[cfg_attr(feature = "tracing", tracing::instrument)]
 fn lower_nibble(shared_byte: u8, byte: u8) -> u16 {
-    // grab the upper four bits. that's the upper u4 part
+    // grab the lower four bits. that's the upper u4 part
     let upper = shared_byte.view_bits::<Lsb0>()[0x00..=0x03].load::<u8>();
 
     // combine it with the real byte
-    let combined: u16 = bytemuck::must_cast([byte, upper]);
-    combined
+    le::read_u12(byte, upper)
 }
 
 /// Finds the "part 2" section of the detailed timing definition.
-#[tracing::instrument]
+#[cfg_attr(feature = "tracing", tracing::instrument)]
 fn part_2(byte: u8) -> (SignalInterfaceType, StereoViewingSupport, SyncSignal) {
     // grab it as bits
     let bits = byte.view_bits::<Lsb0>(); // lsb0 to make bits[0x07] = bit 7
@@ -236,6 +205,7 @@ mod tests {
         assert_eq!(lower_nibble(shared, byte), 0b0000_0001_1111_0000);
     }
 
+    #[test]
     fn _sam02e3_2c47316eff13_preferred_tm() {
         logger();
         let path = "linuxhw_edid_EDID_Digital_Samsung_SAM02E3_2C47316EFF13.input";
@@ -247,7 +217,7 @@ mod tests {
 
         // prepare yourself
         let expected = DetailedTimingDefinition {
-            pixel_clock_khz: 10650_u16,
+            pixel_clock_khz: 10650_u16, // 106.5 MHz
 
             horizontal_addressable_video_px: 1440,
             horizontal_blanking_px: 80 + 152 + 232, // just sum the h values
@@ -272,4 +242,35 @@ mod tests {
 
         assert_eq!(got, expected);
     }
+
+    /// the vertical porch and sync share a byte, split into nibbles
+    #[test]
+    fn dell_s2417dg_vertical_sync() {
+        logger();
+        let input = raw_edid_by_filename("dell_s2417dg.raw.input");
+        let bytes: [u8; 18] = input[0x36..=0x47].try_into().unwrap();
+        let got = super::parse(&bytes).unwrap();
+
+        assert_eq!(got.horizontal_front_porch, 48);
+        assert_eq!(got.horizontal_sync_pulse_width_px, 32);
+        assert_eq!(got.vertical_front_porch_lines, 3);
+        assert_eq!(got.vertical_sync_pulse_width_lines, 5);
+    }
+
+    /// the vertical porch and sync come from byte 10 and the bottom of byte
+    /// 11. byte 0x10 is the vertical border, which once got read instead
+    #[test]
+    fn vertical_nibbles_ignore_border() {
+        logger();
+        let mut bytes = [0_u8; 18];
+        bytes[0] = 0x01; // any non-zero clock
+        bytes[10] = 0x35; // porch 0x3, sync 0x5
+        bytes[11] = 0b0000_0110; // porch upper 0b01, sync upper 0b10
+        bytes[0x10] = 0xFF; // a vertical border to trip over
+
+        let got = super::parse(&bytes).unwrap();
+        assert_eq!(got.vertical_front_porch_lines, 0x13);
+        assert_eq!(got.vertical_sync_pulse_width_lines, 0x25);
+        assert_eq!(got.vertical_border_lines, 0xFF);
+    }
 }
@@ -3,26 +3,43 @@ use bitvec::{order::Lsb0, slice::BitSlice};
 use crate::prelude::internal::*;
 
 /// Parses out some basic info about the display.
-#[tracing::instrument(skip_all)]
-pub(super) fn parse(input: &[u8]) -> Result<BasicDisplayInfo, EdidError> {
-    let input_definition = video_input_definition(input[0x14])?;
-    let screen_size_or_aspect_ratio = size_or_ratio(input);
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+pub(crate) fn parse(input: &[u8], version: &EdidVersion) -> Result<BasicDisplayInfo, EdidError> {
+    let interpretation = version.interpretation();
+
+    let input_definition = video_input_definition(input[0x14], interpretation)?;
+    let screen_size_or_aspect_ratio = size_or_ratio(input, interpretation);
     let reported_gamma = gamma(input);
-    let feature_support = feature_support(input);
+    let feature_support = feature_support(input, interpretation);
 
     Ok(BasicDisplayInfo {
         input_definition,
         screen_size_or_aspect_ratio,
         reported_gamma,
         feature_support,
+        interpretation,
     })
 }
 
-#[tracing::instrument]
-fn video_input_definition(byte: u8) -> Result<VideoSignalInterface, EdidError> {
+#[cfg_attr(feature = "tracing", tracing::instrument)]
+fn video_input_definition(
+    byte: u8,
+    interpretation: Interpretation,
+) -> Result<VideoSignalInterface, EdidError> {
     // using lsb to keep the bit numbering consistent.
     let bits: &BitSlice<u8, Lsb0> = BitSlice::from_element(&byte);
 
+    // before v1.4, digital inputs only had one meaningful bit
+    fn legacy_digital(bits: &BitSlice<u8, Lsb0>) -> VideoSignalInterface {
+        if bits[1..=6].any() {
+            tracing::warn!("Digital input definition used bits reserved before EDID v1.4.");
+        }
+
+        VideoSignalInterface::LegacyDigital {
+            dfp_1x_compatible: bits[0],
+        }
+    }
+
     fn digital(bits: &BitSlice<u8, Lsb0>) -> Result<VideoSignalInterface, EdidError> {
         let color_bit_depth = match (bits[6], bits[5], bits[4]) {
             (false, false, false) => ColorBitDepth::Undefined,
@@ -51,7 +68,9 @@ fn video_input_definition(byte: u8) -> Result<VideoSignalInterface, EdidError> {
                 [false, true, false, true] => SupportedVideoInterface::DisplayPort,
                 reserved => {
                     tracing::error!("Got an unexpected digital video interface standard bit layout: `{reserved:#?}`");
-                    return Err(EdidError::BasicInfoBadInterface(di_bits));
+                    return Err(
+                        EdidError::from(EdidErrorKind::BasicInfoBadInterface(di_bits)).at(0),
+                    );
                 }
             })
         };
@@ -96,19 +115,31 @@ fn video_input_definition(byte: u8) -> Result<VideoSignalInterface, EdidError> {
     }
 
     // 0 if analog, 1 if digital
-    if bits[7] {
+    if bits[7] && !interpretation.is_v1_4() {
+        Ok(legacy_digital(bits))
+    } else if bits[7] {
         digital(bits)
     } else {
         Ok(analog(bits))
     }
 }
 
-#[tracing::instrument(skip_all)]
-fn size_or_ratio(input: &[u8]) -> Option<SizeOrRatio> {
+#[cfg_attr(feature = "tracing", tracing::instrument(skip(input)))]
+fn size_or_ratio(input: &[u8], interpretation: Interpretation) -> Option<SizeOrRatio> {
     match (input[0x15], input[0x16]) {
         // when both are 0x00, the screen's size isn't given or may be dynamic
         (0x00, 0x00) => None,
 
+        // aspect ratios are new in v1.4. before that, a zero on either side
+        // just means the size is undefined
+        (0x00, _) | (_, 0x00) if !interpretation.is_v1_4() => {
+            tracing::warn!(
+                "Screen size had a single zero value, but aspect ratios are \
+            only available in EDID v1.4. Treating it as undefined."
+            );
+            None
+        }
+
         // if vertical is 0x00, then horizontal is the landscape aspect ratio
         (horizontal, 0x00) => {
             tracing::debug!("landscape aspect ratio, given: `0x{horizontal:x}` (`{horizontal}`)");
@@ -143,7 +174,7 @@ fn size_or_ratio(input: &[u8]) -> Option<SizeOrRatio> {
 ///
 /// To get landscape, pattern match the return value as `(hoz, vert)`. For
 /// portrait, it's `(vert, hoz)`.
-#[tracing::instrument]
+#[cfg_attr(feature = "tracing", tracing::instrument)]
 fn make_ratio(ar: u8) -> Option<(u16, u16)> {
     // note: these values are calculated by dividing one side by the other,
     // then rounding to three decimal places.
@@ -183,8 +214,8 @@ fn make_ratio(ar: u8) -> Option<(u16, u16)> {
 ///
 /// Note that if this is `None`, the display should provide an extension
 /// containing the value.
-#[tracing::instrument(skip_all)]
-fn gamma(input: &[u8]) -> Option<Decimal> {
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+fn gamma(input: &[u8]) -> Option<Ratio<u16>> {
     let byte = input[0x17];
     tracing::debug!("Got byte: 0x{byte:x}");
 
@@ -201,12 +232,12 @@ fn gamma(input: &[u8]) -> Option<Decimal> {
         }
 
         // reverse from the standard: byte = (GAMMA x 100) – 100
-        Some((Decimal::from(byte) + Decimal::from(100)) / Decimal::from(100))
+        Some(Ratio::new(u16::from(byte) + 100, 100))
     }
 }
 
-#[tracing::instrument(skip_all)]
-fn feature_support(input: &[u8]) -> FeatureSupport {
+#[cfg_attr(feature = "tracing", tracing::instrument(skip(input)))]
+fn feature_support(input: &[u8], interpretation: Interpretation) -> FeatureSupport {
     // again, using `Lsb0` despite standard being Msb0.
     //
     // this lets me use their numbering
@@ -220,7 +251,10 @@ fn feature_support(input: &[u8]) -> FeatureSupport {
     };
 
     // get color based on if we're analog/digital...
-    let color_support = if BitSlice::<u8, Lsb0>::from_element(&input[0x14])[7] {
+    //
+    // (only v1.4 digital displays get encoding formats. others use the type)
+    let is_digital = BitSlice::<u8, Lsb0>::from_element(&input[0x14])[7];
+    let color_support = if is_digital && interpretation.is_v1_4() {
         // digital gets a color encoding!
         let formats = match (bits[4], bits[3]) {
             (false, false) => ColorEncodingFormats::Rgb444,
@@ -245,7 +279,21 @@ fn feature_support(input: &[u8]) -> FeatureSupport {
     // other feature support flags
     let srgb_std = bits[2];
     let says_pixel_format_and_refresh = bits[1];
-    let is_continuous_freq = bits[0];
+
+    // bit 0 was "default gtf supported" before becoming "continuous freq." in
+    // v1.4
+    let (is_continuous_freq, supports_default_gtf) = if interpretation.is_v1_4() {
+        (bits[0], false)
+    } else {
+        (false, bits[0])
+    };
+
+    if interpretation == Interpretation::V1_3 && !says_pixel_format_and_refresh {
+        tracing::warn!(
+            "EDID v1.3 requires the preferred timing mode bit to be set, but it \
+        wasn't."
+        );
+    }
 
     FeatureSupport {
         power_management,
@@ -253,6 +301,7 @@ fn feature_support(input: &[u8]) -> FeatureSupport {
         srgb_std,
         says_pixel_format_and_refresh,
         is_continuous_freq,
+        supports_default_gtf,
     }
 }
 
@@ -283,7 +332,7 @@ mod tests {
     fn dell_s2417dg_vsi() {
         logger();
         let input = crate::prelude::internal::raw_edid_by_filename("dell_s2417dg.raw.input");
-        let got = super::video_input_definition(input[0x14]).unwrap();
+        let got = super::video_input_definition(input[0x14], Interpretation::V1_4).unwrap();
 
         let expected = VideoSignalInterface::Digital {
             color_bit_depth: ColorBitDepth::D8Bits,
@@ -297,7 +346,7 @@ mod tests {
     fn that_guys_laptop_vsi() {
         logger();
         let input = crate::prelude::internal::edid_by_filename("1.input");
-        let got = super::video_input_definition(input[0x14]).unwrap();
+        let got = super::video_input_definition(input[0x14], Interpretation::V1_4).unwrap();
 
         let expected = VideoSignalInterface::Digital {
             color_bit_depth: ColorBitDepth::D6Bits,
@@ -311,7 +360,7 @@ mod tests {
     fn dell_s2417dg_sizeratio() {
         logger();
         let input = crate::prelude::internal::raw_edid_by_filename("dell_s2417dg.raw.input");
-        let got = super::size_or_ratio(&input).unwrap();
+        let got = super::size_or_ratio(&input, Interpretation::V1_4).unwrap();
 
         let expected = SizeOrRatio::ScreenSize {
             vertical_cm: 30,
@@ -325,7 +374,7 @@ mod tests {
     fn that_guys_laptop_sizeratio() {
         logger();
         let input = crate::prelude::internal::edid_by_filename("1.input");
-        let got = super::size_or_ratio(&input).unwrap();
+        let got = super::size_or_ratio(&input, Interpretation::V1_4).unwrap();
 
         let expected = SizeOrRatio::ScreenSize {
             vertical_cm: 17,
@@ -339,7 +388,7 @@ mod tests {
     fn display_w_aspect_ratio() {
         logger();
         let input = edid_by_filename("linuxhw_edid_Digital_BOE_BOE07AF_BD22D8FDF96B.input");
-        let got = super::size_or_ratio(&input).unwrap();
+        let got = super::size_or_ratio(&input, Interpretation::V1_4).unwrap();
 
         let expected = SizeOrRatio::AspectRatio {
             horizontal: 16,
@@ -373,7 +422,7 @@ mod tests {
         logger();
         let input = crate::prelude::internal::raw_edid_by_filename("dell_s2417dg.raw.input");
         let got = super::gamma(&input).unwrap();
-        let expected = dec!(2.20);
+        let expected = Ratio::new(220, 100);
 
         assert_eq!(got, expected);
     }
@@ -383,7 +432,7 @@ mod tests {
         logger();
         let input = crate::prelude::internal::edid_by_filename("1.input");
         let got = super::gamma(&input).unwrap();
-        let expected = dec!(2.20);
+        let expected = Ratio::new(220, 100);
 
         assert_eq!(got, expected);
     }
@@ -395,7 +444,7 @@ mod tests {
             "linuxhw_edid_EDID_Digital_Sony_SNY05FA_93D328459FF6.input",
         );
         let got = super::gamma(&input).unwrap();
-        let expected = dec!(1.0);
+        let expected = Ratio::from_integer(1);
 
         assert_eq!(got, expected);
     }
@@ -405,7 +454,7 @@ mod tests {
         logger();
         let input = crate::prelude::internal::raw_edid_by_filename("dell_s2417dg.raw.input");
 
-        let got = super::feature_support(&input);
+        let got = super::feature_support(&input, Interpretation::V1_4);
         let expected = FeatureSupport {
             power_management: PowerManagement {
                 standby: false,
@@ -416,6 +465,7 @@ mod tests {
             srgb_std: true,
             says_pixel_format_and_refresh: true,
             is_continuous_freq: false,
+            supports_default_gtf: false,
         };
 
         assert_eq!(got, expected);
@@ -426,7 +476,7 @@ mod tests {
         logger();
         let input = crate::prelude::internal::edid_by_filename("1.input");
 
-        let got = super::feature_support(&input);
+        let got = super::feature_support(&input, Interpretation::V1_4);
         let expected = FeatureSupport {
             power_management: PowerManagement {
                 standby: false,
@@ -437,6 +487,7 @@ mod tests {
             srgb_std: false,
             says_pixel_format_and_refresh: true,
             is_continuous_freq: false,
+            supports_default_gtf: false,
         };
 
         assert_eq!(got, expected);
@@ -449,7 +500,7 @@ mod tests {
             "linuxhw_edid_EDID_Digital_Sony_SNY05FA_93D328459FF6.input",
         );
 
-        let got = super::feature_support(&input);
+        let got = super::feature_support(&input, Interpretation::V1_4);
         let expected = FeatureSupport {
             power_management: PowerManagement {
                 standby: true,
@@ -460,6 +511,7 @@ mod tests {
             srgb_std: false,
             says_pixel_format_and_refresh: true,
             is_continuous_freq: false,
+            supports_default_gtf: false,
         };
 
         assert_eq!(got, expected);
@@ -473,7 +525,8 @@ mod tests {
             "linuxhw_edid_EDID_Digital_Samsung_SAM02E3_2C47316EFF13.input",
         );
 
-        let got = super::parse(&input).unwrap();
+        let version = crate::parser::version::parse(&input).unwrap();
+        let got = super::parse(&input, &version).unwrap();
         let expected = BasicDisplayInfo {
             // this is gonna be a long one lol
             input_definition: VideoSignalInterface::Digital {
@@ -484,7 +537,7 @@ mod tests {
                 horizontal_cm: 37,
                 vertical_cm: 23,
             }),
-            reported_gamma: Some(dec!(2.35)),
+            reported_gamma: Some(Ratio::new(235, 100)),
             feature_support: FeatureSupport {
                 power_management: PowerManagement {
                     standby: false,
@@ -495,9 +548,79 @@ mod tests {
                 color_support: ColorSupport::EncodingFormats(ColorEncodingFormats::Rgb444),
                 says_pixel_format_and_refresh: true,
                 is_continuous_freq: false,
+                supports_default_gtf: false,
             },
+            interpretation: Interpretation::V1_4,
         };
 
         assert_eq!(got, expected);
     }
+
+    #[test]
+    fn msiaf82_4b2991d4299a_v1_3_digital() {
+        logger();
+        let input = edid_by_filename("linuxhw_edid_Digital_MSI_MSIAF82_4B2991D4299A.input");
+        let version = crate::parser::version::parse(&input).unwrap();
+        let got = super::parse(&input, &version).unwrap();
+
+        // no bit depth or interface before v1.4
+        assert_eq!(
+            got.input_definition,
+            VideoSignalInterface::LegacyDigital {
+                dfp_1x_compatible: true
+            }
+        );
+
+        // ...and digital displays still report a color type
+        assert_eq!(
+            got.feature_support.color_support,
+            ColorSupport::Type(ColorType::RgbColor)
+        );
+        assert!(!got.feature_support.is_continuous_freq);
+        assert_eq!(got.interpretation, Interpretation::V1_3);
+    }
+
+    #[test]
+    fn aoc0320_455954e7ca14_v1_3_analog() {
+        logger();
+        let input = edid_by_filename("linuxhw_edid_Analog_AOC_AOC0320_455954E7CA14.input");
+        let version = crate::parser::version::parse(&input).unwrap();
+        let got = super::parse(&input, &version).unwrap();
+
+        assert_eq!(
+            got.screen_size_or_aspect_ratio,
+            Some(SizeOrRatio::ScreenSize {
+                horizontal_cm: 62,
+                vertical_cm: 34,
+            })
+        );
+        assert!(!got.feature_support.supports_default_gtf);
+        assert_eq!(got.interpretation, Interpretation::V1_3);
+    }
+
+    /// on v1.3, bit 0 is about gtf rather than continuous frequency
+    #[test]
+    fn v1_3_default_gtf() {
+        logger();
+        let mut input = edid_by_filename("linuxhw_edid_Analog_AOC_AOC0320_455954E7CA14.input");
+        input[0x18] |= 0b0000_0001;
+
+        let got = super::feature_support(&input, Interpretation::V1_3);
+        assert!(got.supports_default_gtf);
+        assert!(!got.is_continuous_freq);
+
+        let got = super::feature_support(&input, Interpretation::V1_4);
+        assert!(!got.supports_default_gtf);
+        assert!(got.is_continuous_freq);
+    }
+
+    /// aspect ratios don't exist before v1.4
+    #[test]
+    fn v1_3_no_aspect_ratio() {
+        logger();
+        let input = edid_by_filename("linuxhw_edid_Digital_BOE_BOE07AF_BD22D8FDF96B.input");
+
+        assert!(super::size_or_ratio(&input, Interpretation::V1_3).is_none());
+        assert!(super::size_or_ratio(&input, Interpretation::V1_4).is_some());
+    }
 }
@@ -5,8 +5,8 @@ use crate::prelude::internal::*;
 use crate::color::{ColorCharacteristics, ColorCoordinate};
 
 /// Finds the approximate color characteristics (coordinates) for this display.
-#[tracing::instrument(skip_all)]
-pub(super) fn parse(input: &[u8]) -> ColorCharacteristics {
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+pub(crate) fn parse(input: &[u8]) -> ColorCharacteristics {
     // yo head to page 28 (as of nov. 2024) to know what's going on
     let _0x19 = input[0x19].view_bits::<Lsb0>();
     let _0x1a = input[0x1A].view_bits::<Lsb0>();
@@ -15,53 +15,53 @@ pub(super) fn parse(input: &[u8]) -> ColorCharacteristics {
     let rx = {
         let (rx1, rx0) = (_0x19[7], _0x19[6]);
         let rx_etc = input[0x1b];
-        into_decimal(make_u10(rx1, rx0, rx_etc))
+        into_ratio(make_u10(rx1, rx0, rx_etc))
     };
 
     let ry = {
         let (ry1, ry0) = (_0x19[5], _0x19[4]);
         let ry_etc = input[0x1c];
-        into_decimal(make_u10(ry1, ry0, ry_etc))
+        into_ratio(make_u10(ry1, ry0, ry_etc))
     };
 
     // 🟢️ green!
     let gx = {
         let (gx1, gx0) = (_0x19[3], _0x19[2]);
         let gx_etc = input[0x1D];
-        into_decimal(make_u10(gx1, gx0, gx_etc))
+        into_ratio(make_u10(gx1, gx0, gx_etc))
     };
 
     let gy = {
         let (gy1, gy0) = (_0x19[1], _0x19[0]);
         let gy_etc = input[0x1E];
-        into_decimal(make_u10(gy1, gy0, gy_etc))
+        into_ratio(make_u10(gy1, gy0, gy_etc))
     };
 
     // 🔵️ blue
     let bx = {
         let (bx1, bx0) = (_0x1a[7], _0x1a[6]);
         let bx_etc = input[0x1F];
-        into_decimal(make_u10(bx1, bx0, bx_etc))
+        into_ratio(make_u10(bx1, bx0, bx_etc))
     };
 
     // blue y.
     let by = {
         let (by1, by0) = (_0x1a[5], _0x1a[4]);
         let by_etc = input[0x20];
-        into_decimal(make_u10(by1, by0, by_etc))
+        into_ratio(make_u10(by1, by0, by_etc))
     };
 
     // 🤍️ finally, we can do the white coords. <3 b/c the circle rendered weird
     let wx = {
         let (wx1, wx0) = (_0x1a[3], _0x1a[2]);
         let wx_etc = input[0x21];
-        into_decimal(make_u10(wx1, wx0, wx_etc))
+        into_ratio(make_u10(wx1, wx0, wx_etc))
     };
 
     let wy = {
         let (wy1, wy0) = (_0x1a[1], _0x1a[0]);
         let wy_etc = input[0x22];
-        into_decimal(make_u10(wy1, wy0, wy_etc))
+        into_ratio(make_u10(wy1, wy0, wy_etc))
     };
 
     // make the actual coords
@@ -81,7 +81,7 @@ pub(super) fn parse(input: &[u8]) -> ColorCharacteristics {
 /// Creates a "u10" (10 bit unsigned integer) in a `u16`.
 ///
 /// Used to combine the bits that make up coordinates.
-#[tracing::instrument]
+#[cfg_attr(feature = "tracing", tracing::instrument)]
 pub(crate) fn make_u10(_2nd_smallest: bool, smallest: bool, etc: u8) -> u16 {
     // make a place to store them all
     let mut bits: BitArray<u16, Lsb0> = BitArray::ZERO;
@@ -101,22 +101,21 @@ pub(crate) fn make_u10(_2nd_smallest: bool, smallest: bool, etc: u8) -> u16 {
     bits.load_be::<u16>()
 }
 
-/// Properly converts the given "u10" value into a decimal, then divides it
-/// by its length.
+/// Properly converts the given "u10" value into a ratio over its length.
 ///
-/// This makes a typical decimal. Do not call with greater than 1023 (u10's max).
-#[tracing::instrument]
-pub(crate) fn into_decimal(raw_value: u16) -> Decimal {
+/// This makes a typical fraction. Do not call with greater than 1023 (u10's max).
+#[cfg_attr(feature = "tracing", tracing::instrument)]
+pub(crate) fn into_ratio(raw_value: u16) -> Ratio<u32> {
     debug_assert!(raw_value <= 0b11_1111_1111, "otherwise ur calling it wrong");
     let len = 2_u16.pow(10); // 10 binary digits
-    Decimal::from(raw_value) / Decimal::from(len)
+    Ratio::new(raw_value.into(), len.into())
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
-    use crate::{color::ColorCoordinate, logger, parser::color::into_decimal};
+    use crate::{color::ColorCoordinate, logger, parser::color::into_ratio};
 
     /// it should be comprised only of my ones
     #[test]
@@ -132,32 +131,28 @@ mod tests {
 
     /// make sure the function is behaving according to spec
     #[test]
-    fn into_decimal_endpts() {
+    fn into_ratio_endpts() {
         logger();
         let start = 0b00_0000_0000;
         let midpoint = 0b00_0001_1111;
         let end = 0b11_1111_1111;
 
-        assert_eq!(into_decimal(start), dec!(0));
-        assert_eq!(
-            into_decimal(midpoint),
-            Decimal::from(31) / Decimal::from(1024)
-        );
-        assert_eq!(into_decimal(end), Decimal::from(1023) / Decimal::from(1024));
+        assert_eq!(into_ratio(start), Ratio::from_integer(0));
+        assert_eq!(into_ratio(midpoint), Ratio::new(31, 1024));
+        assert_eq!(into_ratio(end), Ratio::new(1023, 1024));
     }
 
     /// samples from the standard
     #[test]
-    fn into_decimal_samples() {
+    fn into_ratio_samples() {
         let a = 0b10_0111_0001;
         let b = 0b01_0011_1010;
         let c = 0b00_1001_1010;
 
-        // note: i guess this isn't deterministic. these are FLAKEY unless you
-        // use the `deq` fn below
-        assert!(deq(into_decimal(a), dec!(0.6103516)));
-        assert!(deq(into_decimal(b), dec!(0.3066406)));
-        assert!(deq(into_decimal(c), dec!(0.1503906)));
+        // the standard rounds these to 0.6103516, 0.3066406, and 0.1503906
+        assert_eq!(into_ratio(a), Ratio::new(625, 1024));
+        assert_eq!(into_ratio(b), Ratio::new(314, 1024));
+        assert_eq!(into_ratio(c), Ratio::new(154, 1024));
     }
 
     /// test against the (unfortunately rounded) edid-decode values
@@ -168,35 +163,11 @@ mod tests {
         let colors = super::parse(&input);
         tracing::info!("colors: {:#?}", colors);
 
-        assert!(ceq(
-            colors.red,
-            ColorCoordinate::new(dec!(0.6396), dec!(0.3300))
-        ));
-        assert!(ceq(
-            colors.green,
-            ColorCoordinate::new(dec!(0.2998), dec!(0.5996))
-        ));
-        assert!(ceq(
-            colors.blue,
-            ColorCoordinate::new(dec!(0.1503), dec!(0.0595))
-        ));
-        assert!(ceq(
-            colors.white_point,
-            ColorCoordinate::new(dec!(0.3125), dec!(0.3291))
-        ));
-    }
-
-    /// helper func to compare decimals
-    #[tracing::instrument]
-    fn deq(d1: Decimal, d2: Decimal) -> bool {
-        let m = if d1 < d2 { d2 - d1 } else { d1 - d2 };
-        let s = dec!(0.0005);
-        m <= s || m >= s
-    }
-
-    /// helper func to compare the coords :)
-    #[tracing::instrument]
-    fn ceq(c1: ColorCoordinate, c2: ColorCoordinate) -> bool {
-        deq(c1.x, c2.x) && deq(c1.y, c2.y)
+        // edid-decode says (0.6396, 0.3300), and so on
+        let c = |x, y| ColorCoordinate::new(Ratio::new(x, 1024), Ratio::new(y, 1024));
+        assert_eq!(colors.red, c(655, 338));
+        assert_eq!(colors.green, c(307, 614));
+        assert_eq!(colors.blue, c(154, 61));
+        assert_eq!(colors.white_point, c(320, 337));
     }
 }
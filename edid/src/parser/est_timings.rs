@@ -6,7 +6,7 @@ use crate::prelude::internal::*;
 ///
 /// Note that the timings are hardcoded, mostly to have a lowest common
 /// denominator for hardware support.
-#[tracing::instrument]
+#[cfg_attr(feature = "tracing", tracing::instrument)]
 pub(crate) fn parse(input: &[u8]) -> EstablishedTimings {
     let i = est_i(input[0x23]);
     let ii = est_ii(input[0x24]);
@@ -20,7 +20,7 @@ pub(crate) fn parse(input: &[u8]) -> EstablishedTimings {
 }
 
 /// Grabs established timings. Call with byte `0x23`.
-#[tracing::instrument]
+#[cfg_attr(feature = "tracing", tracing::instrument)]
 fn est_i(byte: u8) -> EstablishedTimingsI {
     let bits = byte.view_bits::<Lsb0>();
     EstablishedTimingsI {
@@ -36,7 +36,7 @@ fn est_i(byte: u8) -> EstablishedTimingsI {
 }
 
 /// Grabs established timings. Call with byte `0x24`.
-#[tracing::instrument]
+#[cfg_attr(feature = "tracing", tracing::instrument)]
 fn est_ii(byte: u8) -> EstablishedTimingsII {
     let bits = byte.view_bits::<Lsb0>();
     EstablishedTimingsII {
@@ -55,7 +55,7 @@ fn est_ii(byte: u8) -> EstablishedTimingsII {
 /// timings themselves.
 ///
 /// Call with byte `0x25`.
-#[tracing::instrument]
+#[cfg_attr(feature = "tracing", tracing::instrument)]
 fn manufacturer(byte: u8) -> ManufacturerTimings {
     let bits = byte.view_bits::<Lsb0>();
     ManufacturerTimings {
@@ -1,16 +1,20 @@
 use crate::prelude::internal::*;
 
-const EDID_HEADER: [u8; 8] = [0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00];
+pub(crate) const EDID_HEADER: [u8; 8] = [0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00];
 
-#[tracing::instrument(skip_all)]
-pub(super) fn parse(input: &[u8]) -> Result<(), EdidError> {
+/// The length of each EDID block, in bytes.
+pub const BLOCK_LEN: usize = 128;
+
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+pub(crate) fn parse(input: &[u8]) -> Result<(), EdidError> {
     // header is exactly 8 bytes long
     if input.len() < EDID_HEADER.len() {
         tracing::error!("the input is too small, so can't contain a header.");
-        return Err(EdidError::HeaderTooShort {
+        return Err(EdidError::from(EdidErrorKind::HeaderTooShort {
             real_len: input.len() as u8,
             expected_len: EDID_HEADER.len() as u8,
-        });
+        })
+        .at(input.len() as u16));
     }
     // we want to ensure the edid has the required (static) header
     let real_header = &input[0..=7];
@@ -20,7 +24,12 @@ pub(super) fn parse(input: &[u8]) -> Result<(), EdidError> {
             &real_header
         );
 
-        return Err(EdidError::NoHeader);
+        let wrong = real_header
+            .iter()
+            .zip(EDID_HEADER)
+            .position(|(got, expected)| *got != expected)
+            .unwrap_or_default();
+        return Err(EdidError::from(EdidErrorKind::NoHeader).at(wrong as u16));
     }
 
     Ok(())
@@ -8,19 +8,22 @@ use id::Manufacturer;
 
 use crate::structures::{self, id::VendorProductId};
 
+use super::le;
+
 use crate::prelude::internal::*;
 
 /// Parses out the `VendorProductId` given the raw input.
-#[tracing::instrument(skip_all)]
-pub(super) fn parse(input: &[u8]) -> Result<VendorProductId, EdidError> {
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+pub(crate) fn parse(input: &[u8]) -> Result<VendorProductId, EdidError> {
     // the first two bytes are the manufacturer name
-    let manufacturer_name = vendor(&mut [input[0x08], input[0x09]])?;
+    let manufacturer_id = pnp_id(&mut [input[0x08], input[0x09]])?;
+    let manufacturer_name = vendor(manufacturer_id)?;
 
     // the next two make a unique hex number indicating which display model
     // we've got.
     //
     // this is from the manufacturer. no text conversion.
-    let product_code: u16 = bytemuck::must_cast([input[0x0A], input[0x0B]]);
+    let product_code = le::read_u16([input[0x0A], input[0x0B]]);
 
     // same thing here for the serial number.
     //
@@ -30,7 +33,7 @@ pub(super) fn parse(input: &[u8]) -> Result<VendorProductId, EdidError> {
     Display Descriptors for an alternative method of defining a serial number.
     */
     let serial_section = [input[0x0C], input[0x0D], input[0x0E], input[0x0F]];
-    let serial: u32 = bytemuck::must_cast(serial_section);
+    let serial = le::read_u32(serial_section);
 
     // if the serial is zero
     let serial_number = if serial == 0 { None } else { Some(serial) };
@@ -66,18 +69,19 @@ pub(super) fn parse(input: &[u8]) -> Result<VendorProductId, EdidError> {
     // construct the info!
     Ok(VendorProductId {
         manufacturer_name,
+        manufacturer_id,
         product_code,
         serial_number,
         date,
     })
 }
 
-/// Gets the vendor (company) name from the given input.
+/// Gets the vendor's PNP ID from the given input.
 ///
 /// The input should always be exactly two elements long, containing three
 /// 5-bit ASCII values.
-#[tracing::instrument]
-fn vendor(input: &mut [u8; 2]) -> Result<Manufacturer, EdidError> {
+#[cfg_attr(feature = "tracing", tracing::instrument)]
+pub(crate) fn pnp_id(input: &mut [u8; 2]) -> Result<ArrayString<3>, EdidError> {
     // let's grab the PNP ID.
     let bits = input[0..=1].view_bits_mut::<Msb0>();
 
@@ -92,7 +96,7 @@ fn vendor(input: &mut [u8; 2]) -> Result<Manufacturer, EdidError> {
     })()
     .ok_or_else(|| {
         tracing::error!("Failed to load required bits for PNP ID parsing. Just sending an ID...");
-        EdidError::IdBadValues(*input)
+        EdidErrorKind::IdBadValues(*input)
     })?;
     tracing::trace!("Found all three `u5` values. ({:#?})", &arr);
 
@@ -107,6 +111,12 @@ fn vendor(input: &mut [u8; 2]) -> Result<Manufacturer, EdidError> {
     string.push(chars[2]);
     tracing::trace!("Created ArrayString. (`{}`)", string);
 
+    Ok(string)
+}
+
+/// Gets the vendor (company) name from its PNP ID.
+#[cfg_attr(feature = "tracing", tracing::instrument)]
+fn vendor(string: ArrayString<3>) -> Result<Manufacturer, EdidError> {
     // let's try to find the its name from their pnp id
     Ok(match pnpid::company_from_pnp_id(string.as_str()) {
         Some(name) => {
@@ -115,7 +125,7 @@ fn vendor(input: &mut [u8; 2]) -> Result<Manufacturer, EdidError> {
             // finally, return the company name
             let n = ArrayString::<{ pnpid::MAX_LEN }>::from(name).map_err(|e| {
                 tracing::error!("Couldn't fit company name into ArrayString! (err: {e})");
-                EdidError::ArrayStringError
+                EdidErrorKind::ArrayStringError
             })?;
 
             Manufacturer::Name(n)
@@ -128,7 +138,7 @@ fn vendor(input: &mut [u8; 2]) -> Result<Manufacturer, EdidError> {
 }
 
 /// Converts three u5 ASCII values into Rust `char`s.
-#[tracing::instrument]
+#[cfg_attr(feature = "tracing", tracing::instrument)]
 fn convert_5bit_ascii_slice(codes: [u8; 3]) -> Result<[char; 3], EdidError> {
     Ok([
         convert_5bit_ascii(codes[0])?,
@@ -138,7 +148,7 @@ fn convert_5bit_ascii_slice(codes: [u8; 3]) -> Result<[char; 3], EdidError> {
 }
 
 /// Converts the compressed 5-bit ASCII code into a standard Rust character.
-#[tracing::instrument]
+#[cfg_attr(feature = "tracing", tracing::instrument)]
 fn convert_5bit_ascii(code: u8) -> Result<char, EdidError> {
     const CODES: [char; 26] = [
         'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R',
@@ -148,7 +158,7 @@ fn convert_5bit_ascii(code: u8) -> Result<char, EdidError> {
     // we don't index by zero
     if code == 0 {
         tracing::error!("Attempted to get the zeroth letter in ASCII, but this isn't correct.");
-        return Err(EdidError::IdNoZeroesAllowed);
+        return Err(EdidErrorKind::IdNoZeroesAllowed.into());
     }
 
     if let Some(c) = CODES.get((code - 1) as usize) {
@@ -156,7 +166,7 @@ fn convert_5bit_ascii(code: u8) -> Result<char, EdidError> {
         Ok(*c)
     } else {
         tracing::error!("Passed in an invalid character identifier (`{code}`).");
-        Err(EdidError::CharOutOfBounds(code))
+        Err(EdidErrorKind::CharOutOfBounds(code).into())
     }
 }
 
@@ -186,6 +196,8 @@ mod tests {
             Manufacturer::Name(ArrayString::from("Dell Inc.").unwrap())
         );
 
+        assert_eq!(vendor_product_info.manufacturer_id.as_str(), "DEL");
+
         // model
         assert_eq!(vendor_product_info.product_code, 41191);
 
@@ -214,6 +226,8 @@ mod tests {
             Manufacturer::Name(ArrayString::from("DO NOT USE - AUO").unwrap())
         );
 
+        assert_eq!(vendor_product_info.manufacturer_id.as_str(), "AUO");
+
         // model
         assert_eq!(vendor_product_info.product_code, 8237);
 
@@ -0,0 +1,122 @@
+//! Little-endian readers for multi-byte fields.
+//!
+//! EDID stores everything little-endian. Casting bytes (or loading a
+//! multi-byte `bitvec` slice) gives you the *host's* byte order instead,
+//! which silently misparses on big-endian targets like PowerPC and MIPS. So
+//! every multi-byte field goes through here.
+//!
+//! The tests below check the whole parser against a reference decoder that
+//! only uses arithmetic. To run them on a real big-endian target, use
+//! [`cross`](https://github.com/cross-rs/cross):
+//!
+//! ```sh
+//! cross test -p liboptic_edid --target powerpc-unknown-linux-gnu
+//! ```
+
+/// Reads a `u16` from its low and high bytes.
+pub(crate) fn read_u16(bytes: [u8; 2]) -> u16 {
+    u16::from_le_bytes(bytes)
+}
+
+/// Reads a `u32`, lowest byte first.
+pub(crate) fn read_u32(bytes: [u8; 4]) -> u32 {
+    u32::from_le_bytes(bytes)
+}
+
+/// Reads a `u12` from its low byte and a nibble holding the upper four bits.
+///
+/// Only the low four bits of `upper` are used.
+pub(crate) fn read_u12(lower: u8, upper: u8) -> u16 {
+    read_u16([lower, upper & 0x0F])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::internal::*;
+
+    #[test]
+    fn readers() {
+        assert_eq!(read_u16([0xE7, 0xA0]), 41191);
+        assert_eq!(read_u32([0x01, 0x00, 0x00, 0x00]), 1);
+        assert_eq!(read_u32([0x78, 0x56, 0x34, 0x12]), 0x1234_5678);
+        assert_eq!(read_u12(0x00, 0xA5), 0x0500);
+
+        // every u16 should agree with plain arithmetic
+        for value in 0..=u16::MAX {
+            let (lower, upper) = ((value % 256) as u8, (value / 256) as u8);
+            assert_eq!(read_u16([lower, upper]), value);
+        }
+    }
+
+    /// The multi-byte fields we care about, worked out with arithmetic only.
+    ///
+    /// Nothing here depends on the host's byte order, so the parser should
+    /// agree with it everywhere.
+    fn reference(input: &[u8]) -> (u16, Option<u32>, Option<[u16; 5]>) {
+        let word = |lower: u8, upper: u8| lower as u16 + upper as u16 * 256;
+
+        let product_code = word(input[0x0A], input[0x0B]);
+        let serial = (0..4).fold(0_u32, |acc, i| {
+            acc + (input[0x0C + i] as u32) * 256_u32.pow(i as u32)
+        });
+
+        // the first descriptor is a detailed timing when the clock is non-zero
+        let d = &input[0x36..0x48];
+        let timing = (d[0] != 0 || d[1] != 0).then(|| {
+            [
+                word(d[0], d[1]),
+                word(d[2], d[4] / 16),
+                word(d[3], d[4] % 16),
+                word(d[5], d[7] / 16),
+                word(d[8], d[11] / 64),
+            ]
+        });
+
+        (product_code, (serial != 0).then_some(serial), timing)
+    }
+
+    #[test]
+    fn matches_portable_reference() {
+        logger();
+        let assets = [
+            "1.input",
+            "linuxhw_edid_Analog_AOC_AOC0320_455954E7CA14.input",
+            "linuxhw_edid_Digital_BOE_BOE07AF_BD22D8FDF96B.input",
+            "linuxhw_edid_Digital_Goldstar_GSM7666_FE91A60D5B6E.input",
+            "linuxhw_edid_Digital_Lenovo_LEN0017_3AF8B597ECB9.input",
+            "linuxhw_edid_Digital_MSI_MSIAF82_4B2991D4299A.input",
+            "linuxhw_edid_Digital_Others_HJW0000_F67302F2ED4C.input",
+            "linuxhw_edid_EDID_Digital_Samsung_SAM02E3_2C47316EFF13.input",
+            "linuxhw_edid_EDID_Digital_Sony_SNY05FA_93D328459FF6.input",
+        ]
+        .map(edid_by_filename);
+
+        let dell = raw_edid_by_filename("dell_s2417dg.raw.input");
+        for input in assets.iter().chain([&dell]) {
+            let edid = Edid::new(input).unwrap();
+            let (product_code, serial, timing) = reference(input);
+
+            assert_eq!(edid.vendor_product_info.product_code, product_code);
+            assert_eq!(edid.vendor_product_info.serial_number, serial);
+
+            if let EighteenByteBlock::Timing(dtd) =
+                &edid.eighteen_byte_data_blocks.preferred_timing_mode
+            {
+                let got = [
+                    dtd.pixel_clock_khz,
+                    dtd.horizontal_addressable_video_px,
+                    dtd.horizontal_blanking_px,
+                    dtd.vertical_addressable_video_lines,
+                    dtd.horizontal_front_porch,
+                ];
+                assert_eq!(Some(got), timing);
+            }
+        }
+
+        // and the dell has known values, from `edid-decode`
+        let edid = Edid::new(&dell).unwrap();
+        assert_eq!(edid.vendor_product_info.product_code, 41191);
+        assert_eq!(edid.vendor_product_info.serial_number, Some(1));
+    }
+}
@@ -1,47 +1,60 @@
-mod _18bytes;
-mod basic_info;
-mod color;
-mod est_timings;
-mod header;
-mod id;
-mod std_timings;
+pub(crate) mod _18bytes;
+pub(crate) mod basic_info;
+pub(crate) mod color;
+pub(crate) mod est_timings;
+pub(super) mod header;
+pub(crate) mod id;
+pub(crate) mod le;
+pub(crate) mod std_timings;
 pub(super) mod util;
-mod version;
+pub(crate) mod version;
 
-use crate::prelude::internal::*;
+use arrayvec::ArrayVec;
 
-#[tracing::instrument(skip_all)]
+use crate::{
+    checksum::{self, ChecksumStatus},
+    prelude::internal::*,
+    EdidView, ParseOptions,
+};
+
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
 pub fn parse(input: &[u8]) -> Result<Edid, EdidError> {
-    // check the length
-    check_length(input)?;
+    parse_with_options(input, &ParseOptions::default())
+}
 
-    // make sure header's right
-    header::parse(input)?;
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+pub fn parse_with_options(input: &[u8], options: &ParseOptions) -> Result<Edid, EdidError> {
+    // this checks the length and header
+    let view = EdidView::new(input)?;
+    let checksum_statuses = checksums(input, options)?;
 
     // construct the type
     let edid = Edid {
-        vendor_product_info: id::parse(input)?,
-        version: version::parse(input)?,
-        basic_display_info: basic_info::parse(input)?,
-        color_characteristics: color::parse(input),
-        established_timings: est_timings::parse(input),
-        standard_timings: std_timings::parse(input),
-        eighteen_byte_data_blocks: _18bytes::parse(input)?,
-        extension_info: input[0x7E],
-        checksum: checksum(input),
+        vendor_product_info: view.vendor_product_info()?,
+        version: view.version()?,
+        basic_display_info: view.basic_display_info()?,
+        color_characteristics: view.color_characteristics(),
+        established_timings: view.established_timings(),
+        standard_timings: view.standard_timings()?,
+        eighteen_byte_data_blocks: view.eighteen_byte_data_blocks()?,
+        extension_info: view.extension_info(),
+        checksum: view.checksum(),
+        checksum_statuses,
     };
 
     // finalized checks
     {
         // when the display is cont. freq., we check if the display range limits
-        // descriptor is given
-        let has_range_desc = !edid.eighteen_byte_data_blocks.blocks.iter().any(|b| {
+        // descriptor is given.
+        //
+        // (continuous frequency is only reported on v1.4)
+        let has_range_desc = edid.eighteen_byte_data_blocks.blocks.iter().any(|b| {
             matches!(
                 b,
                 EighteenByteBlock::Display(DisplayDescriptor::DisplayRangeLimits(_))
             )
         });
-        if input[0x18] == 1 && has_range_desc {
+        if edid.basic_display_info.feature_support.is_continuous_freq && !has_range_desc {
             tracing::warn!(
                 "This EDID is for a continuous display, but it didn't not contain \
             the required Display Range Limits and Timing Descriptor."
@@ -52,37 +65,60 @@ pub fn parse(input: &[u8]) -> Result<Edid, EdidError> {
     Ok(edid)
 }
 
-#[tracing::instrument(skip_all)]
-fn check_length(input: &[u8]) -> Result<(), EdidError> {
-    let expected_len = 0x7F;
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+pub(crate) fn check_length(input: &[u8]) -> Result<(), EdidError> {
+    let expected_len = 0x80;
     let real_len = input.len();
 
     if real_len < expected_len {
         tracing::error!("The length is too short: (got: `{real_len}`, expected: `{expected_len}`)");
 
-        return Err(EdidError::TooShort {
+        // the first missing byte is what's wrong
+        return Err(EdidError::from(EdidErrorKind::TooShort {
             got: real_len as u8,
             expected: expected_len as u8,
-        });
+        })
+        .at_byte(input, real_len));
     }
 
     Ok(())
 }
 
-/// Returns the checksum byte to the user.
-#[tracing::instrument(skip_all)]
-fn checksum(input: &[u8]) -> u8 {
-    let sum = |bytes: &[u8]| bytes.iter().map(|b| *b as u32).sum::<u32>();
+/// Checks the checksum of every block, only failing if the options say to.
+///
+/// Blocks past [`checksum::MAX_BLOCKS`] can't be pointed to by the base
+/// block, so they're ignored.
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+pub(crate) fn checksums(
+    input: &[u8],
+    options: &ParseOptions,
+) -> Result<ArrayVec<ChecksumStatus, { checksum::MAX_BLOCKS }>, EdidError> {
+    let mut statuses = ArrayVec::new();
+    for (i, status) in checksum::statuses(input)
+        .take(checksum::MAX_BLOCKS)
+        .enumerate()
+    {
+        statuses.push(status);
+        if status.is_valid() {
+            continue;
+        }
+
+        let ChecksumStatus { stored, expected } = status;
+        if !options.reject_bad_checksum {
+            // warn the user, but keep going
+            tracing::error!(
+                "Block {i} failed its checksum. (stored: {stored:#04x}, expected: {expected:#04x})"
+            );
+            continue;
+        }
 
-    // warn the user if the checksum is wrong
-    let all = sum(&input[..=0x7F]) % 256;
-    if all != 0x00 {
-        tracing::error!(
-            "The given EDID failed its checksum. It will still be included in the type."
+        return Err(
+            EdidError::from(EdidErrorKind::BadChecksum { stored, expected })
+                .at_byte(input, i * header::BLOCK_LEN + 0x7F),
         );
     }
 
-    input[0x7F]
+    Ok(statuses)
 }
 
 #[cfg(test)]
@@ -103,14 +139,118 @@ mod tests {
     /// try some edid v1.3s to ensure a least a lil compatability
     #[test]
     fn edid_v1_3() {
-        parse(&edid_by_filename(
+        let msi = parse(&edid_by_filename(
             "linuxhw_edid_Digital_MSI_MSIAF82_4B2991D4299A.input",
         ))
         .unwrap();
 
-        parse(&edid_by_filename(
+        let aoc = parse(&edid_by_filename(
             "linuxhw_edid_Analog_AOC_AOC0320_455954E7CA14.input",
         ))
         .unwrap();
+
+        // each section should've been read with the v1.3 rules
+        for edid in [msi, aoc] {
+            assert_eq!(edid.version.interpretation(), Interpretation::V1_3);
+            assert_eq!(edid.basic_display_info.interpretation, Interpretation::V1_3);
+            assert_eq!(
+                edid.eighteen_byte_data_blocks.interpretation,
+                Interpretation::V1_3
+            );
+        }
+    }
+
+    /// tags 0xf7 to 0xf9 were still reserved before v1.4
+    #[test]
+    fn v1_4_descriptor_tags_before_v1_4() {
+        logger();
+        let mut input = raw_edid_by_filename("dell_s2417dg.raw.input");
+
+        // swap the serial number for an empty established timings iii
+        // descriptor
+        input[0x48..0x5A].copy_from_slice(&[
+            0x00, 0x00, 0x00, 0xF7, 0x00, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+        ]);
+        let edid = parse(&input).unwrap();
+        assert!(matches!(
+            edid.eighteen_byte_data_blocks.blocks[0],
+            EighteenByteBlock::Display(DisplayDescriptor::EstablishedTimingsIII { .. })
+        ));
+
+        // on v1.3, it's just a reserved tag
+        input[0x13] = 0x03;
+        let e = parse(&input).unwrap_err();
+        assert!(matches!(
+            e.kind,
+            EdidErrorKind::DescriptorUsedReservedKind { kind_byte: 0xF7 }
+        ));
+        assert_eq!(e.context.section, Some(Section::Descriptor(2)));
+    }
+
+    /// bad checksums are only an error when asked for
+    #[test]
+    fn bad_checksum() {
+        extern crate std;
+        use std::string::ToString as _;
+
+        logger();
+        let input = raw_edid_by_filename("dell_s2417dg.raw.input");
+        let reject = ParseOptions {
+            reject_bad_checksum: true,
+        };
+
+        let edid = Edid::new_with_options(&input, reject.clone()).unwrap();
+        assert!(edid.checksum_valid());
+        assert_eq!(edid.checksum_statuses.len(), 2);
+        assert!(EdidView::new(&input).unwrap().checksum_valid());
+
+        // the base block
+        let mut broken = input.clone();
+        broken[0x7F] = 0x50;
+        let edid = Edid::new(&broken).unwrap();
+        assert_eq!(edid.checksum, 0x50);
+        assert!(!edid.checksum_valid());
+        assert_eq!(edid.checksum_status(), edid.checksum_statuses[0]);
+        let view = EdidView::new(&broken).unwrap();
+        assert!(!view.checksum_valid());
+        assert_eq!(
+            view.checksum_status(),
+            ChecksumStatus {
+                stored: 0x50,
+                expected: 0x51
+            }
+        );
+
+        let e = Edid::new_with_options(&broken, reject.clone()).unwrap_err();
+        assert!(matches!(
+            e.kind,
+            EdidErrorKind::BadChecksum {
+                stored: 0x50,
+                expected: 0x51
+            }
+        ));
+        assert_eq!(e.context.section, Some(Section::Checksum));
+        assert_eq!(
+            e.to_string(),
+            "Block 0, Base EDID: Checksum (offset 0x7f): The block's checksum is 0x50, \
+            but should be 0x51."
+        );
+
+        // and an extension block, which only shows up in the statuses
+        let mut broken = input.clone();
+        broken[0xFF] = broken[0xFF].wrapping_add(1);
+        let edid = Edid::new(&broken).unwrap();
+        assert!(edid.checksum_valid());
+        assert!(edid.checksum_statuses[0].is_valid());
+        assert!(!edid.checksum_statuses[1].is_valid());
+        let view = EdidView::new(&broken).unwrap();
+        assert!(view.checksum_valid());
+        assert!(!view.checksum_statuses().all(|s| s.is_valid()));
+
+        let e = Edid::new_with_options(&broken, reject).unwrap_err();
+        assert_eq!(e.context.block, 1);
+        assert_eq!(e.context.section, Some(Section::Extension { tag: 0x02 }));
+        assert_eq!(e.context.offset, Some(0xFF));
     }
 }
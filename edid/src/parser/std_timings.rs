@@ -3,18 +3,12 @@ use bitvec::{field::BitField, view::BitView};
 use crate::prelude::internal::*;
 
 /// Finds the standard timings for this display.
-#[tracing::instrument(skip_all)]
-pub(crate) fn parse(input: &[u8]) -> StandardTimings {
-    // yeah im not using unsafe to avoid the array here. hardcoding it is :D
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+pub(crate) fn parse(input: &[u8], version: &EdidVersion) -> StandardTimings {
+    // each timing is two bytes, from 0x26 to 0x35
     StandardTimings {
-        st1: one(&input[0x26..=0x27]),
-        st2: one(&input[0x28..=0x29]),
-        st3: one(&input[0x2a..=0x2b]),
-        st4: one(&input[0x2c..=0x2d]),
-        st5: one(&input[0x2e..=0x2f]),
-        st6: one(&input[0x30..=0x31]),
-        st7: one(&input[0x32..=0x33]),
-        st8: one(&input[0x34..=0x35]),
+        timings: core::array::from_fn(|i| one(&input[0x26 + i * 2..][..2])),
+        interpretation: version.interpretation(),
     }
 }
 
@@ -24,7 +18,7 @@ pub(crate) fn parse(input: &[u8]) -> StandardTimings {
 ///
 /// While these aren't noted to be optional in the standard, vendors tend to
 /// treat them as if they were.
-#[tracing::instrument]
+#[cfg_attr(feature = "tracing", tracing::instrument)]
 pub(crate) fn one(bytes: &[u8]) -> Option<STiming> {
     // if both bytes are 1, assume unused
     if bytes[0] == 0x01 && bytes[1] == 0x01 {
@@ -66,7 +60,7 @@ pub(crate) fn one(bytes: &[u8]) -> Option<STiming> {
 }
 
 /// Finds the number of horizontal addressable pixels from the given value.
-#[tracing::instrument]
+#[cfg_attr(feature = "tracing", tracing::instrument)]
 fn hoz_addr_pixels(ct: u8) -> Option<u16> {
     if ct == 0x00 {
         tracing::warn!(
@@ -88,38 +82,42 @@ mod tests {
         let path = "linuxhw_edid_EDID_Digital_Samsung_SAM02E3_2C47316EFF13.input";
         let input = crate::prelude::internal::edid_by_filename(path);
 
-        let got = super::parse(&input);
+        let version = crate::parser::version::parse(&input).unwrap();
+        let got = super::parse(&input, &version);
         tracing::info!("got: {got:#?}");
 
         let expected = StandardTimings {
-            st1: Some(STiming {
-                horizontal_addr_pixel_ct: 1440,
-                aspect_ratio: StandardAspectRatio::_16_10,
-                field_refresh_rate: 60,
-            }),
-            st2: Some(STiming {
-                horizontal_addr_pixel_ct: 1440,
-                aspect_ratio: StandardAspectRatio::_16_10,
-                field_refresh_rate: 75,
-            }),
-            st3: Some(STiming {
-                horizontal_addr_pixel_ct: 1280,
-                aspect_ratio: StandardAspectRatio::_5_4,
-                field_refresh_rate: 60,
-            }),
-            st4: Some(STiming {
-                horizontal_addr_pixel_ct: 1280,
-                aspect_ratio: StandardAspectRatio::_4_3,
-                field_refresh_rate: 60,
-            }),
-            st5: Some(STiming {
-                horizontal_addr_pixel_ct: 1152,
-                aspect_ratio: StandardAspectRatio::_4_3,
-                field_refresh_rate: 75,
-            }),
-            st6: None,
-            st7: None,
-            st8: None,
+            timings: [
+                Some(STiming {
+                    horizontal_addr_pixel_ct: 1440,
+                    aspect_ratio: StandardAspectRatio::_16_10,
+                    field_refresh_rate: 60,
+                }),
+                Some(STiming {
+                    horizontal_addr_pixel_ct: 1440,
+                    aspect_ratio: StandardAspectRatio::_16_10,
+                    field_refresh_rate: 75,
+                }),
+                Some(STiming {
+                    horizontal_addr_pixel_ct: 1280,
+                    aspect_ratio: StandardAspectRatio::_5_4,
+                    field_refresh_rate: 60,
+                }),
+                Some(STiming {
+                    horizontal_addr_pixel_ct: 1280,
+                    aspect_ratio: StandardAspectRatio::_4_3,
+                    field_refresh_rate: 60,
+                }),
+                Some(STiming {
+                    horizontal_addr_pixel_ct: 1152,
+                    aspect_ratio: StandardAspectRatio::_4_3,
+                    field_refresh_rate: 75,
+                }),
+                None,
+                None,
+                None,
+            ],
+            interpretation: Interpretation::V1_4,
         };
         tracing::warn!("expected: {expected:#?}");
 
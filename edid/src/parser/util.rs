@@ -3,9 +3,12 @@ extern crate alloc;
 #[cfg(test)]
 extern crate std;
 
+#[cfg(test)]
+use crate::prelude::internal::tracing;
+
 /// Grabs an EDID from disk at `tests/assets/`
 #[cfg(test)]
-#[tracing::instrument]
+#[cfg_attr(feature = "tracing", tracing::instrument)]
 pub(crate) fn edid_by_filename(name: &str) -> alloc::vec::Vec<u8> {
     if name.contains(".info") {
         tracing::warn!(
@@ -23,20 +26,17 @@ pub(crate) fn edid_by_filename(name: &str) -> alloc::vec::Vec<u8> {
 
     let path =
         std::path::PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/assets")).join(name);
-    let s = std::fs::read_to_string(path)
-        .unwrap()
-        .replace([' ', '\n'], "");
-
-    if s.contains("edid-decode") {
-        panic!("you forgot to remove the edid-decode stuff from the input test file");
-    }
 
-    hex::decode(s.trim()).unwrap()
+    // this also handles hex that's still wrapped in `edid-decode` output.
+    // we don't check the header, though, since some assets are broken on
+    // purpose
+    let (_format, mut edids) = crate::io::extract(&std::fs::read(path).unwrap()).unwrap();
+    edids.swap_remove(0)
 }
 
 /// Grabs a raw (not encoded) EDID from disk at `tests/assets/`
 #[cfg(test)]
-#[tracing::instrument]
+#[cfg_attr(feature = "tracing", tracing::instrument)]
 pub(crate) fn raw_edid_by_filename(name: &str) -> alloc::vec::Vec<u8> {
     if name.contains(".info") {
         tracing::warn!(
@@ -60,10 +60,10 @@ pub(crate) fn raw_edid_by_filename(name: &str) -> alloc::vec::Vec<u8> {
 
 /// Starts the tracing subscriber.
 #[cfg(test)]
-#[tracing::instrument]
+#[cfg_attr(feature = "tracing", tracing::instrument)]
 pub(crate) fn logger() {
     _ = tracing_subscriber::fmt()
         .pretty()
-        .with_max_level(tracing::Level::DEBUG)
+        .with_max_level(tracing_subscriber::filter::LevelFilter::DEBUG)
         .try_init();
 }
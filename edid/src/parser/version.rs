@@ -3,13 +3,13 @@ use crate::structures::version::EdidVersion;
 use crate::prelude::internal::*;
 
 /// Parses out the EDID version info.
-#[tracing::instrument(skip_all)]
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
 pub(crate) fn parse(input: &[u8]) -> Result<EdidVersion, EdidError> {
     let version = input[0x12];
     let revision = input[0x13];
 
     // notify the user if the version/revisions aren't supported
-    if version > crate::LATEST_SUPPORTED_VERSION || revision > crate::LATEST_SUPPORTED_VERSION {
+    if version > crate::LATEST_SUPPORTED_VERSION || revision > crate::LATEST_SUPPORTED_REVISION {
         tracing::warn!(
             "This crate hasn't yet been tested above EDID v{}.{}.",
             crate::LATEST_SUPPORTED_VERSION,
@@ -17,11 +17,15 @@ pub(crate) fn parse(input: &[u8]) -> Result<EdidVersion, EdidError> {
         );
     }
 
-    Ok(EdidVersion { version, revision })
+    let vnr = EdidVersion { version, revision };
+    tracing::debug!("Using EDID interpretation: {:?}", vnr.interpretation());
+    Ok(vnr)
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::structures::version::Interpretation;
+
     #[test]
     fn vnr_dell_s2417dg() {
         let input = crate::prelude::internal::raw_edid_by_filename("dell_s2417dg.raw.input");
@@ -29,5 +33,17 @@ mod tests {
 
         assert_eq!(vnr.version, 0x1);
         assert_eq!(vnr.revision, 0x4);
+        assert_eq!(vnr.interpretation(), Interpretation::V1_4);
+    }
+
+    #[test]
+    fn vnr_aoc0320_455954e7ca14() {
+        let input = crate::prelude::internal::edid_by_filename(
+            "linuxhw_edid_Analog_AOC_AOC0320_455954E7CA14.input",
+        );
+        let vnr = super::parse(&input).unwrap();
+
+        assert_eq!(vnr.revision, 0x3);
+        assert_eq!(vnr.interpretation(), Interpretation::V1_3);
     }
 }
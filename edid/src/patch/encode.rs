@@ -0,0 +1,485 @@
+//! Turns parsed structures back into their EDID bytes.
+//!
+//! Each of these is the inverse of the matching parser, so parsing their
+//! output gives back what they were given.
+
+use crate::prelude::internal::*;
+
+/// An 18-byte descriptor that isn't being used.
+pub(crate) const DUMMY_DESCRIPTOR: [u8; 18] = {
+    let mut bytes = [0x00; 18];
+    bytes[3] = 0x10;
+    bytes
+};
+
+/// Makes sure `value` fits in the given number of `bits`.
+fn fits(value: u16, bits: u32, field: &'static str) -> Result<u16, EdidError> {
+    if u32::from(value) >> bits != 0 {
+        tracing::error!("`{field}` doesn't fit in {bits} bits. (value: {value})");
+        return Err(EdidErrorKind::PatchValueOutOfRange { field }.into());
+    }
+    Ok(value)
+}
+
+/// Encodes a Detailed Timing Definition.
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+pub(crate) fn timing(dtd: &DetailedTimingDefinition) -> Result<[u8; 18], EdidError> {
+    // a zero pixel clock would make this a display descriptor instead
+    if dtd.pixel_clock_khz == 0 {
+        tracing::error!("a detailed timing can't have a pixel clock of zero.");
+        return Err(EdidErrorKind::PatchValueOutOfRange {
+            field: "pixel_clock_khz",
+        }
+        .into());
+    }
+
+    let h_active = fits(
+        dtd.horizontal_addressable_video_px,
+        12,
+        "horizontal_addressable_video_px",
+    )?;
+    let h_blank = fits(dtd.horizontal_blanking_px, 12, "horizontal_blanking_px")?;
+    let v_active = fits(
+        dtd.vertical_addressable_video_lines,
+        12,
+        "vertical_addressable_video_lines",
+    )?;
+    let v_blank = fits(dtd.vertical_blanking_lines, 12, "vertical_blanking_lines")?;
+    let h_front = fits(dtd.horizontal_front_porch, 10, "horizontal_front_porch")?;
+    let h_sync = fits(
+        dtd.horizontal_sync_pulse_width_px,
+        10,
+        "horizontal_sync_pulse_width_px",
+    )?;
+    let v_front = fits(
+        dtd.vertical_front_porch_lines.into(),
+        6,
+        "vertical_front_porch_lines",
+    )?;
+    let v_sync = fits(
+        dtd.vertical_sync_pulse_width_lines.into(),
+        6,
+        "vertical_sync_pulse_width_lines",
+    )?;
+    let h_size = fits(
+        dtd.horizontal_addressable_video_size_mm.unwrap_or(0),
+        12,
+        "horizontal_addressable_video_size_mm",
+    )?;
+    let v_size = fits(
+        dtd.vertical_addressable_video_size_mm.unwrap_or(0),
+        12,
+        "vertical_addressable_video_size_mm",
+    )?;
+
+    // these are all split into a low byte and a few high bits
+    let lo = |value: u16| (value & 0xFF) as u8;
+    let hi = |value: u16| (value >> 8) as u8;
+
+    let [clock_lo, clock_hi] = dtd.pixel_clock_khz.to_le_bytes();
+    Ok([
+        clock_lo,
+        clock_hi,
+        lo(h_active),
+        lo(h_blank),
+        (hi(h_active) << 4) | hi(h_blank),
+        lo(v_active),
+        lo(v_blank),
+        (hi(v_active) << 4) | hi(v_blank),
+        lo(h_front),
+        lo(h_sync),
+        ((v_front as u8 & 0x0F) << 4) | (v_sync as u8 & 0x0F),
+        (hi(h_front) << 6) | (hi(h_sync) << 4) | ((v_front as u8 >> 4) << 2) | (v_sync as u8 >> 4),
+        lo(h_size),
+        lo(v_size),
+        (hi(h_size) << 4) | hi(v_size),
+        dtd.horizontal_border_px,
+        dtd.vertical_border_lines,
+        signal_byte(dtd),
+    ])
+}
+
+/// Encodes the last byte of a timing, which describes its signal.
+fn signal_byte(dtd: &DetailedTimingDefinition) -> u8 {
+    let interlaced = match dtd.signal_interface_type {
+        SignalInterfaceType::NonInterlaced => 0b0000_0000,
+        SignalInterfaceType::Interlaced => 0b1000_0000,
+    };
+
+    // these use bits 6, 5, and 0
+    let stereo = match dtd.stereo_support {
+        StereoViewingSupport::NormalDisplay => 0b0000_0000,
+        StereoViewingSupport::FieldSequentialRight => 0b0010_0000,
+        StereoViewingSupport::FieldSequentialLeft => 0b0100_0000,
+        StereoViewingSupport::TwoWayInterleavedRight => 0b0010_0001,
+        StereoViewingSupport::TwoWayInterleavedLeft => 0b0100_0001,
+        StereoViewingSupport::FourWayInterleaved => 0b0110_0000,
+        StereoViewingSupport::SideBySide => 0b0110_0001,
+    };
+
+    // and these use bits 4 to 1
+    let sync = match &dtd.sync_signal {
+        SyncSignal::Analog {
+            bipolar,
+            with_serrations,
+            sync_mode,
+        } => {
+            (u8::from(*bipolar) << 3)
+                | (u8::from(*with_serrations) << 2)
+                | (u8::from(*sync_mode == AnalogSyncOn::Rgb) << 1)
+        }
+        SyncSignal::Digital(digital) => match digital {
+            DigitalSyncSignal::Composite => 0b0001_0000,
+            DigitalSyncSignal::CompositeSerrations => 0b0001_0100,
+            DigitalSyncSignal::SeparateNegVNegH => 0b0001_1000,
+            DigitalSyncSignal::SeparateNegVPosH => 0b0001_1010,
+            DigitalSyncSignal::SeparatePosVNegH => 0b0001_1100,
+            DigitalSyncSignal::SeparatePosVPosH => 0b0001_1110,
+        },
+    };
+
+    interlaced | stereo | sync
+}
+
+/// Encodes a Display Range Limits descriptor.
+///
+/// The offsets are worked out from the rates themselves, which can only go
+/// past 255 on EDID v1.4. Whether range limits only are `flexible` isn't
+/// stored here; see [`continuous_freq`].
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+pub(crate) fn range_limits(
+    desc: &RangeLimitsDesc,
+    interpretation: Interpretation,
+) -> Result<[u8; 18], EdidError> {
+    let limits = desc.limits();
+    let mut bytes = [0x00; 18];
+    bytes[3] = 0xFD;
+
+    // each rate might need an offset of 255
+    let (max_v, max_v_offset) = offset_rate(limits.max_v_rate_hz, interpretation, "max_v_rate_hz")?;
+    let (min_v, min_v_offset) = offset_rate(limits.min_v_rate_hz, interpretation, "min_v_rate_hz")?;
+    let (max_h, max_h_offset) =
+        offset_rate(limits.max_h_rate_khz, interpretation, "max_h_rate_khz")?;
+    let (min_h, min_h_offset) =
+        offset_rate(limits.min_h_rate_khz, interpretation, "min_h_rate_khz")?;
+
+    // the minimum can only be offset when the maximum is, too
+    if (min_v_offset && !max_v_offset) || (min_h_offset && !max_h_offset) {
+        tracing::error!("a minimum rate was over 255, but its maximum wasn't.");
+        return Err(EdidErrorKind::PatchValueOutOfRange { field: "offsets" }.into());
+    }
+
+    bytes[4] = (u8::from(max_h_offset) << 3)
+        | (u8::from(min_h_offset) << 2)
+        | (u8::from(max_v_offset) << 1)
+        | u8::from(min_v_offset);
+    bytes[5] = min_v;
+    bytes[6] = max_v;
+    bytes[7] = min_h;
+    bytes[8] = max_h;
+
+    // the pixel clock is stored in multiples of 10 MHz
+    bytes[9] = u8::try_from(limits.max_pixel_clock_mhz.div_ceil(10)).map_err(|_| {
+        tracing::error!(
+            "max pixel clock is too high. (mhz: {})",
+            limits.max_pixel_clock_mhz
+        );
+        EdidErrorKind::PatchValueOutOfRange {
+            field: "max_pixel_clock_mhz",
+        }
+    })?;
+
+    // the rest depends on the kind of timing support
+    let padding = [0x0A, 0x20, 0x20, 0x20, 0x20, 0x20, 0x20];
+    match desc {
+        RangeLimitsDesc::GtfSupported { .. } => {
+            bytes[10] = 0x00;
+            bytes[11..].copy_from_slice(&padding);
+        }
+        RangeLimitsDesc::LimitsOnly { .. } => {
+            bytes[10] = 0x01;
+            bytes[11..].copy_from_slice(&padding);
+        }
+        RangeLimitsDesc::GtfSecondaryCurveSupported {
+            start_break_freq,
+            c2,
+            m,
+            k,
+            j2,
+            ..
+        } => {
+            bytes[10] = 0x02;
+            bytes[12] = u8::try_from(start_break_freq / 2).map_err(|_| {
+                EdidErrorKind::PatchValueOutOfRange {
+                    field: "start_break_freq",
+                }
+            })?;
+            bytes[13] = *c2;
+            bytes[14..=15].copy_from_slice(&m.to_le_bytes());
+            bytes[16] = *k;
+            bytes[17] = *j2;
+        }
+        RangeLimitsDesc::CvtSupported {
+            enhanced_px_clk,
+            cvt_version,
+            maximum_active_pxls_per_line,
+            supported_aspect_ratios,
+            preferred_aspect_ratio,
+            supports_standard_cvt_blanking,
+            supports_reduced_cvt_blanking,
+            supports_h_shrink_scaling,
+            supports_h_stretch_scaling,
+            supports_v_shrink_scaling,
+            supports_v_stretch_scaling,
+            preferred_v_refresh_rate_hz,
+            ..
+        } => {
+            bytes[10] = 0x04;
+            bytes[11] = *cvt_version;
+
+            // the enhanced clock is subtracted from the normal one, in
+            // quarters of a MHz
+            let quarters = (u32::from(*enhanced_px_clk.numer()) * 4)
+                .div_ceil(u32::from(*enhanced_px_clk.denom()));
+            let to_sub = (u32::from(bytes[9]) * 40)
+                .checked_sub(quarters)
+                .filter(|q| *q < 64)
+                .map(|q| q as u8)
+                .ok_or(EdidErrorKind::PatchValueOutOfRange {
+                    field: "enhanced_px_clk",
+                })?;
+
+            // the max line length is in multiples of 8 px, and zero means
+            // there's no limit
+            let max_active = match maximum_active_pxls_per_line {
+                Some(px) => fits(px / 8, 10, "maximum_active_pxls_per_line")?,
+                None => 0,
+            };
+
+            bytes[12] = (to_sub << 2) | (max_active >> 8) as u8;
+            bytes[13] = (max_active & 0xFF) as u8;
+
+            let ratios = supported_aspect_ratios;
+            bytes[14] = (u8::from(ratios._4x3) << 7)
+                | (u8::from(ratios._16x9) << 6)
+                | (u8::from(ratios._16x10) << 5)
+                | (u8::from(ratios._5x4) << 4)
+                | (u8::from(ratios._15x9) << 3);
+
+            let preferred: u8 = match preferred_aspect_ratio {
+                PreferredAspectRatio::_4x3 => 0b000,
+                PreferredAspectRatio::_16x9 => 0b001,
+                PreferredAspectRatio::_16x10 => 0b010,
+                PreferredAspectRatio::_5x4 => 0b011,
+                PreferredAspectRatio::_15x9 => 0b100,
+            };
+            bytes[15] = (preferred << 5)
+                | (u8::from(*supports_reduced_cvt_blanking) << 4)
+                | (u8::from(*supports_standard_cvt_blanking) << 3);
+
+            bytes[16] = (u8::from(*supports_h_shrink_scaling) << 7)
+                | (u8::from(*supports_h_stretch_scaling) << 6)
+                | (u8::from(*supports_v_shrink_scaling) << 5)
+                | (u8::from(*supports_v_stretch_scaling) << 4);
+            bytes[17] = *preferred_v_refresh_rate_hz;
+        }
+    }
+
+    Ok(bytes)
+}
+
+/// The continuous frequency bit of the feature support byte (0x18) that the
+/// given range limits need, if they need one at all.
+///
+/// For range limits only, that bit is what says the display is `flexible`.
+/// Before v1.4, the same bit meant default GTF support instead, so a flexible
+/// display can't be described.
+pub(crate) fn continuous_freq(
+    desc: &RangeLimitsDesc,
+    interpretation: Interpretation,
+) -> Result<Option<bool>, EdidError> {
+    match desc {
+        RangeLimitsDesc::LimitsOnly { flexible, .. } if interpretation.is_v1_4() => {
+            Ok(Some(*flexible))
+        }
+        RangeLimitsDesc::LimitsOnly { flexible: true, .. } => {
+            tracing::error!("flexible range limits are new in EDID v1.4.");
+            Err(EdidErrorKind::PatchValueOutOfRange { field: "flexible" }.into())
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Splits a rate into its byte and whether it needs the 255 offset.
+fn offset_rate(
+    rate: u16,
+    interpretation: Interpretation,
+    field: &'static str,
+) -> Result<(u8, bool), EdidError> {
+    match u8::try_from(rate) {
+        Ok(byte) => Ok((byte, false)),
+
+        // offsets are new in v1.4
+        Err(_) if interpretation.is_v1_4() && rate <= 255 + 255 => Ok(((rate - 255) as u8, true)),
+        Err(_) => {
+            tracing::error!("`{field}` is too large for this EDID. (value: {rate})");
+            Err(EdidErrorKind::PatchValueOutOfRange { field }.into())
+        }
+    }
+}
+
+/// Encodes a Display Product Name descriptor.
+///
+/// The name must be ASCII, and can't be longer than 13 characters.
+#[cfg_attr(feature = "tracing", tracing::instrument)]
+pub(crate) fn product_name(name: &str) -> Result<[u8; 18], EdidError> {
+    if name.len() > 13 || !name.is_ascii() || name.contains('\n') {
+        tracing::error!("product names must be up to 13 ASCII characters.");
+        return Err(EdidErrorKind::PatchBadProductName.into());
+    }
+
+    // shorter names end with a newline, then get padded with spaces
+    let mut bytes = [0x20; 18];
+    bytes[..5].copy_from_slice(&[0x00, 0x00, 0x00, 0xFC, 0x00]);
+    bytes[5..5 + name.len()].copy_from_slice(name.as_bytes());
+    if name.len() < 13 {
+        bytes[5 + name.len()] = 0x0A;
+    }
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::checksum::fix_checksums;
+
+    fn assets() -> [Vec<u8>; 10] {
+        [
+            raw_edid_by_filename("dell_s2417dg.raw.input"),
+            edid_by_filename("1.input"),
+            edid_by_filename("linuxhw_edid_Analog_AOC_AOC0320_455954E7CA14.input"),
+            edid_by_filename("linuxhw_edid_Digital_BOE_BOE07AF_BD22D8FDF96B.input"),
+            edid_by_filename("linuxhw_edid_Digital_Goldstar_GSM7666_FE91A60D5B6E.input"),
+            edid_by_filename("linuxhw_edid_Digital_Lenovo_LEN0017_3AF8B597ECB9.input"),
+            edid_by_filename("linuxhw_edid_Digital_MSI_MSIAF82_4B2991D4299A.input"),
+            edid_by_filename("linuxhw_edid_Digital_Others_HJW0000_F67302F2ED4C.input"),
+            edid_by_filename("linuxhw_edid_EDID_Digital_Samsung_SAM02E3_2C47316EFF13.input"),
+            edid_by_filename("linuxhw_edid_EDID_Digital_Sony_SNY05FA_93D328459FF6.input"),
+        ]
+    }
+
+    /// encoding what we parsed should give back the original bytes
+    #[test]
+    fn round_trip_assets() {
+        logger();
+
+        for input in assets() {
+            let edid = Edid::new(&input).unwrap();
+            let interpretation = edid.version.interpretation();
+            let blocks = &edid.eighteen_byte_data_blocks;
+
+            let all = core::iter::once(&blocks.preferred_timing_mode).chain(blocks.blocks.iter());
+            for (block, start) in all.zip([0x36, 0x48, 0x5A, 0x6C]) {
+                let original = &input[start..start + 18];
+
+                let encoded = match block {
+                    EighteenByteBlock::Timing(dtd) => timing(dtd).unwrap(),
+
+                    // some displays pad these differently, so we only check
+                    // the parts that mean something
+                    EighteenByteBlock::Display(DisplayDescriptor::DisplayRangeLimits(
+                        desc @ (RangeLimitsDesc::GtfSupported { .. }
+                        | RangeLimitsDesc::LimitsOnly { .. }),
+                    )) => {
+                        let encoded = range_limits(desc, interpretation).unwrap();
+                        assert_eq!(original[..11], encoded[..11]);
+                        continue;
+                    }
+                    EighteenByteBlock::Display(DisplayDescriptor::DisplayRangeLimits(desc)) => {
+                        range_limits(desc, interpretation).unwrap()
+                    }
+                    EighteenByteBlock::Display(DisplayDescriptor::ProductName(name)) => {
+                        product_name(name.trim_end()).unwrap()
+                    }
+                    _ => continue,
+                };
+
+                assert_eq!(
+                    original, encoded,
+                    "at {start:#x} in {:?}",
+                    edid.vendor_product_info
+                );
+            }
+        }
+    }
+
+    /// none of the good assets use cvt, but this one does
+    #[test]
+    fn round_trip_cvt_limits() {
+        logger();
+        let input =
+            edid_by_filename("bad/linuxhw_edid_EDID_Digital_LG Display_LGD0555_7D17E3014129.input");
+        let edid = Edid::new(&input).unwrap();
+
+        let EighteenByteBlock::Display(DisplayDescriptor::DisplayRangeLimits(
+            desc @ RangeLimitsDesc::CvtSupported { .. },
+        )) = &edid.eighteen_byte_data_blocks.blocks[0]
+        else {
+            panic!("this edid has cvt range limits");
+        };
+
+        // this display sets some reserved bits, which we don't keep
+        let mut expected: [u8; 18] = input[0x48..0x5A].try_into().unwrap();
+        expected[14] &= 0b1111_1000;
+        expected[15] &= 0b1111_1000;
+        expected[16] &= 0b1111_0000;
+
+        assert_eq!(expected, range_limits(desc, Interpretation::V1_4).unwrap());
+    }
+
+    #[test]
+    fn checksums() {
+        logger();
+        let mut input = raw_edid_by_filename("dell_s2417dg.raw.input");
+        let expected = input.clone();
+
+        input[0x7F] = 0x00;
+        input[0xFF] = 0x12;
+        fix_checksums(&mut input);
+        assert_eq!(input, expected);
+    }
+
+    #[test]
+    fn bad_values() {
+        logger();
+        let edid = Edid::new(raw_edid_by_filename("dell_s2417dg.raw.input")).unwrap();
+        let EighteenByteBlock::Timing(mut dtd) =
+            edid.eighteen_byte_data_blocks.preferred_timing_mode
+        else {
+            panic!("dell has a preferred timing");
+        };
+
+        dtd.vertical_front_porch_lines = 64;
+        assert!(matches!(
+            timing(&dtd),
+            Err(EdidError {
+                kind: EdidErrorKind::PatchValueOutOfRange {
+                    field: "vertical_front_porch_lines"
+                },
+                ..
+            })
+        ));
+
+        assert!(matches!(
+            product_name("this name is way too long"),
+            Err(EdidError {
+                kind: EdidErrorKind::PatchBadProductName,
+                ..
+            })
+        ));
+    }
+}
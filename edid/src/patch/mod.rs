@@ -0,0 +1,729 @@
+//! Fixes broken EDIDs by patching their bytes.
+//!
+//! Plenty of displays ship with EDIDs that are just wrong. Linux can load a
+//! replacement from `/lib/firmware/edid/` instead (with the
+//! `drm.edid_firmware=` kernel parameter), so this module makes those
+//! replacements.
+//!
+//! ```edition2021
+//! use liboptic_edid::{patch::EdidPatch, Edid};
+//!
+//! let original = std::fs::read("tests/assets/dell_s2417dg.raw.input")?;
+//!
+//! // rename it, then drop its extension block
+//! let patched = EdidPatch::new()
+//!     .product_name("Fixed Dell")
+//!     .strip_extension(1)
+//!     .apply(&original)?;
+//!
+//! let edid = Edid::new(&patched)?;
+//! assert_eq!(edid.extension_info, 0);
+//! #
+//! # Ok::<(), Box<dyn core::error::Error>>(())
+//! ```
+
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+use alloc::{string::String, vec::Vec};
+
+use crate::{checksum::fix_checksums, parser::header::BLOCK_LEN, prelude::internal::*};
+
+pub(crate) mod encode;
+
+use encode::DUMMY_DESCRIPTOR;
+
+/// The tag of a block map extension block.
+const BLOCK_MAP_TAG: u8 = 0xF0;
+
+/// Where each 18-byte descriptor starts in the base block.
+const SLOTS: [usize; 4] = [0x36, 0x48, 0x5A, 0x6C];
+
+/// A set of changes to make to an EDID.
+///
+/// Changes are applied in a fixed order, no matter the order they were
+/// given in:
+///
+/// 1. extension blocks are stripped,
+/// 2. timings are injected,
+/// 3. descriptors are replaced,
+/// 4. and finally, the pixel clock is clamped.
+///
+/// Afterwards, every block gets a new checksum, and the result is parsed to
+/// make sure it's still a valid EDID.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[must_use]
+pub struct EdidPatch {
+    preferred_timing: Option<DetailedTimingDefinition>,
+    extra_timings: Vec<DetailedTimingDefinition>,
+    range_limits: Option<RangeLimitsDesc>,
+    product_name: Option<String>,
+    stripped_extensions: Vec<u8>,
+    max_pixel_clock_mhz: Option<u16>,
+}
+
+impl EdidPatch {
+    /// Creates a patch that doesn't change anything (yet).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Makes `timing` the preferred timing, in the first 18-byte block.
+    ///
+    /// If the old preferred timing was a detailed timing, it's moved into
+    /// an unused descriptor, if there is one.
+    pub fn preferred_timing(mut self, timing: DetailedTimingDefinition) -> Self {
+        self.preferred_timing = Some(timing);
+        self
+    }
+
+    /// Adds another detailed timing, in place of an unused descriptor.
+    pub fn add_timing(mut self, timing: DetailedTimingDefinition) -> Self {
+        self.extra_timings.push(timing);
+        self
+    }
+
+    /// Replaces the Display Range Limits descriptor, or adds one if there
+    /// wasn't one already.
+    pub fn range_limits(mut self, limits: RangeLimitsDesc) -> Self {
+        self.range_limits = Some(limits);
+        self
+    }
+
+    /// Replaces the Display Product Name descriptor, or adds one if there
+    /// wasn't one already.
+    ///
+    /// The name must be ASCII, and can't be longer than 13 characters.
+    pub fn product_name(mut self, name: &str) -> Self {
+        self.product_name = Some(name.into());
+        self
+    }
+
+    /// Removes the extension block at `index`, where `1` is the first block
+    /// after the base EDID.
+    ///
+    /// If the first extension is a block map, the removed block's entry is
+    /// taken out of it too.
+    pub fn strip_extension(mut self, index: u8) -> Self {
+        self.stripped_extensions.push(index);
+        self
+    }
+
+    /// Lowers the maximum pixel clock to `mhz`.
+    ///
+    /// This changes the range limits (if the EDID has them) and removes any
+    /// detailed timings in the base block that are faster. Note that the
+    /// preferred timing can't be removed, so replace it first if it's too
+    /// fast.
+    ///
+    /// The range limits keep this in steps of 10 MHz, so anything under 10
+    /// is refused.
+    pub fn max_pixel_clock_mhz(mut self, mhz: u16) -> Self {
+        self.max_pixel_clock_mhz = Some(mhz);
+        self
+    }
+
+    /// Applies the patch to the given `edid`, returning the new bytes.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub fn apply(&self, edid: &[u8]) -> Result<Vec<u8>, EdidError> {
+        // make sure we're starting from something that parses
+        let original = Edid::new(edid)?;
+        let interpretation = original.version.interpretation();
+
+        let block_count = 1 + usize::from(original.extension_info);
+        let mut bytes = edid[..edid.len().min(block_count * BLOCK_LEN)].to_vec();
+        if bytes.len() < block_count * BLOCK_LEN {
+            tracing::warn!(
+                "The EDID says it has {} extensions, but only {} bytes were given.",
+                original.extension_info,
+                edid.len()
+            );
+        }
+
+        self.strip(&mut bytes)?;
+
+        if let Some(ref timing) = self.preferred_timing {
+            let old = slot(&bytes, 0);
+            if is_timing(&old) {
+                match free_slot(&bytes) {
+                    Some(free) => set_slot(&mut bytes, free, &old),
+                    None => tracing::warn!("There's no room for the old preferred timing."),
+                }
+            }
+            set_slot(&mut bytes, 0, &encode::timing(timing)?);
+        }
+
+        for timing in &self.extra_timings {
+            let free = free_slot(&bytes).ok_or(EdidErrorKind::PatchNoFreeDescriptor)?;
+            set_slot(&mut bytes, free, &encode::timing(timing)?);
+        }
+
+        if let Some(ref limits) = self.range_limits {
+            let encoded = encode::range_limits(limits, interpretation)?;
+            replace_descriptor(&mut bytes, 0xFD, &encoded)?;
+
+            // whether range limits only are flexible lives in the feature
+            // support byte, not the descriptor
+            if let Some(cf) = encode::continuous_freq(limits, interpretation)? {
+                bytes[0x18] = (bytes[0x18] & !0x01) | u8::from(cf);
+            }
+        }
+
+        if let Some(ref name) = self.product_name {
+            replace_descriptor(&mut bytes, 0xFC, &encode::product_name(name)?)?;
+        }
+
+        if let Some(mhz) = self.max_pixel_clock_mhz {
+            clamp_pixel_clock(&mut bytes, mhz)?;
+        }
+
+        // finally, fix up the checksums and make sure it still parses
+        fix_checksums(&mut bytes);
+        Edid::new(&bytes)?;
+
+        Ok(bytes)
+    }
+
+    /// Removes the extensions we were asked to.
+    fn strip(&self, bytes: &mut Vec<u8>) -> Result<(), EdidError> {
+        let mut indices = self.stripped_extensions.clone();
+        indices.sort_unstable();
+        indices.dedup();
+
+        // go backwards so the earlier indices stay put
+        for &index in indices.iter().rev() {
+            let start = usize::from(index) * BLOCK_LEN;
+            if index == 0 || index > bytes[0x7E] || bytes.len() < start + BLOCK_LEN {
+                tracing::error!("There's no extension block at index {index}.");
+                return Err(EdidErrorKind::PatchNoSuchExtension { index }.into());
+            }
+
+            bytes.drain(start..start + BLOCK_LEN);
+            bytes[0x7E] -= 1;
+
+            // with more than one extension, the first one can be a block
+            // map, listing the tags of the blocks after it. byte 1 is for
+            // block 2, and so on
+            if index >= 2 && bytes.len() >= 2 * BLOCK_LEN && bytes[BLOCK_LEN] == BLOCK_MAP_TAG {
+                let map = &mut bytes[BLOCK_LEN + 1..2 * BLOCK_LEN - 1];
+                let entry = usize::from(index) - 2;
+                map.copy_within(entry + 1.., entry);
+                map[map.len() - 1] = 0x00;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Grabs the 18-byte descriptor in `slot`.
+fn slot(bytes: &[u8], slot: usize) -> [u8; 18] {
+    let mut out = [0x00; 18];
+    out.copy_from_slice(&bytes[SLOTS[slot]..SLOTS[slot] + 18]);
+    out
+}
+
+/// Overwrites the 18-byte descriptor in `slot`.
+fn set_slot(bytes: &mut [u8], slot: usize, descriptor: &[u8; 18]) {
+    bytes[SLOTS[slot]..SLOTS[slot] + 18].copy_from_slice(descriptor);
+}
+
+/// Checks if a descriptor is a detailed timing.
+fn is_timing(descriptor: &[u8; 18]) -> bool {
+    descriptor[0] != 0x00 || descriptor[1] != 0x00
+}
+
+/// Finds a descriptor with the given `tag`.
+fn find_tag(bytes: &[u8], tag: u8) -> Option<usize> {
+    (0..SLOTS.len()).find(|&i| {
+        let descriptor = slot(bytes, i);
+        !is_timing(&descriptor) && descriptor[3] == tag
+    })
+}
+
+/// Finds an unused (dummy) descriptor. The first slot is never used for
+/// this.
+fn free_slot(bytes: &[u8]) -> Option<usize> {
+    find_tag(bytes, 0x10).filter(|&i| i != 0)
+}
+
+/// Replaces the descriptor with `tag`, or puts it in a free slot.
+fn replace_descriptor(bytes: &mut [u8], tag: u8, descriptor: &[u8; 18]) -> Result<(), EdidError> {
+    let index = find_tag(bytes, tag)
+        .or_else(|| free_slot(bytes))
+        .ok_or_else(|| {
+            tracing::error!("No room to add a descriptor with tag `{tag:x}`.");
+            EdidErrorKind::PatchNoFreeDescriptor
+        })?;
+
+    set_slot(bytes, index, descriptor);
+    Ok(())
+}
+
+/// Lowers the range limits to `mhz`, then removes any faster timings.
+fn clamp_pixel_clock(bytes: &mut [u8], mhz: u16) -> Result<(), EdidError> {
+    // the range limits store it in steps of 10 MHz, and zero isn't a limit
+    if mhz < 10 {
+        tracing::error!("A maximum pixel clock of {mhz} MHz is too low to store.");
+        return Err(EdidErrorKind::PatchValueOutOfRange {
+            field: "max_pixel_clock_mhz",
+        }
+        .into());
+    }
+
+    if let Some(index) = find_tag(bytes, 0xFD) {
+        let mut limits = slot(bytes, index);
+
+        if u16::from(limits[9]) * 10 > mhz {
+            // cvt can get closer to the real value, in steps of 0.25 MHz
+            if limits[10] == 0x04 {
+                limits[9] = mhz.div_ceil(10) as u8;
+                let to_sub = (u16::from(limits[9]) * 10 - mhz) * 4;
+                limits[12] = ((to_sub as u8) << 2) | (limits[12] & 0b11);
+            } else {
+                limits[9] = (mhz / 10) as u8;
+            }
+
+            set_slot(bytes, index, &limits);
+        }
+    }
+
+    for index in 0..SLOTS.len() {
+        let descriptor = slot(bytes, index);
+        if !is_timing(&descriptor) {
+            continue;
+        }
+
+        // timings store their clock in units of 10 kHz
+        let clock_khz = u32::from(u16::from_le_bytes([descriptor[0], descriptor[1]])) * 10;
+        if clock_khz <= u32::from(mhz) * 1000 {
+            continue;
+        }
+
+        if index == 0 {
+            tracing::error!("The preferred timing is faster than {mhz} MHz.");
+            return Err(
+                EdidError::from(EdidErrorKind::PatchPreferredTimingTooFast).within(
+                    0,
+                    Section::Descriptor(1),
+                    SLOTS[0] as u16,
+                ),
+            );
+        }
+        tracing::info!("Removing a {clock_khz} kHz timing from slot {index}.");
+        set_slot(bytes, index, &DUMMY_DESCRIPTOR);
+    }
+
+    Ok(())
+}
+
+/// Writes the patched EDID to `dir`, under `name`, ready for the kernel to
+/// load.
+///
+/// With `dir` set to `/lib/firmware/edid`, boot with the parameter from
+/// `kernel_parameter` to use it.
+#[cfg(feature = "std")]
+pub fn write_firmware<P: AsRef<std::path::Path>>(
+    edid: &[u8],
+    dir: P,
+    name: &str,
+) -> std::io::Result<std::path::PathBuf> {
+    let path = dir.as_ref().join(name);
+    std::fs::write(&path, edid)?;
+    Ok(path)
+}
+
+/// Makes the kernel parameter that loads the firmware file `name` from
+/// `/lib/firmware/edid/` for the given `connector`, like `DP-1`.
+///
+/// ```edition2021
+/// use liboptic_edid::patch::kernel_parameter;
+///
+/// assert_eq!(
+///     kernel_parameter("DP-1", "dell.bin"),
+///     "drm.edid_firmware=DP-1:edid/dell.bin"
+/// );
+/// ```
+pub fn kernel_parameter(connector: &str, name: &str) -> String {
+    alloc::format!("drm.edid_firmware={connector}:edid/{name}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dell() -> Vec<u8> {
+        raw_edid_by_filename("dell_s2417dg.raw.input")
+    }
+
+    fn dell_preferred() -> DetailedTimingDefinition {
+        match Edid::new(dell())
+            .unwrap()
+            .eighteen_byte_data_blocks
+            .preferred_timing_mode
+        {
+            EighteenByteBlock::Timing(dtd) => dtd,
+            EighteenByteBlock::Display(_) => panic!("dell has a preferred timing"),
+        }
+    }
+
+    /// the dell, but with its serial descriptor replaced by a dummy
+    fn dell_with_free_slot() -> Vec<u8> {
+        let mut input = dell();
+        set_slot(&mut input, 1, &DUMMY_DESCRIPTOR);
+        fix_checksums(&mut input);
+        input
+    }
+
+    /// a 1080p60 timing, like you'd get from `cvt -r 1920 1080`
+    fn slow_timing() -> DetailedTimingDefinition {
+        DetailedTimingDefinition {
+            pixel_clock_khz: 13850,
+            horizontal_addressable_video_px: 1920,
+            horizontal_blanking_px: 160,
+            vertical_addressable_video_lines: 1080,
+            vertical_blanking_lines: 31,
+            horizontal_front_porch: 48,
+            horizontal_sync_pulse_width_px: 32,
+            vertical_front_porch_lines: 3,
+            vertical_sync_pulse_width_lines: 5,
+            horizontal_border_px: 0,
+            vertical_border_lines: 0,
+            sync_signal: SyncSignal::Digital(DigitalSyncSignal::SeparateNegVPosH),
+            ..dell_preferred()
+        }
+    }
+
+    /// all blocks should sum to zero
+    fn assert_checksums(bytes: &[u8]) {
+        for block in bytes.chunks(BLOCK_LEN) {
+            assert_eq!(block.iter().fold(0_u8, |s, b| s.wrapping_add(*b)), 0);
+        }
+    }
+
+    #[test]
+    fn no_changes() {
+        logger();
+        assert_eq!(EdidPatch::new().apply(&dell()).unwrap(), dell());
+    }
+
+    #[test]
+    fn product_name() {
+        logger();
+        let patched = EdidPatch::new()
+            .product_name("My Monitor")
+            .apply(&dell())
+            .unwrap();
+        assert_checksums(&patched);
+
+        let edid = Edid::new(&patched).unwrap();
+        assert_eq!(
+            edid.eighteen_byte_data_blocks.blocks[2],
+            EighteenByteBlock::Display(DisplayDescriptor::ProductName(
+                arrayvec::ArrayString::from("My Monitor\n  ").unwrap()
+            ))
+        );
+
+        assert!(matches!(
+            EdidPatch::new().product_name("ÜberMonitor").apply(&dell()),
+            Err(EdidError {
+                kind: EdidErrorKind::PatchBadProductName,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn strip_extension() {
+        logger();
+        let patched = EdidPatch::new().strip_extension(1).apply(&dell()).unwrap();
+        assert_eq!(patched.len(), BLOCK_LEN);
+        assert_checksums(&patched);
+        assert_eq!(Edid::new(&patched).unwrap().extension_info, 0);
+
+        assert!(matches!(
+            EdidPatch::new().strip_extension(2).apply(&dell()),
+            Err(EdidError {
+                kind: EdidErrorKind::PatchNoSuchExtension { index: 2 },
+                ..
+            })
+        ));
+    }
+
+    /// stripping a block after a block map removes its entry from the map
+    #[test]
+    fn strip_extension_block_map() {
+        logger();
+
+        // base, block map, then two copies of the cta block
+        let mut input = dell();
+        let cta = input[BLOCK_LEN..].to_vec();
+        let mut map = [0x00; BLOCK_LEN];
+        map[0] = BLOCK_MAP_TAG;
+        map[1] = 0x02;
+        map[2] = 0x02;
+        input.truncate(BLOCK_LEN);
+        input.extend_from_slice(&map);
+        input.extend_from_slice(&cta);
+        input.extend_from_slice(&cta);
+        input[0x7E] = 3;
+        fix_checksums(&mut input);
+
+        let patched = EdidPatch::new().strip_extension(2).apply(&input).unwrap();
+        assert_eq!(patched.len(), 3 * BLOCK_LEN);
+        assert_eq!(patched[0x7E], 2);
+        assert_eq!(patched[BLOCK_LEN], BLOCK_MAP_TAG);
+        assert_eq!(patched[BLOCK_LEN + 1..BLOCK_LEN + 4], [0x02, 0x00, 0x00]);
+        assert_checksums(&patched);
+    }
+
+    #[test]
+    fn preferred_timing() {
+        logger();
+        let patched = EdidPatch::new()
+            .preferred_timing(slow_timing())
+            .apply(&dell_with_free_slot())
+            .unwrap();
+        assert_checksums(&patched);
+
+        // the old preferred timing moves into the free slot
+        let blocks = Edid::new(&patched).unwrap().eighteen_byte_data_blocks;
+        assert_eq!(
+            blocks.preferred_timing_mode,
+            EighteenByteBlock::Timing(slow_timing())
+        );
+        assert_eq!(
+            blocks.blocks[0],
+            EighteenByteBlock::Timing(dell_preferred())
+        );
+    }
+
+    #[test]
+    fn add_timing() {
+        logger();
+        assert!(matches!(
+            EdidPatch::new().add_timing(slow_timing()).apply(&dell()),
+            Err(EdidError {
+                kind: EdidErrorKind::PatchNoFreeDescriptor,
+                ..
+            })
+        ));
+
+        let patched = EdidPatch::new()
+            .add_timing(slow_timing())
+            .apply(&dell_with_free_slot())
+            .unwrap();
+        let blocks = Edid::new(&patched).unwrap().eighteen_byte_data_blocks;
+        assert_eq!(blocks.blocks[0], EighteenByteBlock::Timing(slow_timing()));
+    }
+
+    #[test]
+    fn range_limits() {
+        logger();
+        let limits = RangeLimitsDesc::LimitsOnly {
+            limits: RangeLimits {
+                min_v_rate_hz: 24,
+                max_v_rate_hz: 165,
+                min_h_rate_khz: 30,
+                max_h_rate_khz: 255,
+                offsets: Offsets {
+                    vertical: VerticalOffset::Zero,
+                    horizontal: HorizontalOffset::Zero,
+                },
+                max_pixel_clock_mhz: 600,
+                interpretation: Interpretation::V1_4,
+            },
+            flexible: false,
+        };
+
+        let patched = EdidPatch::new()
+            .range_limits(limits.clone())
+            .apply(&dell())
+            .unwrap();
+        let blocks = Edid::new(&patched).unwrap().eighteen_byte_data_blocks;
+        assert_eq!(
+            blocks.blocks[1],
+            EighteenByteBlock::Display(DisplayDescriptor::DisplayRangeLimits(limits))
+        );
+    }
+
+    /// flexible range limits set the continuous frequency bit, so they come
+    /// back the same
+    #[test]
+    fn flexible_range_limits() {
+        logger();
+        let limits = |flexible| RangeLimitsDesc::LimitsOnly {
+            limits: RangeLimits {
+                min_v_rate_hz: 30,
+                max_v_rate_hz: 144,
+                min_h_rate_khz: 34,
+                max_h_rate_khz: 222,
+                offsets: Offsets {
+                    vertical: VerticalOffset::Zero,
+                    horizontal: HorizontalOffset::Zero,
+                },
+                max_pixel_clock_mhz: 590,
+                interpretation: Interpretation::V1_4,
+            },
+            flexible,
+        };
+
+        // the dell's range limits aren't flexible
+        let original = dell();
+        for flexible in [true, false] {
+            let patched = EdidPatch::new()
+                .range_limits(limits(flexible))
+                .apply(&original)
+                .unwrap();
+            assert_eq!(patched[0x18] & 0x01, u8::from(flexible));
+
+            let edid = Edid::new(&patched).unwrap();
+            let EighteenByteBlock::Display(DisplayDescriptor::DisplayRangeLimits(ref parsed)) =
+                edid.eighteen_byte_data_blocks.blocks[1]
+            else {
+                panic!("the range limits should still be there");
+            };
+            assert_eq!(parsed, &limits(flexible));
+
+            // encoding what was decoded changes nothing
+            let again = EdidPatch::new()
+                .range_limits(parsed.clone())
+                .apply(&patched)
+                .unwrap();
+            assert_eq!(again, patched);
+        }
+
+        // the limits the dell already has give back the same bytes
+        let patched = EdidPatch::new()
+            .range_limits(limits(false))
+            .apply(&original)
+            .unwrap();
+        assert_eq!(patched, original);
+
+        // and v1.3 has no way to say it
+        let msi = edid_by_filename("linuxhw_edid_Digital_MSI_MSIAF82_4B2991D4299A.input");
+        assert!(matches!(
+            EdidPatch::new().range_limits(limits(true)).apply(&msi),
+            Err(EdidError {
+                kind: EdidErrorKind::PatchValueOutOfRange { field: "flexible" },
+                ..
+            })
+        ));
+    }
+
+    /// rates past 255 need an offset, which only exists in v1.4
+    #[test]
+    fn range_limit_offsets() {
+        logger();
+        let limits = |max_v_rate_hz, interpretation| RangeLimitsDesc::GtfSupported {
+            limits: RangeLimits {
+                min_v_rate_hz: 48,
+                max_v_rate_hz,
+                min_h_rate_khz: 30,
+                max_h_rate_khz: 160,
+                offsets: Offsets {
+                    vertical: VerticalOffset::Max255Hz_MinNotOffset,
+                    horizontal: HorizontalOffset::Zero,
+                },
+                max_pixel_clock_mhz: 340,
+                interpretation,
+            },
+        };
+
+        let patched = EdidPatch::new()
+            .range_limits(limits(360, Interpretation::V1_4))
+            .apply(&dell())
+            .unwrap();
+        assert_eq!(patched[0x5A + 4], 0b0000_0010);
+        assert_eq!(
+            Edid::new(&patched)
+                .unwrap()
+                .eighteen_byte_data_blocks
+                .blocks[1],
+            EighteenByteBlock::Display(DisplayDescriptor::DisplayRangeLimits(limits(
+                360,
+                Interpretation::V1_4
+            )))
+        );
+
+        // the msi is v1.3
+        let msi = edid_by_filename("linuxhw_edid_Digital_MSI_MSIAF82_4B2991D4299A.input");
+        assert!(matches!(
+            EdidPatch::new()
+                .range_limits(limits(360, Interpretation::V1_3))
+                .apply(&msi),
+            Err(EdidError {
+                kind: EdidErrorKind::PatchValueOutOfRange {
+                    field: "max_v_rate_hz"
+                },
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn clamp_pixel_clock() {
+        logger();
+
+        // the dell's preferred timing runs at 241.5 MHz
+        assert!(matches!(
+            EdidPatch::new().max_pixel_clock_mhz(200).apply(&dell()),
+            Err(EdidError {
+                kind: EdidErrorKind::PatchPreferredTimingTooFast,
+                ..
+            })
+        ));
+
+        // ...so we'll swap it out first
+        let patched = EdidPatch::new()
+            .preferred_timing(slow_timing())
+            .max_pixel_clock_mhz(200)
+            .apply(&dell_with_free_slot())
+            .unwrap();
+        assert_checksums(&patched);
+
+        let blocks = Edid::new(&patched).unwrap().eighteen_byte_data_blocks;
+        assert_eq!(
+            blocks.blocks[0],
+            EighteenByteBlock::Display(DisplayDescriptor::DummyDescriptor)
+        );
+        let EighteenByteBlock::Display(DisplayDescriptor::DisplayRangeLimits(limits)) =
+            &blocks.blocks[1]
+        else {
+            panic!("the range limits should still be there");
+        };
+        assert_eq!(limits.limits().max_pixel_clock_mhz, 200);
+
+        // the range limits can't store anything under 10 MHz
+        assert!(matches!(
+            EdidPatch::new().max_pixel_clock_mhz(9).apply(&dell()),
+            Err(EdidError {
+                kind: EdidErrorKind::PatchValueOutOfRange {
+                    field: "max_pixel_clock_mhz"
+                },
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn kernel_parameters() {
+        assert_eq!(
+            kernel_parameter("HDMI-A-1", "tv.bin"),
+            "drm.edid_firmware=HDMI-A-1:edid/tv.bin"
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn firmware_file() {
+        logger();
+        let patched = EdidPatch::new().strip_extension(1).apply(&dell()).unwrap();
+
+        let dir = std::env::temp_dir();
+        let path = write_firmware(&patched, &dir, "liboptic_patch_test.bin").unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), patched);
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -6,8 +6,10 @@ pub(crate) mod internal {
     pub(crate) use crate::Edid;
 
     // structure modules
+    #[cfg(feature = "decimal")]
+    pub(crate) use crate::structures::size;
     pub(crate) use crate::structures::{
-        _18bytes, basic_info, color, est_timings, id, std_timings, version,
+        _18bytes, basic_info, color, desc, est_timings, id, identity, std_timings, version,
     };
 
     pub use crate::std_timings::{STiming, StandardAspectRatio, StandardTimings};
@@ -28,11 +30,23 @@ pub(crate) mod internal {
     pub use crate::structures::est_timings::{
         EstablishedTimings, EstablishedTimingsI, EstablishedTimingsII, ManufacturerTimings,
     };
+    pub(crate) use crate::structures::version::{EdidVersion, Interpretation};
 
     // errors
-    pub(crate) use crate::error::EdidError;
+    pub(crate) use crate::error::{EdidError, EdidErrorKind, Section};
 
-    // decimal crate
+    // logging. without the `tracing` feature, these compile to nothing
+    #[cfg(not(feature = "tracing"))]
+    pub(crate) use crate::log as tracing;
+    #[cfg(feature = "tracing")]
+    pub(crate) use ::tracing;
+
+    // exact fractions, like gamma
+    pub(crate) use num_rational::Ratio;
+
+    // decimal crate, for the color math
+    #[cfg(feature = "decimal")]
     pub(crate) use rust_decimal::Decimal;
+    #[cfg(feature = "decimal")]
     pub(crate) use rust_decimal_macros::dec;
 }
@@ -1,28 +1,37 @@
 //! Eighteen-byte descriptors.
 
-use crate::structures::desc::DisplayDescriptor;
+use core::fmt;
+
+use crate::structures::{desc::DisplayDescriptor, version::Interpretation};
 
 /// A collection of "18-byte descriptors".
 #[repr(C)]
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EighteenByteDescriptors {
     /// In EDID v1.3 and v1.4, this always contains the video timing mode that
     /// produces the "best quality image" according to the display's
     /// manufacturer.
     ///
-    /// However, earlier versions of EDID may not always contain this block
+    /// However, versions of EDID before v1.3 may not always contain this block
     /// (a [`DetailedTimingDefinition`]), so for compatibility, it is
     /// typed as an [`EighteenByteBlock`].
     pub preferred_timing_mode: EighteenByteBlock,
 
     /// Three additional blocks with information about the display.
     pub blocks: [EighteenByteBlock; 3],
+
+    /// The EDID revision used to read these blocks.
+    ///
+    /// Before v1.3, `preferred_timing_mode` may be a display descriptor.
+    pub interpretation: Interpretation,
 }
 
 /// An eighteen-byte block containing either a detailed timing or display
 /// descriptor.
 #[repr(C)]
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EighteenByteBlock {
     /// It's a timing definition block.
     Timing(timing::DetailedTimingDefinition),
@@ -30,16 +39,41 @@ pub enum EighteenByteBlock {
     Display(DisplayDescriptor),
 }
 
+impl fmt::Display for EighteenByteDescriptors {
+    /// Writes each block on its own line, like `Descriptor #1: ...`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Descriptor #1: {}", self.preferred_timing_mode)?;
+        for (i, block) in self.blocks.iter().enumerate() {
+            write!(f, "\nDescriptor #{}: {block}", i + 2)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for EighteenByteBlock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Timing(t) => write!(f, "{t}"),
+            Self::Display(d) => write!(f, "{d}"),
+        }
+    }
+}
+
 pub mod timing {
+    use core::fmt;
+
+    use num_rational::Ratio;
 
     #[repr(C)]
     #[derive(Clone, Debug, PartialEq, PartialOrd)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct DetailedTimingDefinition {
         // timing defs!
         //
-        /// The pixel clock of the device in kHz.
+        /// The pixel clock of the device, in units of 10 kHz.
         ///
-        /// Range is [10, 65535] KHz, in steps of 10 kHz.
+        /// Range is [1, 65535], so [10, 655350] kHz. Use
+        /// [`Self::pixel_clock_hz()`] for the clock in Hz.
         pub pixel_clock_khz: u16,
 
         pub horizontal_addressable_video_px: u16,
@@ -91,6 +125,7 @@ pub mod timing {
 
     #[repr(C)]
     #[derive(Clone, Debug, PartialEq, PartialOrd)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum SignalInterfaceType {
         /// 1 frame = 1 field
         NonInterlaced,
@@ -100,6 +135,7 @@ pub mod timing {
 
     #[repr(C)]
     #[derive(Clone, Debug, PartialEq, PartialOrd)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum StereoViewingSupport {
         NormalDisplay,
         FieldSequentialRight,
@@ -112,6 +148,7 @@ pub mod timing {
 
     #[repr(C)]
     #[derive(Clone, Debug, PartialEq, PartialOrd)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum SyncSignal {
         Analog {
             bipolar: bool,
@@ -124,13 +161,24 @@ pub mod timing {
 
     #[repr(C)]
     #[derive(Clone, Debug, PartialEq, PartialOrd)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum AnalogSyncOn {
         Green,
         Rgb,
     }
 
+    /// The polarity of a sync pulse.
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub enum SyncPolarity {
+        Positive,
+        Negative,
+    }
+
     #[repr(C)]
     #[derive(Clone, Debug, PartialEq, PartialOrd)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum DigitalSyncSignal {
         Composite,
         CompositeSerrations,
@@ -139,4 +187,294 @@ pub mod timing {
         SeparatePosVNegH,
         SeparatePosVPosH,
     }
+
+    impl DetailedTimingDefinition {
+        /// Whether each frame is made of two interlaced fields.
+        pub fn is_interlaced(&self) -> bool {
+            self.signal_interface_type == SignalInterfaceType::Interlaced
+        }
+
+        /// The pixel clock in Hz.
+        ///
+        /// The `pixel_clock_khz` field is in units of 10 kHz, like the EDID
+        /// stores it.
+        pub fn pixel_clock_hz(&self) -> u64 {
+            u64::from(self.pixel_clock_khz) * 10_000
+        }
+
+        /// The total width of a line, including blanking, in pixels.
+        pub fn h_total(&self) -> u32 {
+            u32::from(self.horizontal_addressable_video_px) + u32::from(self.horizontal_blanking_px)
+        }
+
+        /// The total height of a field, including blanking, in lines.
+        ///
+        /// For interlaced timings, this is one field, not the whole frame.
+        pub fn v_total(&self) -> u32 {
+            u32::from(self.vertical_addressable_video_lines)
+                + u32::from(self.vertical_blanking_lines)
+        }
+
+        /// From the end of the sync pulse to the end of blanking, in pixels.
+        ///
+        /// This is zero if the porch and sync don't fit in the blanking.
+        pub fn h_back_porch(&self) -> u16 {
+            self.horizontal_blanking_px
+                .saturating_sub(self.horizontal_front_porch)
+                .saturating_sub(self.horizontal_sync_pulse_width_px)
+        }
+
+        /// From the end of the sync pulse to the end of blanking, in lines.
+        ///
+        /// This is zero if the porch and sync don't fit in the blanking.
+        pub fn v_back_porch(&self) -> u16 {
+            self.vertical_blanking_lines
+                .saturating_sub(u16::from(self.vertical_front_porch_lines))
+                .saturating_sub(u16::from(self.vertical_sync_pulse_width_lines))
+        }
+
+        /// The horizontal (line) frequency in kHz.
+        ///
+        /// Returns `None` if the line is zero pixels wide.
+        pub fn h_freq_khz(&self) -> Option<Ratio<u64>> {
+            let h_total = u64::from(self.h_total());
+            (h_total != 0).then(|| Ratio::new(self.pixel_clock_hz(), h_total * 1000))
+        }
+
+        /// The exact vertical refresh rate in Hz.
+        ///
+        /// For interlaced timings, this is the field rate. Each field gets an
+        /// extra half line, so a frame has `2 * v_total() + 1` lines. Use
+        /// [`Self::frame_rate`] for the rate of whole frames.
+        ///
+        /// Returns `None` if either total is zero.
+        pub fn refresh_rate(&self) -> Option<Ratio<u64>> {
+            let h_total = u64::from(self.h_total());
+            let v_total = u64::from(self.v_total());
+            if h_total == 0 || v_total == 0 {
+                return None;
+            }
+
+            let clock_hz = self.pixel_clock_hz();
+            Some(if self.is_interlaced() {
+                Ratio::new(2 * clock_hz, h_total * (2 * v_total + 1))
+            } else {
+                Ratio::new(clock_hz, h_total * v_total)
+            })
+        }
+
+        /// The exact rate of whole frames in Hz.
+        ///
+        /// This is half the [refresh rate](Self::refresh_rate) for interlaced
+        /// timings, and the same otherwise.
+        pub fn frame_rate(&self) -> Option<Ratio<u64>> {
+            let rate = self.refresh_rate()?;
+            Some(if self.is_interlaced() { rate / 2 } else { rate })
+        }
+
+        /// The polarity of the horizontal sync pulse.
+        ///
+        /// Only separate digital sync has one.
+        pub fn h_sync_polarity(&self) -> Option<SyncPolarity> {
+            match self.sync_signal {
+                SyncSignal::Digital(ref d) => d.h_sync_polarity(),
+                SyncSignal::Analog { .. } => None,
+            }
+        }
+
+        /// The polarity of the vertical sync pulse.
+        ///
+        /// Only separate digital sync has one.
+        pub fn v_sync_polarity(&self) -> Option<SyncPolarity> {
+            match self.sync_signal {
+                SyncSignal::Digital(ref d) => d.v_sync_polarity(),
+                SyncSignal::Analog { .. } => None,
+            }
+        }
+
+        /// The aspect ratio of the image, from its size in millimeters.
+        ///
+        /// Returns `None` if either size is missing or zero.
+        pub fn aspect_ratio(&self) -> Option<Ratio<u32>> {
+            let h = self.horizontal_addressable_video_size_mm?;
+            let v = self.vertical_addressable_video_size_mm?;
+            (h != 0 && v != 0).then(|| Ratio::new(u32::from(h), u32::from(v)))
+        }
+    }
+
+    impl DigitalSyncSignal {
+        /// The polarity of the horizontal sync pulse, if the sync is separate.
+        pub fn h_sync_polarity(&self) -> Option<SyncPolarity> {
+            match self {
+                Self::SeparateNegVNegH | Self::SeparatePosVNegH => Some(SyncPolarity::Negative),
+                Self::SeparateNegVPosH | Self::SeparatePosVPosH => Some(SyncPolarity::Positive),
+                Self::Composite | Self::CompositeSerrations => None,
+            }
+        }
+
+        /// The polarity of the vertical sync pulse, if the sync is separate.
+        pub fn v_sync_polarity(&self) -> Option<SyncPolarity> {
+            match self {
+                Self::SeparateNegVNegH | Self::SeparateNegVPosH => Some(SyncPolarity::Negative),
+                Self::SeparatePosVNegH | Self::SeparatePosVPosH => Some(SyncPolarity::Positive),
+                Self::Composite | Self::CompositeSerrations => None,
+            }
+        }
+    }
+
+    impl fmt::Display for DetailedTimingDefinition {
+        /// Writes the timing like an X11 modeline, without the `Modeline`
+        /// keyword:
+        ///
+        /// ```text
+        /// "2560x1440" 241.50 2560 2608 2640 2720 1440 1443 1448 1481 +HSync -VSync
+        /// ```
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            crate::modeline::Modeline::from(self).write_body(f)
+        }
+    }
+
+    impl fmt::Display for SignalInterfaceType {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(match self {
+                Self::NonInterlaced => "non-interlaced",
+                Self::Interlaced => "interlaced",
+            })
+        }
+    }
+
+    impl fmt::Display for StereoViewingSupport {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(match self {
+                Self::NormalDisplay => "no stereo",
+                Self::FieldSequentialRight => "field sequential stereo, right on sync",
+                Self::FieldSequentialLeft => "field sequential stereo, left on sync",
+                Self::TwoWayInterleavedRight => "2-way interleaved stereo, right on even lines",
+                Self::TwoWayInterleavedLeft => "2-way interleaved stereo, left on even lines",
+                Self::FourWayInterleaved => "4-way interleaved stereo",
+                Self::SideBySide => "side-by-side interleaved stereo",
+            })
+        }
+    }
+
+    impl fmt::Display for SyncSignal {
+        /// Writes the sync the way X11 modelines do, like `+HSync -VSync`.
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                Self::Analog {
+                    bipolar,
+                    with_serrations,
+                    sync_mode,
+                } => {
+                    f.write_str("Composite")?;
+                    if *bipolar {
+                        f.write_str(" (bipolar)")?;
+                    }
+                    if *with_serrations {
+                        f.write_str(" (serrations)")?;
+                    }
+                    write!(f, " ({sync_mode})")
+                }
+                Self::Digital(d) => write!(f, "{d}"),
+            }
+        }
+    }
+
+    impl fmt::Display for AnalogSyncOn {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(match self {
+                Self::Green => "sync on green",
+                Self::Rgb => "sync on RGB",
+            })
+        }
+    }
+
+    impl fmt::Display for DigitalSyncSignal {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(match self {
+                Self::Composite => "Composite",
+                Self::CompositeSerrations => "Composite (serrations)",
+                Self::SeparateNegVNegH => "-HSync -VSync",
+                Self::SeparateNegVPosH => "+HSync -VSync",
+                Self::SeparatePosVNegH => "-HSync +VSync",
+                Self::SeparatePosVPosH => "+HSync +VSync",
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_rational::Ratio;
+
+    use crate::prelude::internal::*;
+
+    #[test]
+    fn dell_timing_metrics() {
+        logger();
+        let edid = Edid::new(raw_edid_by_filename("dell_s2417dg.raw.input")).unwrap();
+        let EighteenByteBlock::Timing(ref t) = edid.eighteen_byte_data_blocks.preferred_timing_mode
+        else {
+            panic!("the dell's preferred timing should be a dtd");
+        };
+
+        assert_eq!(
+            (t.pixel_clock_khz, t.pixel_clock_hz()),
+            (24150, 241_500_000)
+        );
+        assert_eq!((t.h_total(), t.v_total()), (2720, 1481));
+        assert_eq!((t.h_back_porch(), t.v_back_porch()), (80, 33));
+        assert_eq!(t.h_freq_khz(), Some(Ratio::new(241_500, 2720)));
+
+        // 241.5 MHz / (2720 * 1481), which isn't quite 60 Hz
+        let refresh = t.refresh_rate().unwrap();
+        assert_eq!(refresh, Ratio::new(241_500_000, 2720 * 1481));
+        assert_eq!(refresh.to_integer(), 59);
+        assert_eq!(t.frame_rate(), Some(refresh));
+
+        assert_eq!(
+            (t.h_sync_polarity(), t.v_sync_polarity()),
+            (Some(SyncPolarity::Positive), Some(SyncPolarity::Negative))
+        );
+        // 527x296 mm is only close to 16:9
+        assert_eq!(t.aspect_ratio(), Some(Ratio::new(527, 296)));
+    }
+
+    #[test]
+    fn interlaced_rates() {
+        logger();
+        let t = DetailedTimingDefinition {
+            pixel_clock_khz: 7425,
+            horizontal_addressable_video_px: 1920,
+            horizontal_blanking_px: 280,
+            vertical_addressable_video_lines: 540,
+            vertical_blanking_lines: 22,
+            horizontal_front_porch: 88,
+            horizontal_sync_pulse_width_px: 44,
+            vertical_front_porch_lines: 2,
+            vertical_sync_pulse_width_lines: 5,
+            horizontal_addressable_video_size_mm: Some(0),
+            vertical_addressable_video_size_mm: Some(0),
+            horizontal_border_px: 0,
+            vertical_border_lines: 0,
+            signal_interface_type: SignalInterfaceType::Interlaced,
+            stereo_support: StereoViewingSupport::NormalDisplay,
+            sync_signal: SyncSignal::Analog {
+                bipolar: false,
+                with_serrations: true,
+                sync_mode: AnalogSyncOn::Rgb,
+            },
+        };
+
+        // 1080i has 1125 lines per frame, so exactly 60 fields a second
+        assert!(t.is_interlaced());
+        assert_eq!(t.refresh_rate(), Some(Ratio::from_integer(60)));
+        assert_eq!(t.frame_rate(), Some(Ratio::from_integer(30)));
+        assert_eq!(t.h_freq_khz(), Some(Ratio::new(135, 4)));
+        assert_eq!(t.v_back_porch(), 15);
+
+        // analog sync and a missing size don't have an answer
+        assert_eq!((t.h_sync_polarity(), t.v_sync_polarity()), (None, None));
+        assert_eq!(t.aspect_ratio(), None);
+    }
 }
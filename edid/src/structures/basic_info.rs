@@ -1,9 +1,22 @@
 //! Basic display info.
 
-use rust_decimal::Decimal;
+use core::fmt;
+
+use num_rational::Ratio;
+
+use super::{decimal::AsDecimal, version::Interpretation, write_list};
+use feature_support::{
+    ColorEncodingFormats, ColorSupport, ColorType, FeatureSupport, PowerManagement,
+};
+use vsi::{
+    analog::{SignalLevelStandard, SyncTypes, VideoSetup},
+    digital::{ColorBitDepth, SupportedVideoInterface},
+    VideoSignalInterface,
+};
 
 #[repr(C)]
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BasicDisplayInfo {
     pub input_definition: vsi::VideoSignalInterface,
 
@@ -15,16 +28,24 @@ pub struct BasicDisplayInfo {
     /// The device's gamma value.
     ///
     /// If `None`, it should be in an extension block.
-    pub reported_gamma: Option<Decimal>,
+    #[cfg_attr(feature = "serde", serde(with = "super::decimal::string::option"))]
+    pub reported_gamma: Option<Ratio<u16>>,
 
     /// Info about the display's support for various misc. features.
     pub feature_support: feature_support::FeatureSupport,
+
+    /// The EDID revision used to read these bytes.
+    ///
+    /// The input definition, screen size, and feature support bytes changed
+    /// meaning in v1.4.
+    pub interpretation: Interpretation,
 }
 
 pub mod vsi {
     /// Either an analog or digital VSI.
     #[repr(C)]
     #[derive(Clone, Debug, PartialEq, PartialOrd)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum VideoSignalInterface {
         // IMPLEMENTATION NOTE:
         //
@@ -43,6 +64,12 @@ pub mod vsi {
             color_bit_depth: digital::ColorBitDepth,
             supported_interface: Option<digital::SupportedVideoInterface>,
         },
+
+        /// A digital input on EDID v1.3 and earlier.
+        ///
+        /// These revisions don't report a bit depth or interface. Instead,
+        /// they only say if the display is compatible with VESA DFP 1.x.
+        LegacyDigital { dfp_1x_compatible: bool },
     }
 
     pub mod analog {
@@ -50,6 +77,7 @@ pub mod vsi {
         #[repr(C)]
         #[expect(non_camel_case_types)]
         #[derive(Clone, Debug, PartialEq, PartialOrd)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         pub enum SignalLevelStandard {
             /// 0.7 + 0.3 = 1.0 V
             _0700S_0300L_1000T,
@@ -67,6 +95,7 @@ pub mod vsi {
         /// The type of video setup on an analog display.
         #[repr(C)]
         #[derive(Clone, Debug, PartialEq, PartialOrd)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         pub enum VideoSetup {
             BlackLevel,
             B2BOrPedestal,
@@ -77,6 +106,7 @@ pub mod vsi {
         /// `true` is supported, `false` is unsupported.
         #[repr(C)]
         #[derive(Clone, Debug, PartialEq, PartialOrd)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         pub struct SyncTypes {
             pub separate_sync_h_and_v: bool,
             pub composite_sync_horizontal: bool,
@@ -88,6 +118,7 @@ pub mod vsi {
         /// The bit depth of a digital display.
         #[repr(C)]
         #[derive(Clone, Debug, PartialEq, PartialOrd)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         pub enum ColorBitDepth {
             /// The display didn't tell us!
             Undefined,
@@ -104,6 +135,7 @@ pub mod vsi {
         /// A supported digital video interface standard for a digital display.
         #[repr(C)]
         #[derive(Clone, Debug, PartialEq, PartialOrd)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         pub enum SupportedVideoInterface {
             Dvi,
             HdmiA,
@@ -115,8 +147,12 @@ pub mod vsi {
 }
 
 /// The screen size or aspect ratio of a device, if given.
+///
+/// Note that only EDID v1.4 can report an aspect ratio. Earlier revisions only
+/// provide a screen size.
 #[repr(C)]
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SizeOrRatio {
     /// The screen size in centimeters (cm).
     ScreenSize { horizontal_cm: u8, vertical_cm: u8 },
@@ -128,11 +164,13 @@ pub enum SizeOrRatio {
 pub mod feature_support {
     #[repr(C)]
     #[derive(Clone, Debug, PartialEq, PartialOrd)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct FeatureSupport {
         pub power_management: PowerManagement,
         //
         // IMPLEMENTATION NOTE: we get "color type" if VideoSignalInterface::Analog,
-        // otherwise we check for encoding formats
+        // otherwise we check for encoding formats. before v1.4, it's always
+        // "color type"
         pub color_support: ColorSupport,
 
         /// Whether sRGB Standard is the default color space.
@@ -140,10 +178,22 @@ pub mod feature_support {
 
         /// Whether the Preferred Timing Mode has info about the native
         /// pixel format and preferred refresh rate for the display.
+        ///
+        /// On EDID v1.3, this instead indicates that the first 18-byte block
+        /// is the preferred timing, which is required for that revision.
         pub says_pixel_format_and_refresh: bool,
 
         /// Whether the display is continuous-frequency.
+        ///
+        /// This is only reported by EDID v1.4. Earlier revisions use the same
+        /// bit for `supports_default_gtf` instead.
         pub is_continuous_freq: bool,
+
+        /// Whether the display supports timings based on the GTF standard
+        /// using its default values.
+        ///
+        /// This is only reported by EDID v1.3 and earlier.
+        pub supports_default_gtf: bool,
     }
 
     /// Supported power modes.
@@ -154,6 +204,7 @@ pub mod feature_support {
     /// `true` means supported; `false` indicates unsupported.
     #[repr(C)]
     #[derive(Clone, Debug, PartialEq, PartialOrd)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct PowerManagement {
         pub standby: bool,
         pub suspend: bool,
@@ -162,6 +213,7 @@ pub mod feature_support {
 
     #[repr(C)]
     #[derive(Clone, Debug, PartialEq, PartialOrd)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum ColorSupport {
         Type(ColorType),
         EncodingFormats(ColorEncodingFormats),
@@ -169,6 +221,7 @@ pub mod feature_support {
 
     #[repr(C)]
     #[derive(Clone, Debug, PartialEq, PartialOrd)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum ColorType {
         MonochromeOrGrayscale,
         RgbColor,
@@ -179,6 +232,7 @@ pub mod feature_support {
     #[repr(C)]
     #[expect(non_camel_case_types)]
     #[derive(Clone, Debug, PartialEq, PartialOrd)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum ColorEncodingFormats {
         Rgb444,
         Rgb444_YCrCb444,
@@ -186,3 +240,199 @@ pub mod feature_support {
         Rgb444_YCrCb444_YCrCb422,
     }
 }
+
+impl fmt::Display for BasicDisplayInfo {
+    /// Writes the input, size, gamma, and features, separated by semicolons.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}; ", self.input_definition)?;
+        match self.screen_size_or_aspect_ratio {
+            Some(ref size) => write!(f, "{size}; ")?,
+            None => f.write_str("variable size; ")?,
+        }
+        match self.reported_gamma {
+            Some(gamma) => write!(f, "gamma {:.2}; ", AsDecimal::new(&gamma))?,
+            None => f.write_str("gamma in extension; ")?,
+        }
+        write!(f, "{}", self.feature_support)
+    }
+}
+
+impl fmt::Display for VideoSignalInterface {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Analog {
+                signal_level_standard,
+                video_setup,
+                sync_types,
+                serrations,
+            } => {
+                write!(
+                    f,
+                    "analog, {signal_level_standard}, {video_setup}, sync ({sync_types})"
+                )?;
+                if *serrations {
+                    f.write_str(", serrations")?;
+                }
+                Ok(())
+            }
+            Self::Digital {
+                color_bit_depth,
+                supported_interface,
+            } => {
+                write!(f, "digital, {color_bit_depth}")?;
+                if let Some(interface) = supported_interface {
+                    write!(f, ", {interface}")?;
+                }
+                Ok(())
+            }
+            Self::LegacyDigital { dfp_1x_compatible } => {
+                f.write_str("digital")?;
+                if *dfp_1x_compatible {
+                    f.write_str(", DFP 1.x compatible")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl fmt::Display for SignalLevelStandard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::_0700S_0300L_1000T => "0.700 : 0.300 : 1.000 V p-p",
+            Self::_0714S_0286L_1000T => "0.714 : 0.286 : 1.000 V p-p",
+            Self::_1000S_0400L_1400T => "1.000 : 0.400 : 1.400 V p-p",
+            Self::_0700S_0000L_0700T => "0.700 : 0.000 : 0.700 V p-p",
+        })
+    }
+}
+
+impl fmt::Display for VideoSetup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::BlackLevel => "blank level is black",
+            Self::B2BOrPedestal => "blank-to-black setup",
+        })
+    }
+}
+
+impl fmt::Display for SyncTypes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let all = [
+            (self.separate_sync_h_and_v, "separate"),
+            (self.composite_sync_horizontal, "composite on HSync"),
+            (self.composite_sync_green_video, "on green"),
+        ];
+        write_list(f, all.into_iter().filter_map(|(set, s)| set.then_some(s)))
+    }
+}
+
+impl fmt::Display for ColorBitDepth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bits = match self {
+            Self::Undefined => return f.write_str("undefined bit depth"),
+            Self::Reserved => return f.write_str("reserved bit depth"),
+            Self::D6Bits => 6,
+            Self::D8Bits => 8,
+            Self::D10Bits => 10,
+            Self::D12Bits => 12,
+            Self::D14Bits => 14,
+            Self::D16Bits => 16,
+        };
+        write!(f, "{bits} bits per channel")
+    }
+}
+
+impl fmt::Display for SupportedVideoInterface {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Dvi => "DVI",
+            Self::HdmiA => "HDMI-a",
+            Self::HdmiB => "HDMI-b",
+            Self::Mddi => "MDDI",
+            Self::DisplayPort => "DisplayPort",
+        })
+    }
+}
+
+impl fmt::Display for SizeOrRatio {
+    /// Writes a size like `53x30 cm`, or an aspect ratio like `16:9`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ScreenSize {
+                horizontal_cm,
+                vertical_cm,
+            } => write!(f, "{horizontal_cm}x{vertical_cm} cm"),
+            Self::AspectRatio {
+                horizontal,
+                vertical,
+            } => write!(f, "{horizontal}:{vertical}"),
+        }
+    }
+}
+
+impl fmt::Display for FeatureSupport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}, DPMS ({})",
+            self.color_support, self.power_management
+        )?;
+
+        let flags = [
+            (self.srgb_std, "sRGB default"),
+            (
+                self.says_pixel_format_and_refresh,
+                "native preferred timing",
+            ),
+            (self.is_continuous_freq, "continuous frequency"),
+            (self.supports_default_gtf, "default GTF"),
+        ];
+        for (_, flag) in flags.iter().filter(|(set, _)| *set) {
+            write!(f, ", {flag}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for PowerManagement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let all = [
+            (self.standby, "standby"),
+            (self.suspend, "suspend"),
+            (self.active_off, "active off"),
+        ];
+        write_list(f, all.into_iter().filter_map(|(set, s)| set.then_some(s)))
+    }
+}
+
+impl fmt::Display for ColorSupport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Type(t) => write!(f, "{t}"),
+            Self::EncodingFormats(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl fmt::Display for ColorType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::MonochromeOrGrayscale => "monochrome or grayscale",
+            Self::RgbColor => "RGB color",
+            Self::NonRgbColor => "non-RGB color",
+            Self::Undefined => "undefined color type",
+        })
+    }
+}
+
+impl fmt::Display for ColorEncodingFormats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Rgb444 => "RGB 4:4:4",
+            Self::Rgb444_YCrCb444 => "RGB 4:4:4 + YCrCb 4:4:4",
+            Self::Rgb444_YCrCb422 => "RGB 4:4:4 + YCrCb 4:2:2",
+            Self::Rgb444_YCrCb444_YCrCb422 => "RGB 4:4:4 + YCrCb 4:4:4 + YCrCb 4:2:2",
+        })
+    }
+}
@@ -1,12 +1,17 @@
 //! Color characteristics.
 
-use rust_decimal::Decimal;
+use core::fmt;
+
+use num_rational::Ratio;
+
+use super::decimal::AsDecimal;
 
 /// A representation of the CIE 1931 color space.
 ///
 /// Indicates the colors a device can display.
 #[repr(C)]
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ColorCharacteristics {
     pub red: ColorCoordinate,
     pub green: ColorCoordinate,
@@ -18,14 +23,39 @@ pub struct ColorCharacteristics {
 /// which colors a device can display.
 #[repr(C)]
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ColorCoordinate {
-    pub x: Decimal,
-    pub y: Decimal,
+    #[cfg_attr(feature = "serde", serde(with = "super::decimal::string"))]
+    pub x: Ratio<u32>,
+    #[cfg_attr(feature = "serde", serde(with = "super::decimal::string"))]
+    pub y: Ratio<u32>,
 }
 
 impl ColorCoordinate {
     /// Creates a new color coordinate on the chart.
-    pub fn new(x: Decimal, y: Decimal) -> Self {
+    pub fn new(x: Ratio<u32>, y: Ratio<u32>) -> Self {
         Self { x, y }
     }
 }
+
+impl fmt::Display for ColorCharacteristics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "red {}, green {}, blue {}, white {}",
+            self.red, self.green, self.blue, self.white_point
+        )
+    }
+}
+
+impl fmt::Display for ColorCoordinate {
+    /// Writes the coordinate to four decimal places, like `(0.6406, 0.3300)`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "({:.4}, {:.4})",
+            AsDecimal::new(&self.x),
+            AsDecimal::new(&self.y)
+        )
+    }
+}
@@ -0,0 +1,228 @@
+//! Exact ratios, written out as decimal numbers.
+//!
+//! The EDID stores things like gamma and chromaticity coordinates as
+//! fractions with a fixed denominator, like 1024ths. They're kept as
+//! `Ratio`s so they stay exact, but people expect to read them as decimals.
+
+use core::fmt::{self, Write as _};
+
+use arrayvec::ArrayString;
+use num_rational::Ratio;
+
+/// Stops runaway digits from ratios that don't end, like a third.
+const MAX_PLACES: usize = 20;
+
+/// Formats a ratio as a decimal number, like `2.2`.
+///
+/// With a precision, like `{:.2}`, this truncates (or pads) to that many
+/// places. Otherwise, it writes every digit the ratio has.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct AsDecimal(pub(crate) Ratio<i64>);
+
+impl AsDecimal {
+    pub(crate) fn new<T: Clone + Into<i64>>(ratio: &Ratio<T>) -> Self {
+        Self(Ratio::new_raw(
+            ratio.numer().clone().into(),
+            ratio.denom().clone().into(),
+        ))
+    }
+}
+
+impl fmt::Display for AsDecimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (numer, denom) = (*self.0.numer(), *self.0.denom());
+        let negative = numer != 0 && (numer < 0) != (denom < 0);
+        // in a u128, so multiplying the remainder by ten can't overflow
+        let (numer, denom) = (
+            u128::from(numer.unsigned_abs()),
+            u128::from(denom.unsigned_abs()),
+        );
+
+        // an i64 has at most 20 digits, so this always fits
+        let mut out = ArrayString::<64>::new();
+        write!(out, "{}", numer / denom)?;
+
+        let places = f.precision().map_or(MAX_PLACES, |p| p.min(40));
+        let mut rest = numer % denom;
+        for place in 0..places {
+            if rest == 0 && f.precision().is_none() {
+                break;
+            }
+            if place == 0 {
+                out.push('.');
+            }
+
+            rest *= 10;
+            write!(out, "{}", rest / denom)?;
+            rest %= denom;
+        }
+
+        f.pad_integral(!negative, "", &out)
+    }
+}
+
+/// Reads a decimal number, like `148.5` or `-0.20`, into an exact ratio.
+pub(crate) fn parse(s: &str) -> Option<Ratio<i64>> {
+    let (negative, s) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s),
+    };
+    let (whole, frac) = s.split_once('.').unwrap_or((s, ""));
+    if whole.is_empty() && frac.is_empty() {
+        return None;
+    }
+
+    let mut numer = 0_i64;
+    let mut denom = 1_i64;
+    for (i, c) in whole.chars().chain(frac.chars()).enumerate() {
+        let digit = i64::from(c.to_digit(10)?);
+        numer = numer.checked_mul(10)?.checked_add(digit)?;
+        if i >= whole.len() {
+            denom = denom.checked_mul(10)?;
+        }
+    }
+
+    Some(Ratio::new(if negative { -numer } else { numer }, denom))
+}
+
+/// Serializes a ratio as a string holding its exact decimal value.
+#[cfg(feature = "serde")]
+pub(crate) mod string {
+    use core::{fmt, marker::PhantomData};
+
+    use num_rational::Ratio;
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{parse, AsDecimal};
+
+    /// A ratio, as it's serialized.
+    struct Exact<T>(Ratio<T>);
+
+    impl<T: Clone + Into<i64>> Serialize for Exact<T> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_str(&AsDecimal::new(&self.0))
+        }
+    }
+
+    impl<'de, T: TryFrom<i64>> Deserialize<'de> for Exact<T> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_str(Visitor(PhantomData))
+        }
+    }
+
+    struct Visitor<T>(PhantomData<T>);
+
+    impl<T: TryFrom<i64>> de::Visitor<'_> for Visitor<T> {
+        type Value = Exact<T>;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a decimal number in a string")
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            // `parse` already reduced it, so this only fails when it's too big
+            let fits = |r: Ratio<i64>| {
+                let numer = T::try_from(*r.numer()).ok()?;
+                let denom = T::try_from(*r.denom()).ok()?;
+                Some(Exact(Ratio::new_raw(numer, denom)))
+            };
+
+            parse(v)
+                .and_then(fits)
+                .ok_or_else(|| E::invalid_value(de::Unexpected::Str(v), &self))
+        }
+    }
+
+    pub(crate) fn serialize<T, S>(ratio: &Ratio<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Clone + Into<i64>,
+        S: Serializer,
+    {
+        Exact(ratio.clone()).serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, T, D>(deserializer: D) -> Result<Ratio<T>, D::Error>
+    where
+        T: TryFrom<i64>,
+        D: Deserializer<'de>,
+    {
+        Exact::deserialize(deserializer).map(|exact| exact.0)
+    }
+
+    /// The same, for optional ratios.
+    pub(crate) mod option {
+        use num_rational::Ratio;
+        use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+        use super::Exact;
+
+        pub(crate) fn serialize<T, S>(
+            ratio: &Option<Ratio<T>>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error>
+        where
+            T: Clone + Into<i64>,
+            S: Serializer,
+        {
+            ratio.clone().map(Exact).serialize(serializer)
+        }
+
+        pub(crate) fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<Ratio<T>>, D::Error>
+        where
+            T: TryFrom<i64>,
+            D: Deserializer<'de>,
+        {
+            let ratio = Option::<Exact<T>>::deserialize(deserializer)?;
+            Ok(ratio.map(|exact| exact.0))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrayvec::ArrayString;
+    use core::fmt::Write as _;
+
+    use super::*;
+
+    fn show(ratio: Ratio<i64>, places: Option<usize>) -> ArrayString<64> {
+        let mut out = ArrayString::new();
+        match places {
+            Some(p) => write!(out, "{:.p$}", AsDecimal(ratio)),
+            None => write!(out, "{}", AsDecimal(ratio)),
+        }
+        .unwrap();
+        out
+    }
+
+    #[test]
+    fn formatting() {
+        assert_eq!(show(Ratio::new(11, 5), None).as_str(), "2.2");
+        assert_eq!(show(Ratio::new(11, 5), Some(2)).as_str(), "2.20");
+        assert_eq!(show(Ratio::new(655, 1024), None).as_str(), "0.6396484375");
+
+        // this truncates instead of rounding
+        assert_eq!(show(Ratio::new(655, 1024), Some(4)).as_str(), "0.6396");
+        assert_eq!(show(Ratio::new(-1, 5), Some(2)).as_str(), "-0.20");
+        assert_eq!(show(Ratio::new(3, 1), None).as_str(), "3");
+        assert_eq!(
+            show(Ratio::new(1, 3), None).as_str(),
+            "0.33333333333333333333"
+        );
+    }
+
+    #[test]
+    fn parsing() {
+        assert_eq!(parse("148.5"), Some(Ratio::new(297, 2)));
+        assert_eq!(parse("2.20"), Some(Ratio::new(11, 5)));
+        assert_eq!(parse("-0.20"), Some(Ratio::new(-1, 5)));
+        assert_eq!(parse("25"), Some(Ratio::from_integer(25)));
+        assert_eq!(parse(".5"), Some(Ratio::new(1, 2)));
+
+        assert_eq!(parse(""), None);
+        assert_eq!(parse("."), None);
+        assert_eq!(parse("1.2.3"), None);
+        assert_eq!(parse("abc"), None);
+        assert_eq!(parse("99999999999999999999"), None);
+    }
+}
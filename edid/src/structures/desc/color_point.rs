@@ -1,16 +1,35 @@
-use rust_decimal::Decimal;
+use core::fmt;
+
+use num_rational::Ratio;
 
 use super::color::ColorCoordinate;
+use crate::structures::decimal::AsDecimal;
 
 /// A white point coordinate on the CIE 1931 color space graph.
 ///
 /// These are used in the 18 byte descriptors when additional colors are given.
 #[repr(C)]
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct WhitePoint {
     pub index_number: u8,
     pub coord: ColorCoordinate,
 
     /// If None, then the gamma is defined in an extension block.
-    pub gamma: Option<Decimal>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::structures::decimal::string::option")
+    )]
+    pub gamma: Option<Ratio<u16>>,
+}
+
+impl fmt::Display for WhitePoint {
+    /// Writes the point like `#1 (0.3125, 0.3291), gamma 2.20`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{} {}, ", self.index_number, self.coord)?;
+        match self.gamma {
+            Some(gamma) => write!(f, "gamma {:.2}", AsDecimal::new(&gamma)),
+            None => f.write_str("gamma in extension"),
+        }
+    }
 }
@@ -1,5 +1,10 @@
+use core::fmt;
+
+use crate::structures::write_list;
+
 #[repr(C)]
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TimingCodeDesc {
     /// really a "u12"
     pub addressable_lines: u16,
@@ -11,6 +16,7 @@ pub struct TimingCodeDesc {
 
 #[repr(C)]
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CvtAspectRatio {
     _4_3,
     _16_9,
@@ -21,6 +27,7 @@ pub enum CvtAspectRatio {
 /// The referred vertical rate.
 #[repr(C)]
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CvtPreferredVerticalRate {
     _50Hz,
     _60Hz,
@@ -31,6 +38,7 @@ pub enum CvtPreferredVerticalRate {
 /// The supported vertical rates and their blanking styles.
 #[repr(C)]
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SupportedVRates {
     pub _50_hz_standard: bool,
     pub _60_hz_standard: bool,
@@ -38,3 +46,52 @@ pub struct SupportedVRates {
     pub _85_hz_standard: bool,
     pub _60_hz_reduced: bool,
 }
+
+impl fmt::Display for TimingCodeDesc {
+    /// Writes the code like `1080 lines (16:9) @ 60Hz, supports 60Hz, 60Hz reduced`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} lines ({}) @ {}, supports {}",
+            self.addressable_lines,
+            self.aspect_ratio,
+            self.preferred_vertical_rate,
+            self.supported_vertical_rates
+        )
+    }
+}
+
+impl fmt::Display for CvtAspectRatio {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::_4_3 => "4:3",
+            Self::_16_9 => "16:9",
+            Self::_16_10 => "16:10",
+            Self::_15_9 => "15:9",
+        })
+    }
+}
+
+impl fmt::Display for CvtPreferredVerticalRate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::_50Hz => "50Hz",
+            Self::_60Hz => "60Hz",
+            Self::_75Hz => "75Hz",
+            Self::_85Hz => "85Hz",
+        })
+    }
+}
+
+impl fmt::Display for SupportedVRates {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let all = [
+            (self._50_hz_standard, "50Hz"),
+            (self._60_hz_standard, "60Hz"),
+            (self._75_hz_standard, "75Hz"),
+            (self._85_hz_standard, "85Hz"),
+            (self._60_hz_reduced, "60Hz reduced"),
+        ];
+        write_list(f, all.into_iter().filter_map(|(set, s)| set.then_some(s)))
+    }
+}
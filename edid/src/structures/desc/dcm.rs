@@ -0,0 +1,71 @@
+use core::fmt;
+
+use num_rational::Ratio;
+#[cfg(feature = "decimal")]
+use rust_decimal::{Decimal, MathematicalOps as _};
+
+use crate::structures::decimal::AsDecimal;
+
+/// One channel's luminance curve from a Display Color Management (DCM)
+/// descriptor.
+///
+/// VESA DCM models each channel's response as a cubic polynomial on top of
+/// the display's gamma:
+///
+/// ```text
+/// t   = x^γ
+/// Lum = a3·t³ + a2·t² + (1 − a3 − a2)·t
+/// ```
+///
+/// Here, `x` is the normalized drive level, from zero to one. The linear
+/// coefficient isn't stored, since the curve has to reach one at full drive.
+#[repr(C)]
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DcmCurve {
+    /// The cubic coefficient.
+    #[cfg_attr(feature = "serde", serde(with = "crate::structures::decimal::string"))]
+    pub a3: Ratio<i32>,
+
+    /// The quadratic coefficient.
+    #[cfg_attr(feature = "serde", serde(with = "crate::structures::decimal::string"))]
+    pub a2: Ratio<i32>,
+}
+
+impl DcmCurve {
+    /// The linear coefficient, `1 − a3 − a2`.
+    pub fn a1(&self) -> Ratio<i32> {
+        Ratio::from_integer(1) - self.a3 - self.a2
+    }
+
+    /// Finds the relative luminance at drive level `x` for a display with
+    /// the given gamma.
+    ///
+    /// `x` is clamped to `0..=1`. Pass in the EDID's
+    /// [`reported_gamma`](crate::structures::basic_info::BasicDisplayInfo::reported_gamma),
+    /// or `1` to get the bare polynomial.
+    #[cfg(feature = "decimal")]
+    pub fn evaluate(&self, x: Decimal, gamma: Decimal) -> Decimal {
+        use crate::colorimetry::to_decimal;
+
+        let x = x.clamp(Decimal::ZERO, Decimal::ONE);
+        let t = if x.is_zero() || gamma == Decimal::ONE {
+            x
+        } else {
+            x.powd(gamma)
+        };
+
+        ((to_decimal(&self.a3) * t + to_decimal(&self.a2)) * t + to_decimal(&self.a1())) * t
+    }
+}
+
+impl fmt::Display for DcmCurve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "a3 {:.2}, a2 {:.2}",
+            AsDecimal::new(&self.a3),
+            AsDecimal::new(&self.a2)
+        )
+    }
+}
@@ -1,4 +1,8 @@
-use rust_decimal::Decimal;
+use core::fmt;
+
+use num_rational::Ratio;
+
+use crate::structures::{decimal::AsDecimal, version::Interpretation, write_list};
 
 /// Tagged with 0xFD.
 ///
@@ -6,6 +10,7 @@ use rust_decimal::Decimal;
 /// frequency for a display.
 #[repr(C)]
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RangeLimitsDesc {
     /// Range limits only. No additional timing information is provided.
     ///
@@ -52,7 +57,8 @@ pub enum RangeLimitsDesc {
         limits: RangeLimits,
 
         /// The pixel clock from the range limits, with enhanced accuracy (to 0.25 MHz).
-        enhanced_px_clk: Decimal,
+        #[cfg_attr(feature = "serde", serde(with = "crate::structures::decimal::string"))]
+        enhanced_px_clk: Ratio<u16>,
 
         /// The version of CVT implemented. For example, 11_u8 is v1.1.
         cvt_version: u8,
@@ -91,6 +97,7 @@ impl RangeLimitsDesc {
 /// scanning rate and the maximum supported pixel clock frequency.
 #[repr(C)]
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RangeLimits {
     /// Minimum Vertical Rate in Hz.
     pub min_v_rate_hz: u16,
@@ -103,15 +110,21 @@ pub struct RangeLimits {
     pub max_h_rate_khz: u16,
 
     /// Any offsets augmenting the above fields.
+    ///
+    /// These are always zero before EDID v1.4.
     pub offsets: Offsets,
 
     /// The maximum pixel clock in MHz. Note that this is in multiples of ten.
     pub max_pixel_clock_mhz: u16,
+
+    /// The EDID revision used to read these limits.
+    pub interpretation: Interpretation,
 }
 
 /// Info about any potential range limit offsets.
 #[repr(C)]
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Offsets {
     pub vertical: VerticalOffset,
     pub horizontal: HorizontalOffset,
@@ -129,6 +142,7 @@ impl Offsets {
 /// Vertical range limit offset.
 #[repr(C)]
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[expect(non_camel_case_types, reason = "enhances readability a lot")]
 pub enum VerticalOffset {
     Zero,
@@ -151,6 +165,7 @@ impl VerticalOffset {
 /// Horizontal range limit offset.
 #[repr(C)]
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[expect(non_camel_case_types, reason = "enhances readability a lot")]
 pub enum HorizontalOffset {
     Zero,
@@ -174,6 +189,7 @@ impl HorizontalOffset {
 ///
 /// These are defined in the EDID standard and aren't arbitrary.
 #[derive(Clone, Debug, PartialEq, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 #[repr(C)]
 pub struct SupportedAspectRatios {
@@ -188,6 +204,7 @@ pub struct SupportedAspectRatios {
 ///
 /// These are defined in the EDID standard and aren't arbitrary.
 #[derive(Clone, Debug, PartialEq, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[non_exhaustive]
 #[repr(C)]
 pub enum PreferredAspectRatio {
@@ -197,3 +214,102 @@ pub enum PreferredAspectRatio {
     _5x4,
     _15x9,
 }
+
+impl fmt::Display for RangeLimitsDesc {
+    /// Writes the limits, then anything the variant adds, like
+    /// `48-144 Hz V, 30-160 kHz H, max 600 MHz, CVT 1.1`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.limits())?;
+        match self {
+            Self::LimitsOnly { .. } => Ok(()),
+            Self::GtfSupported { .. } => f.write_str(", default GTF"),
+            Self::GtfSecondaryCurveSupported {
+                start_break_freq,
+                c2,
+                m,
+                k,
+                j2,
+                ..
+            } => write!(
+                f,
+                ", secondary GTF (start {} kHz, C {}, M {m}, K {k}, J {})",
+                u32::from(*start_break_freq) * 2,
+                AsDecimal(Ratio::new(i64::from(*c2), 2)),
+                AsDecimal(Ratio::new(i64::from(*j2), 2)),
+            ),
+            Self::CvtSupported { cvt_version, .. } => {
+                write!(f, ", CVT {}.{}", cvt_version / 10, cvt_version % 10)
+            }
+        }
+    }
+}
+
+impl fmt::Display for RangeLimits {
+    /// Writes the limits like `48-144 Hz V, 30-160 kHz H, max 600 MHz`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}-{} Hz V, {}-{} kHz H, max {} MHz",
+            self.min_v_rate_hz,
+            self.max_v_rate_hz,
+            self.min_h_rate_khz,
+            self.max_h_rate_khz,
+            self.max_pixel_clock_mhz
+        )
+    }
+}
+
+impl fmt::Display for Offsets {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "vertical {}, horizontal {}",
+            self.vertical, self.horizontal
+        )
+    }
+}
+
+impl fmt::Display for VerticalOffset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Zero => "no offset",
+            Self::Max255Hz_MinNotOffset => "max +255 Hz",
+            Self::Max255Hz_Min255Hz => "min and max +255 Hz",
+        })
+    }
+}
+
+impl fmt::Display for HorizontalOffset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Zero => "no offset",
+            Self::Max255kHz_MinNotOffset => "max +255 kHz",
+            Self::Max255kHz_Min255kHz => "min and max +255 kHz",
+        })
+    }
+}
+
+impl fmt::Display for SupportedAspectRatios {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let all = [
+            (self._4x3, "4:3"),
+            (self._16x9, "16:9"),
+            (self._16x10, "16:10"),
+            (self._5x4, "5:4"),
+            (self._15x9, "15:9"),
+        ];
+        write_list(f, all.into_iter().filter_map(|(set, s)| set.then_some(s)))
+    }
+}
+
+impl fmt::Display for PreferredAspectRatio {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::_4x3 => "4:3",
+            Self::_16x9 => "16:9",
+            Self::_16x10 => "16:10",
+            Self::_5x4 => "5:4",
+            Self::_15x9 => "15:9",
+        })
+    }
+}
@@ -1,13 +1,17 @@
+use core::fmt;
+
 use arrayvec::ArrayString;
 use color_point::WhitePoint;
 use cvt_3_byte_timing::TimingCodeDesc;
+use dcm::DcmCurve;
 
 use crate::prelude::internal::*;
 
-use super::std_timings::STiming;
+use super::{std_timings::STiming, write_list};
 
 pub mod color_point;
 pub mod cvt_3_byte_timing;
+pub mod dcm;
 pub mod display_range_limits;
 
 /// A byte "string" comprised of alphanumerics.
@@ -18,6 +22,7 @@ pub type ByteStr13 = ArrayString<13>;
 /// These are sometimes called "Monitor Descriptors" in older EDID revisions.
 #[repr(C)]
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DisplayDescriptor {
     /// <= 13 alphanumeric characters of a serial number.
     ///
@@ -48,29 +53,30 @@ pub enum DisplayDescriptor {
     /// be found in an extension block.
     ///
     /// TAG: 0xFA
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            serialize_with = "crate::structures::std_timings::named::serialize_6",
+            deserialize_with = "crate::structures::std_timings::named::deserialize_6"
+        )
+    )]
     StandardTimingIdentifications {
-        _9: Option<STiming>,
-        _10: Option<STiming>,
-        _11: Option<STiming>,
-        _12: Option<STiming>,
-        _13: Option<STiming>,
-        _14: Option<STiming>,
+        /// Standard timings 9 through 14, in order. Unused slots are `None`.
+        ///
+        /// With the `serde` feature, each slot keeps its own key, `_9` to `_14`.
+        timings: [Option<STiming>; 6],
     },
 
-    /// aka "Display Color Management". These are the unparsed values.
+    /// aka "Display Color Management". Each channel's luminance curve, for
+    /// displays that were calibrated at the factory.
     ///
     /// TAG: 0xF9
     DcmData {
         version_number: u8,
 
-        red_a3: u16,
-        red_a2: u16,
-
-        green_a3: u16,
-        green_a2: u16,
-
-        blue_a3: u16,
-        blue_a2: u16,
+        red: DcmCurve,
+        green: DcmCurve,
+        blue: DcmCurve,
     },
 
     /// Coordinated Video Timings (CVT) used to define video timing modes that
@@ -163,3 +169,144 @@ pub enum DisplayDescriptor {
         data: [u8; 18],
     },
 }
+
+impl fmt::Display for DisplayDescriptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ProductSerial(s) => write!(f, "serial number \"{}\"", s.trim()),
+            Self::DataString(s) => write!(f, "data string \"{}\"", s.trim()),
+            Self::DisplayRangeLimits(limits) => write!(f, "range limits: {limits}"),
+            Self::ProductName(s) => write!(f, "product name \"{}\"", s.trim()),
+            Self::ColorPointData { w1, w2 } => write!(f, "color points: {w1}; {w2}"),
+            Self::StandardTimingIdentifications { timings } => {
+                f.write_str("standard timings: ")?;
+                write_list(f, timings.iter().flatten())
+            }
+            Self::DcmData {
+                version_number,
+                red,
+                green,
+                blue,
+            } => write!(
+                f,
+                "color management v{version_number}: red {red}; green {green}; blue {blue}"
+            ),
+            Self::Cvt3ByteTimingCodes {
+                version_number,
+                first,
+                second,
+                third,
+                last,
+            } => {
+                write!(f, "CVT timing codes v{version_number}: ")?;
+                write_list(
+                    f,
+                    core::iter::once(first).chain([second, third, last].into_iter().flatten()),
+                )
+            }
+            Self::EstablishedTimingsIII {
+                _640x350_85hz,
+                _640x400_85hz,
+                _720x400_85hz,
+                _640x480_85hz,
+                _848x480_60hz,
+                _800x600_85hz,
+                _1024x768_85hz,
+                _1152x864_75hz,
+                _1280x768_60hz_reduced,
+                _1280x768_60hz,
+                _1280x768_75hz,
+                _1280x768_85hz,
+                _1280x960_60hz,
+                _1280x960_85hz,
+                _1280x1024_60hz,
+                _1280x1024_85hz,
+                _1360x768_60hz,
+                _1440x900_60hz_reduced,
+                _1440x900_60hz,
+                _1440x900_75hz,
+                _1440x900_85hz,
+                _1400x1050_60hz_reduced,
+                _1400x1050_60hz,
+                _1400x1050_75hz,
+                _1400x1050_85hz,
+                _1680x1050_60hz_reduced,
+                _1680x1050_60hz,
+                _1680x1050_75hz,
+                _1680x1050_85hz,
+                _1600x1200_60hz,
+                _1600x1200_65hz,
+                _1600x1200_70hz,
+                _1600x1200_75hz,
+                _1600x1200_85hz,
+                _1792x1344_60hz,
+                _1792x1344_75hz,
+                _1856x1392_60hz,
+                _1856x1392_75hz,
+                _1920x1200_60hz_reduced,
+                _1920x1200_60hz,
+                _1920x1200_75hz,
+                _1920x1200_85hz,
+                _1920x1440_60hz,
+                _1920x1440_75hz,
+            } => {
+                let all = [
+                    (_640x350_85hz, "640x350@85Hz"),
+                    (_640x400_85hz, "640x400@85Hz"),
+                    (_720x400_85hz, "720x400@85Hz"),
+                    (_640x480_85hz, "640x480@85Hz"),
+                    (_848x480_60hz, "848x480@60Hz"),
+                    (_800x600_85hz, "800x600@85Hz"),
+                    (_1024x768_85hz, "1024x768@85Hz"),
+                    (_1152x864_75hz, "1152x864@75Hz"),
+                    (_1280x768_60hz_reduced, "1280x768@60Hz (reduced blanking)"),
+                    (_1280x768_60hz, "1280x768@60Hz"),
+                    (_1280x768_75hz, "1280x768@75Hz"),
+                    (_1280x768_85hz, "1280x768@85Hz"),
+                    (_1280x960_60hz, "1280x960@60Hz"),
+                    (_1280x960_85hz, "1280x960@85Hz"),
+                    (_1280x1024_60hz, "1280x1024@60Hz"),
+                    (_1280x1024_85hz, "1280x1024@85Hz"),
+                    (_1360x768_60hz, "1360x768@60Hz"),
+                    (_1440x900_60hz_reduced, "1440x900@60Hz (reduced blanking)"),
+                    (_1440x900_60hz, "1440x900@60Hz"),
+                    (_1440x900_75hz, "1440x900@75Hz"),
+                    (_1440x900_85hz, "1440x900@85Hz"),
+                    (_1400x1050_60hz_reduced, "1400x1050@60Hz (reduced blanking)"),
+                    (_1400x1050_60hz, "1400x1050@60Hz"),
+                    (_1400x1050_75hz, "1400x1050@75Hz"),
+                    (_1400x1050_85hz, "1400x1050@85Hz"),
+                    (_1680x1050_60hz_reduced, "1680x1050@60Hz (reduced blanking)"),
+                    (_1680x1050_60hz, "1680x1050@60Hz"),
+                    (_1680x1050_75hz, "1680x1050@75Hz"),
+                    (_1680x1050_85hz, "1680x1050@85Hz"),
+                    (_1600x1200_60hz, "1600x1200@60Hz"),
+                    (_1600x1200_65hz, "1600x1200@65Hz"),
+                    (_1600x1200_70hz, "1600x1200@70Hz"),
+                    (_1600x1200_75hz, "1600x1200@75Hz"),
+                    (_1600x1200_85hz, "1600x1200@85Hz"),
+                    (_1792x1344_60hz, "1792x1344@60Hz"),
+                    (_1792x1344_75hz, "1792x1344@75Hz"),
+                    (_1856x1392_60hz, "1856x1392@60Hz"),
+                    (_1856x1392_75hz, "1856x1392@75Hz"),
+                    (_1920x1200_60hz_reduced, "1920x1200@60Hz (reduced blanking)"),
+                    (_1920x1200_60hz, "1920x1200@60Hz"),
+                    (_1920x1200_75hz, "1920x1200@75Hz"),
+                    (_1920x1200_85hz, "1920x1200@85Hz"),
+                    (_1920x1440_60hz, "1920x1440@60Hz"),
+                    (_1920x1440_75hz, "1920x1440@75Hz"),
+                ];
+                f.write_str("established timings III: ")?;
+                write_list(f, all.into_iter().filter_map(|(set, s)| set.then_some(s)))
+            }
+            Self::DummyDescriptor => f.write_str("dummy descriptor"),
+            Self::Manufacturer { data } => {
+                write!(
+                    f,
+                    "manufacturer-specified descriptor (tag {:#04x})",
+                    data[3]
+                )
+            }
+        }
+    }
+}
@@ -1,10 +1,15 @@
 //! Established timings.
 
+use core::fmt;
+
+use super::write_list;
+
 /// A collection of common timings for a device.
 ///
 /// These are mostly legacy, maybe even obsolute, but still useful.
 #[repr(C)]
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EstablishedTimings {
     pub i: EstablishedTimingsI,
     pub ii: EstablishedTimingsII,
@@ -23,6 +28,7 @@ pub struct EstablishedTimings {
 */
 #[repr(C)]
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EstablishedTimingsI {
     pub _720x400_70hz: bool,
     pub _720x400_88hz: bool,
@@ -46,6 +52,7 @@ pub struct EstablishedTimingsI {
 */
 #[repr(C)]
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EstablishedTimingsII {
     pub _800x600_72hz: bool,
     pub _800x600_75hz: bool,
@@ -68,6 +75,7 @@ pub struct EstablishedTimingsII {
 /// more info.
 #[repr(C)]
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ManufacturerTimings {
     pub _1152x870_75hz: bool,
     pub _6: bool,
@@ -78,3 +86,82 @@ pub struct ManufacturerTimings {
     pub _1: bool,
     pub _0: bool,
 }
+
+impl EstablishedTimingsI {
+    /// Each timing's name, next to whether it's supported.
+    fn named(&self) -> [(bool, &'static str); 8] {
+        [
+            (self._720x400_70hz, "720x400@70Hz"),
+            (self._720x400_88hz, "720x400@88Hz"),
+            (self._640x480_60hz, "640x480@60Hz"),
+            (self._640x480_67hz, "640x480@67Hz"),
+            (self._640x480_72hz, "640x480@72Hz"),
+            (self._640x480_75hz, "640x480@75Hz"),
+            (self._800x600_56hz, "800x600@56Hz"),
+            (self._800x600_60hz, "800x600@60Hz"),
+        ]
+    }
+}
+
+impl EstablishedTimingsII {
+    /// Each timing's name, next to whether it's supported.
+    fn named(&self) -> [(bool, &'static str); 8] {
+        [
+            (self._800x600_72hz, "800x600@72Hz"),
+            (self._800x600_75hz, "800x600@75Hz"),
+            (self._832x624_75hz, "832x624@75Hz"),
+            (self._1024x768_87hz_interlaced, "1024x768i@87Hz"),
+            (self._1024x768_60hz, "1024x768@60Hz"),
+            (self._1024x768_70hz, "1024x768@70Hz"),
+            (self._1024x768_75hz, "1024x768@75Hz"),
+            (self._1280x1024_75hz, "1280x1024@75Hz"),
+        ]
+    }
+}
+
+impl ManufacturerTimings {
+    /// Each timing's name, next to whether it's supported.
+    ///
+    /// The other seven bits are up to the manufacturer, so they aren't named.
+    fn named(&self) -> [(bool, &'static str); 1] {
+        [(self._1152x870_75hz, "1152x870@75Hz")]
+    }
+}
+
+/// Keeps the names of the supported timings.
+fn supported<const N: usize>(
+    named: [(bool, &'static str); N],
+) -> impl Iterator<Item = &'static str> {
+    named
+        .into_iter()
+        .filter_map(|(set, name)| set.then_some(name))
+}
+
+impl fmt::Display for EstablishedTimings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_list(
+            f,
+            supported(self.i.named())
+                .chain(supported(self.ii.named()))
+                .chain(supported(self.manufacturer_timings.named())),
+        )
+    }
+}
+
+impl fmt::Display for EstablishedTimingsI {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_list(f, supported(self.named()))
+    }
+}
+
+impl fmt::Display for EstablishedTimingsII {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_list(f, supported(self.named()))
+    }
+}
+
+impl fmt::Display for ManufacturerTimings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_list(f, supported(self.named()))
+    }
+}
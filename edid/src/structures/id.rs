@@ -1,12 +1,20 @@
+use core::fmt;
+
 use arrayvec::ArrayString;
 
 /// Identifies the display product.
 #[repr(C)]
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VendorProductId {
     /// The name of the display's manufacturer.
     pub manufacturer_name: Manufacturer,
 
+    /// The manufacturer's three-letter PNP ID, like `DEL`.
+    ///
+    /// This is what `manufacturer_name` was looked up from.
+    pub manufacturer_id: ArrayString<3>,
+
     /// The manufacturer-unique identifier for this display.
     pub product_code: u16,
 
@@ -23,6 +31,7 @@ pub struct VendorProductId {
 /// non-compliant.
 #[repr(C)]
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Manufacturer {
     Name(ArrayString<{ pnpid::MAX_LEN }>),
     Id(ArrayString<3>),
@@ -32,6 +41,7 @@ pub enum Manufacturer {
 /// manufacturing, or when it was released.
 #[repr(C)]
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Date {
     /// The approximate date the display was manufactured, down to the week,
     /// if given by the manufacturer.
@@ -47,3 +57,47 @@ pub enum Date {
     /// Note that this is not the display's year of manufacture.
     ModelYear(u16),
 }
+
+impl fmt::Display for VendorProductId {
+    /// Writes the ID, product code, serial, and date, like
+    /// `DEL (Dell Inc.), product 41191, serial 1, week 28 of 2018`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.manufacturer_id)?;
+        if let Manufacturer::Name(ref name) = self.manufacturer_name {
+            write!(f, " ({name})")?;
+        }
+
+        write!(f, ", product {}", self.product_code)?;
+        if let Some(serial) = self.serial_number {
+            write!(f, ", serial {serial}")?;
+        }
+        write!(f, ", {}", self.date)
+    }
+}
+
+impl fmt::Display for Manufacturer {
+    /// Writes the manufacturer's name, or its PNP ID when the name isn't
+    /// known.
+    ///
+    /// Use [`VendorProductId`]'s `Display` to get both, like
+    /// `DEL (Dell Inc.)`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Name(name) => f.write_str(name),
+            Self::Id(id) => f.write_str(id),
+        }
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Manufacture {
+                week: Some(week),
+                year,
+            } => write!(f, "week {week} of {year}"),
+            Self::Manufacture { week: None, year } => write!(f, "made in {year}"),
+            Self::ModelYear(year) => write!(f, "model year {year}"),
+        }
+    }
+}
@@ -0,0 +1,162 @@
+//! A key for telling physical displays apart.
+
+use arrayvec::ArrayString;
+
+use crate::structures::{desc::ByteStr13, id::Date};
+
+/// Everything an EDID says about which physical display it came from.
+///
+/// Get one with [`Edid::identity`](crate::Edid::identity), then use
+/// [`DisplayIdentity::hash`] as a key for per-display settings.
+///
+/// ## Telling displays apart
+///
+/// Many panels don't fill in their serial number. Laptop panels almost never
+/// do, and plenty of desktop monitors leave the numeric serial at zero or
+/// only give it in a serial descriptor. When neither serial is present, the
+/// identity falls back to the model, product name, and date of manufacture.
+///
+/// In that case, two displays of the same model made in the same week are
+/// indistinguishable, and [`DisplayIdentity::has_serial`] returns `false`.
+/// If that matters, combine the identity with where the display is plugged
+/// in, like its connector name.
+///
+/// Some manufacturers also reuse a placeholder serial (like `0x01010101`)
+/// for every unit. Those can't be detected, so they'll look unique here.
+#[repr(C)]
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DisplayIdentity {
+    /// The manufacturer's three-letter PNP ID, like `DEL`.
+    pub manufacturer_id: ArrayString<3>,
+
+    /// The manufacturer-unique identifier for this display's model.
+    pub product_code: u16,
+
+    /// The numeric serial number, if it's not zero.
+    pub serial_number: Option<u32>,
+
+    /// The serial number descriptor's text, without its padding.
+    pub serial_string: Option<ByteStr13>,
+
+    /// The product name descriptor's text, without its padding.
+    pub product_name: Option<ByteStr13>,
+
+    /// When the display was made, or the year its model came out.
+    pub date: Date,
+}
+
+impl DisplayIdentity {
+    /// Whether this identity includes a serial number.
+    ///
+    /// If it doesn't, other displays of the same model may share this
+    /// identity.
+    pub fn has_serial(&self) -> bool {
+        self.serial_number.is_some() || self.serial_string.is_some()
+    }
+
+    /// A hash of this identity that won't change between runs, machines, or
+    /// versions of this crate.
+    ///
+    /// This is 64-bit FNV-1a over each field in order. Strings are prefixed
+    /// with their length, and missing values are hashed as zeroes.
+    pub fn hash(&self) -> u64 {
+        let mut hasher = Fnv1a::new();
+
+        hasher.write(self.manufacturer_id.as_bytes());
+        hasher.write(&self.product_code.to_le_bytes());
+        hasher.write(&self.serial_number.unwrap_or(0).to_le_bytes());
+        hasher.write_str(self.serial_string.as_deref());
+        hasher.write_str(self.product_name.as_deref());
+
+        let (kind, week, year) = match self.date {
+            Date::Manufacture { week, year } => (0, week.unwrap_or(0), year),
+            Date::ModelYear(year) => (1, 0, year),
+        };
+        hasher.write(&[kind, week]);
+        hasher.write(&year.to_le_bytes());
+
+        hasher.finish()
+    }
+}
+
+/// A tiny 64-bit FNV-1a hasher.
+///
+/// `core::hash` doesn't promise its output stays the same, so we can't use
+/// that for keys that get saved to disk.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    const OFFSET_BASIS: u64 = 0xCBF2_9CE4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01B3;
+
+    fn new() -> Self {
+        Self(Self::OFFSET_BASIS)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 ^= u64::from(*b);
+            self.0 = self.0.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    fn write_str(&mut self, s: Option<&str>) {
+        let s = s.unwrap_or_default();
+        self.write(&[s.len() as u8]);
+        self.write(s.as_bytes());
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::internal::*;
+
+    #[test]
+    fn dell_s2417dg_identity() {
+        logger();
+        let input = raw_edid_by_filename("dell_s2417dg.raw.input");
+        let identity = Edid::new(&input).unwrap().identity();
+
+        assert_eq!(identity.manufacturer_id.as_str(), "DEL");
+        assert_eq!(identity.product_code, 41191);
+        assert_eq!(identity.serial_number, Some(1));
+        assert_eq!(identity.serial_string.unwrap().as_str(), "#ASNTobz/bdLd");
+        assert_eq!(identity.product_name.unwrap().as_str(), "Dell S2417DG");
+        assert!(identity.has_serial());
+
+        // this has to stay the same forever. people save these!
+        assert_eq!(identity.hash(), 0x4055_9321_DA9A_CBA8);
+    }
+
+    #[test]
+    fn no_serial() {
+        logger();
+        let input = edid_by_filename("1.input");
+        let identity = Edid::new(&input).unwrap().identity();
+
+        assert_eq!(identity.serial_number, None);
+        assert_eq!(identity.serial_string, None);
+        assert!(!identity.has_serial());
+    }
+
+    /// removing the serials should give a different display
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn anonymized_differs() {
+        logger();
+        let input = raw_edid_by_filename("dell_s2417dg.raw.input");
+        let anonymous = crate::anonymize(&input, crate::AnonymizeOptions::default()).unwrap();
+
+        let original = Edid::new(&input).unwrap().identity();
+        let anonymous = Edid::new(&anonymous).unwrap().identity();
+
+        assert!(!anonymous.has_serial());
+        assert_ne!(original.hash(), anonymous.hash());
+        assert_eq!(anonymous.hash(), anonymous.clone().hash());
+    }
+}
@@ -1,13 +1,216 @@
 //! Structures for the EDID data.
 //!
 //! Each of these submodules are specialized to have data parsed into them.
+//!
+//! Every section implements `Display` with a short, human-friendly summary,
+//! like `1920x1080@60Hz (16:9)` for a standard timing. These formats are
+//! stable, so they're safe to snapshot-test against.
+
+use core::fmt;
 
 pub mod _18bytes;
 pub mod basic_info;
 pub mod color;
+pub(crate) mod decimal;
 pub mod desc;
 pub mod est_timings;
 pub mod extension;
 pub mod id;
+pub mod identity;
+#[cfg(feature = "decimal")]
+pub mod size;
 pub mod std_timings;
 pub mod version;
+
+/// Writes each item, separated by commas, or `none` when there aren't any.
+pub(crate) fn write_list<T: fmt::Display>(
+    f: &mut fmt::Formatter<'_>,
+    items: impl IntoIterator<Item = T>,
+) -> fmt::Result {
+    let mut items = items.into_iter();
+    let Some(first) = items.next() else {
+        return f.write_str("none");
+    };
+
+    write!(f, "{first}")?;
+    for item in items {
+        write!(f, ", {item}")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use std::{string::ToString as _, vec::Vec};
+
+    use crate::prelude::internal::*;
+
+    use super::{
+        desc::display_range_limits::{HorizontalOffset, Offsets, RangeLimits, VerticalOffset},
+        id::Date,
+        std_timings::{STiming, StandardAspectRatio},
+    };
+
+    /// the whole edid, one section per line
+    #[test]
+    fn dell_s2417dg_snapshot() {
+        logger();
+        let edid = Edid::new(raw_edid_by_filename("dell_s2417dg.raw.input")).unwrap();
+
+        assert_eq!(
+            edid.to_string(),
+            "\
+Vendor & Product Identification: DEL (Dell Inc.), product 41191, serial 1, week 28 of 2018
+EDID Structure Version & Revision: 1.4
+Basic Display Parameters & Features: digital, 8 bits per channel, DisplayPort; 53x30 cm; \
+gamma 2.20; RGB 4:4:4, DPMS (none), sRGB default, native preferred timing
+Color Characteristics: red (0.6396, 0.3300), green (0.2998, 0.5996), blue (0.1503, 0.0595), \
+white (0.3125, 0.3291)
+Established Timings I & II: 640x480@60Hz, 800x600@60Hz, 1024x768@60Hz
+Standard Timings: none
+Descriptor #1: \"2560x1440\" 241.50 2560 2608 2640 2720 1440 1443 1448 1481 +HSync -VSync
+Descriptor #2: serial number \"#ASNTobz/bdLd\"
+Descriptor #3: range limits: 30-144 Hz V, 34-222 kHz H, max 590 MHz
+Descriptor #4: product name \"Dell S2417DG\"
+Extension blocks: 1
+Checksum: 0x51"
+        );
+    }
+
+    #[test]
+    fn aoc_snapshot() {
+        logger();
+        let edid = Edid::new(edid_by_filename(
+            "linuxhw_edid_Analog_AOC_AOC0320_455954E7CA14.input",
+        ))
+        .unwrap();
+
+        assert_eq!(
+            edid.to_string(),
+            "\
+Vendor & Product Identification: AOC, product 800, week 3 of 2017
+EDID Structure Version & Revision: 1.3
+Basic Display Parameters & Features: analog, 0.700 : 0.000 : 0.700 V p-p, blank level is black, \
+sync (none); 62x34 cm; gamma 2.20; undefined color type, DPMS (standby, suspend, active off), \
+native preferred timing
+Color Characteristics: red (0.6484, 0.3388), green (0.2919, 0.6025), blue (0.1425, 0.0703), \
+white (0.3134, 0.3291)
+Established Timings I & II: 640x480@60Hz, 640x480@75Hz, 800x600@56Hz, 800x600@60Hz, \
+800x600@72Hz, 800x600@75Hz, 1024x768@60Hz, 1024x768@75Hz, 1280x1024@75Hz
+Standard Timings: 1152x864@75Hz (4:3), 1280x720@60Hz (16:9), 1280x1024@60Hz (5:4), \
+1440x900@60Hz (16:10), 1680x1050@60Hz (16:10), 1920x1080@60Hz (16:9), 1920x1200@60Hz (16:10), \
+1280x800@60Hz (16:10)
+Descriptor #1: \"1920x1080\" 148.50 1920 1968 2000 2200 1080 1083 1088 1125 +HSync +VSync
+Descriptor #2: \"2560x1440\" 241.50 2560 2608 2640 2720 1440 1443 1448 1481 +HSync +VSync
+Descriptor #3: product name \"s32e511\"
+Descriptor #4: standard timings: 1600x900@60Hz (16:9)
+Extension blocks: 0
+Checksum: 0xd6"
+        );
+    }
+
+    /// the smaller pieces on their own
+    #[test]
+    fn sections() {
+        logger();
+        let edid = Edid::new(raw_edid_by_filename("dell_s2417dg.raw.input")).unwrap();
+        assert!(edid
+            .vendor_product_info
+            .to_string()
+            .starts_with("DEL (Dell Inc.), "));
+        assert_eq!(
+            edid.vendor_product_info.manufacturer_name.to_string(),
+            "Dell Inc."
+        );
+
+        let date = Date::Manufacture {
+            week: Some(12),
+            year: 2019,
+        };
+        assert_eq!(date.to_string(), "week 12 of 2019");
+
+        let timing = STiming {
+            horizontal_addr_pixel_ct: 1920,
+            aspect_ratio: StandardAspectRatio::_16_9,
+            field_refresh_rate: 60,
+        };
+        assert_eq!(timing.to_string(), "1920x1080@60Hz (16:9)");
+
+        let limits = RangeLimitsDesc::LimitsOnly {
+            limits: RangeLimits {
+                min_v_rate_hz: 48,
+                max_v_rate_hz: 144,
+                min_h_rate_khz: 30,
+                max_h_rate_khz: 160,
+                offsets: Offsets {
+                    vertical: VerticalOffset::Zero,
+                    horizontal: HorizontalOffset::Zero,
+                },
+                max_pixel_clock_mhz: 600,
+                interpretation: Interpretation::V1_4,
+            },
+            flexible: false,
+        };
+        assert_eq!(limits.to_string(), "48-144 Hz V, 30-160 kHz H, max 600 MHz");
+    }
+
+    /// a wrong checksum says what it should've been
+    #[test]
+    fn bad_checksum() {
+        logger();
+        let mut input = raw_edid_by_filename("dell_s2417dg.raw.input");
+        input[0x7F] = 0x50;
+        let edid = Edid::new(&input).unwrap();
+
+        assert!(edid
+            .to_string()
+            .ends_with("\nChecksum: 0x50 (should be 0x51)"));
+    }
+
+    /// the base block's eight, then the 0xfa descriptor's six
+    #[test]
+    fn all_standard_timings() {
+        logger();
+        let edid = Edid::new(edid_by_filename(
+            "linuxhw_edid_Digital_Others_HJW0000_F67302F2ED4C.input",
+        ))
+        .unwrap();
+        let interpretation = edid.standard_timings.interpretation;
+
+        assert_eq!(edid.standard_timings.iter().count(), 8);
+        let modes: Vec<_> = edid
+            .all_standard_timings()
+            .map(|t| {
+                (
+                    t.horizontal_addr_pixel_ct,
+                    t.vertical_active(interpretation),
+                )
+            })
+            .collect();
+        assert_eq!(modes.len(), 14);
+        assert_eq!(modes[0], (1152, 864));
+        assert_eq!(modes[8], (1152, 864));
+        assert_eq!(modes[13], (1600, 900));
+    }
+
+    /// before v1.3, the 16:10 bits meant 1:1
+    #[test]
+    fn pre_v1_3_square_timings() {
+        logger();
+        let mut input = edid_by_filename("linuxhw_edid_Digital_Others_HJW0000_F67302F2ED4C.input");
+        input[0x13] = 0x02;
+        crate::checksum::fix_checksums(&mut input);
+        let edid = Edid::new(&input).unwrap();
+
+        let timings = &edid.standard_timings;
+        assert_eq!(timings.interpretation, Interpretation::PreV1_3);
+        let square = timings
+            .iter()
+            .find(|t| t.aspect_ratio == StandardAspectRatio::_16_10)
+            .unwrap();
+        assert_eq!(square.vertical_active(Interpretation::PreV1_3), 1280);
+        assert_eq!(square.vertical_active(Interpretation::V1_3), 800);
+        assert!(timings.to_string().contains("1280x1280@60Hz (1:1)"));
+    }
+}
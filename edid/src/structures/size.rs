@@ -0,0 +1,440 @@
+//! The physical size of the display, and what that means for its modes.
+//!
+//! EDIDs give the size in a few places: the basic display info (in
+//! centimeters), and each detailed timing (in millimeters). These often
+//! disagree, and plenty are just wrong, so this module decides which to
+//! trust.
+
+extern crate alloc;
+use alloc::vec::Vec;
+use num_traits::ToPrimitive as _;
+
+use crate::prelude::internal::*;
+use crate::structures::basic_info::SizeOrRatio;
+
+/// The DPI that a scale factor of 1 is meant for.
+pub const REFERENCE_DPI: u16 = 96;
+
+/// Sizes that show up in EDIDs way more often than real displays of that
+/// size would. These are mostly aspect ratios written where a size should
+/// go, like TVs that say they're 160 x 90 mm.
+const PLACEHOLDERS_MM: [(u16, u16); 6] = [
+    (160, 90),
+    (160, 100),
+    (40, 30),
+    (1600, 900),
+    (1600, 1000),
+    (1600, 1200),
+];
+
+/// Anything with a diagonal shorter than this (in mm) is probably reporting
+/// centimeters as millimeters. That's about 3 inches.
+const MIN_DIAGONAL_MM: u32 = 76;
+
+/// Anything wider than this (in mm) is probably garbage. Big TVs are around
+/// 2.5 meters wide.
+const MAX_WIDTH_MM: u16 = 5000;
+
+/// How far (as a fraction) two sizes or aspect ratios can be apart and still
+/// be considered the same.
+const TOLERANCE: Decimal = dec!(0.1);
+
+/// The display's physical size, after checking every place the EDID gives it.
+#[repr(C)]
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PhysicalSize {
+    /// The width of the display's active area, in millimeters.
+    pub width_mm: u16,
+
+    /// The height of the display's active area, in millimeters.
+    pub height_mm: u16,
+
+    /// The diagonal, in inches. Rounded to one decimal place.
+    pub diagonal_in: Decimal,
+
+    /// Where this size came from.
+    pub source: SizeSource,
+
+    /// The DPI and suggested scale of each detailed timing on this display.
+    pub modes: Vec<ModeDensity>,
+
+    /// Other sizes in the EDID that weren't used, and why.
+    pub rejected: Vec<RejectedSize>,
+}
+
+/// A place in the EDID that gives the display's size.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SizeSource {
+    /// The image size of a detailed timing, in millimeters.
+    ///
+    /// `descriptor` counts the 18-byte blocks from 1, so the preferred timing
+    /// is 1.
+    DetailedTiming { descriptor: u8 },
+
+    /// The basic display info's screen size, in centimeters.
+    ScreenSize,
+}
+
+/// A size that `Edid::physical_size` didn't trust.
+#[repr(C)]
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RejectedSize {
+    pub source: SizeSource,
+    pub width_mm: u16,
+    pub height_mm: u16,
+    pub reason: Implausible,
+}
+
+/// Why a size looked wrong.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Implausible {
+    /// It's a well-known placeholder, like 160 x 90 mm.
+    Placeholder,
+
+    /// It's too small to be a real display.
+    TooSmall,
+
+    /// It's too big to be a real display.
+    TooLarge,
+
+    /// Its shape doesn't match the preferred timing's resolution (assuming
+    /// square pixels).
+    AspectMismatch,
+
+    /// It's plausible, but disagrees with the size that was picked.
+    Disagrees,
+}
+
+/// How dense one mode is on this display.
+#[repr(C)]
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ModeDensity {
+    pub width_px: u16,
+    pub height_px: u16,
+
+    /// Pixels per inch, measured along the diagonal. Rounded to one decimal
+    /// place.
+    pub dpi: Decimal,
+
+    /// A whole-number scale, for compositors that can't do fractional
+    /// scaling. Halfway values round down, so text doesn't get too big.
+    pub integer_scale: u8,
+
+    /// A fractional scale, in steps of 0.25, from 1 to 4.
+    pub fractional_scale: Decimal,
+}
+
+impl PhysicalSize {
+    /// Finds the most trustworthy size in the EDID.
+    ///
+    /// Detailed timings come first (the preferred one before the others), as
+    /// they're in millimeters. The basic display info's centimeters are the
+    /// fallback. Sizes that are placeholders, too big, too small, or the
+    /// wrong shape are skipped.
+    ///
+    /// Returns `None` when there's no plausible size, like for projectors or
+    /// EDIDs that only give an aspect ratio.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub(crate) fn from_edid(edid: &Edid) -> Option<Self> {
+        let timings = timings(edid);
+
+        // the preferred timing tells us the shape of the screen
+        let preferred = timings.first().map(|(_, t)| {
+            (
+                t.horizontal_addressable_video_px,
+                t.vertical_addressable_video_lines,
+            )
+        });
+
+        // gather up every size, in order of trust
+        let mut candidates = Vec::new();
+        for (descriptor, t) in &timings {
+            if let (Some(w), Some(h)) = (
+                t.horizontal_addressable_video_size_mm,
+                t.vertical_addressable_video_size_mm,
+            ) {
+                if w != 0 && h != 0 {
+                    let source = SizeSource::DetailedTiming {
+                        descriptor: *descriptor,
+                    };
+                    candidates.push((source, w, h));
+                }
+            }
+        }
+        if let Some(SizeOrRatio::ScreenSize {
+            horizontal_cm,
+            vertical_cm,
+        }) = edid.basic_display_info.screen_size_or_aspect_ratio
+        {
+            candidates.push((
+                SizeSource::ScreenSize,
+                u16::from(horizontal_cm) * 10,
+                u16::from(vertical_cm) * 10,
+            ));
+        }
+
+        // pick the first one that looks right
+        let mut rejected = Vec::new();
+        let mut picked = None;
+        for (source, width_mm, height_mm) in candidates {
+            let reason = implausible(width_mm, height_mm, preferred).or_else(|| {
+                let (_, w, h) = picked?;
+                (!same_size((w, h), (width_mm, height_mm))).then_some(Implausible::Disagrees)
+            });
+
+            match reason {
+                Some(reason) => {
+                    tracing::warn!(
+                        "Not using size {width_mm}x{height_mm} mm from {source:?}: {reason:?}"
+                    );
+                    rejected.push(RejectedSize {
+                        source,
+                        width_mm,
+                        height_mm,
+                        reason,
+                    });
+                }
+                None if picked.is_none() => picked = Some((source, width_mm, height_mm)),
+                None => (),
+            }
+        }
+
+        let Some((source, width_mm, height_mm)) = picked else {
+            tracing::warn!("This EDID doesn't give a plausible physical size.");
+            return None;
+        };
+
+        let diagonal_mm_100 = hypot_100(width_mm, height_mm);
+        let diagonal_in = (Decimal::new(diagonal_mm_100 as i64, 2) / dec!(25.4)).round_dp(1);
+
+        // each distinct resolution, in the order they're given
+        let mut modes: Vec<ModeDensity> = Vec::new();
+        for (_, t) in &timings {
+            let (w, h) = (
+                t.horizontal_addressable_video_px,
+                t.vertical_addressable_video_lines,
+            );
+            if w == 0 || h == 0 || modes.iter().any(|m| (m.width_px, m.height_px) == (w, h)) {
+                continue;
+            }
+            modes.push(density(w, h, diagonal_mm_100));
+        }
+
+        Some(Self {
+            width_mm,
+            height_mm,
+            diagonal_in,
+            source,
+            modes,
+            rejected,
+        })
+    }
+}
+
+/// The detailed timings in the base block, alongside their descriptor
+/// number.
+fn timings(edid: &Edid) -> Vec<(u8, &DetailedTimingDefinition)> {
+    let blocks = &edid.eighteen_byte_data_blocks;
+    core::iter::once(&blocks.preferred_timing_mode)
+        .chain(&blocks.blocks)
+        .zip(1..)
+        .filter_map(|(block, i)| match block {
+            EighteenByteBlock::Timing(t) => Some((i, t)),
+            EighteenByteBlock::Display(_) => None,
+        })
+        .collect()
+}
+
+/// Checks a size on its own, returning what's wrong with it.
+fn implausible(
+    width_mm: u16,
+    height_mm: u16,
+    preferred: Option<(u16, u16)>,
+) -> Option<Implausible> {
+    if PLACEHOLDERS_MM.contains(&(width_mm, height_mm)) {
+        return Some(Implausible::Placeholder);
+    }
+
+    if hypot_100(width_mm, height_mm) < MIN_DIAGONAL_MM * 100 {
+        return Some(Implausible::TooSmall);
+    }
+
+    if width_mm > MAX_WIDTH_MM || height_mm > MAX_WIDTH_MM {
+        return Some(Implausible::TooLarge);
+    }
+
+    // rotated panels often give their size in the other orientation, so
+    // either way around is fine
+    if let Some((w_px, h_px)) = preferred.filter(|(w, h)| *w != 0 && *h != 0) {
+        let physical = Decimal::from(width_mm) / Decimal::from(height_mm);
+        let pixels = Decimal::from(w_px) / Decimal::from(h_px);
+        if !close(physical, pixels) && !close(physical, Decimal::ONE / pixels) {
+            return Some(Implausible::AspectMismatch);
+        }
+    }
+
+    None
+}
+
+/// Whether two sizes are about the same. Screen sizes in centimeters are
+/// rounded, so this isn't exact.
+fn same_size(a: (u16, u16), b: (u16, u16)) -> bool {
+    close(Decimal::from(a.0), Decimal::from(b.0)) && close(Decimal::from(a.1), Decimal::from(b.1))
+}
+
+/// Whether `a` is within `TOLERANCE` of `b`.
+fn close(a: Decimal, b: Decimal) -> bool {
+    (a - b).abs() <= b * TOLERANCE
+}
+
+/// The hypotenuse of a right triangle, times 100. (so we keep two decimal
+/// places without floats)
+fn hypot_100(a: u16, b: u16) -> u32 {
+    let (a, b) = (u64::from(a), u64::from(b));
+    ((a * a + b * b) * 100 * 100).isqrt() as u32
+}
+
+/// Works out the DPI and scale of a mode.
+fn density(width_px: u16, height_px: u16, diagonal_mm_100: u32) -> ModeDensity {
+    let diagonal_px_100 = hypot_100(width_px, height_px);
+
+    let dpi = Decimal::from(diagonal_px_100) / Decimal::from(diagonal_mm_100) * dec!(25.4);
+
+    // scale in quarters, rounded to the nearest
+    let quarters = (dpi * dec!(4) / Decimal::from(REFERENCE_DPI))
+        .round()
+        .clamp(dec!(4), dec!(16));
+    let fractional_scale = quarters / dec!(4);
+
+    // 1.5 rounds down to 1, but 1.75 goes up to 2
+    let integer_scale = ((quarters + Decimal::ONE) / dec!(4)).floor();
+
+    ModeDensity {
+        width_px,
+        height_px,
+        dpi: dpi.round_dp(1),
+        integer_scale: integer_scale.to_u8().unwrap_or(1),
+        fractional_scale: fractional_scale.normalize(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dell_s2417dg_size() {
+        logger();
+        let input = raw_edid_by_filename("dell_s2417dg.raw.input");
+        let size = Edid::new(&input).unwrap().physical_size().unwrap();
+
+        assert_eq!((size.width_mm, size.height_mm), (527, 296));
+        assert_eq!(size.diagonal_in, dec!(23.8));
+        assert_eq!(size.source, SizeSource::DetailedTiming { descriptor: 1 });
+
+        // 1440p at 24 inches is a bit dense, but not double
+        assert_eq!(
+            size.modes[0],
+            ModeDensity {
+                width_px: 2560,
+                height_px: 1440,
+                dpi: dec!(123.4),
+                integer_scale: 1,
+                fractional_scale: dec!(1.25),
+            }
+        );
+
+        // the screen size is rounded, but close enough
+        assert!(size.rejected.is_empty());
+    }
+
+    /// a tv that says it's 160x90 mm
+    #[test]
+    fn placeholder() {
+        logger();
+        let mut input = raw_edid_by_filename("dell_s2417dg.raw.input");
+
+        // dtd image size: 160 x 90 mm
+        input[0x36 + 12] = 160;
+        input[0x36 + 13] = 90;
+        input[0x36 + 14] = 0x00;
+
+        let size = Edid::new(&input).unwrap().physical_size().unwrap();
+        assert_eq!(size.source, SizeSource::ScreenSize);
+        assert_eq!((size.width_mm, size.height_mm), (530, 300));
+        assert_eq!(
+            size.rejected,
+            [RejectedSize {
+                source: SizeSource::DetailedTiming { descriptor: 1 },
+                width_mm: 160,
+                height_mm: 90,
+                reason: Implausible::Placeholder,
+            }]
+        );
+    }
+
+    #[test]
+    fn only_aspect_ratio() {
+        logger();
+        let input = edid_by_filename("linuxhw_edid_Digital_BOE_BOE07AF_BD22D8FDF96B.input");
+        let edid = Edid::new(&input).unwrap();
+
+        // this one still has a size in its timing
+        let size = edid.physical_size();
+        let mut no_sizes = edid.clone();
+        no_sizes.basic_display_info.screen_size_or_aspect_ratio = None;
+        if let EighteenByteBlock::Timing(ref mut t) =
+            no_sizes.eighteen_byte_data_blocks.preferred_timing_mode
+        {
+            t.horizontal_addressable_video_size_mm = Some(0);
+            t.vertical_addressable_video_size_mm = Some(0);
+        }
+        for block in &mut no_sizes.eighteen_byte_data_blocks.blocks {
+            if let EighteenByteBlock::Timing(t) = block {
+                t.horizontal_addressable_video_size_mm = Some(0);
+            }
+        }
+
+        assert!(size.is_some());
+        assert_eq!(no_sizes.physical_size(), None);
+    }
+
+    #[test]
+    fn scales() {
+        // a 13.3" 1080p laptop panel, and a 27" 4k monitor
+        let laptop = density(1920, 1080, hypot_100(294, 165));
+        assert_eq!(laptop.dpi, dec!(166.0));
+        assert_eq!(
+            (laptop.integer_scale, laptop.fractional_scale),
+            (2, dec!(1.75))
+        );
+
+        let monitor = density(3840, 2160, hypot_100(597, 336));
+        assert_eq!(monitor.dpi, dec!(163.4));
+        assert_eq!(monitor.integer_scale, 2);
+
+        // and a 1080p tv, which gets no scaling
+        let tv = density(1920, 1080, hypot_100(1210, 680));
+        assert_eq!((tv.integer_scale, tv.fractional_scale), (1, dec!(1)));
+    }
+
+    #[test]
+    fn implausible_sizes() {
+        assert_eq!(implausible(53, 30, None), Some(Implausible::TooSmall));
+        assert_eq!(implausible(9000, 5000, None), Some(Implausible::TooLarge));
+        assert_eq!(
+            implausible(527, 296, Some((1280, 1024))),
+            Some(Implausible::AspectMismatch)
+        );
+
+        // portrait is fine
+        assert_eq!(implausible(296, 527, Some((2560, 1440))), None);
+    }
+}
@@ -1,20 +1,38 @@
+use core::fmt;
+
+use super::{version::Interpretation, write_list};
+
 /// A collection of "standard" timings for a device.
+///
+/// With the `serde` feature, each slot keeps its own key, `st1` to `st8`.
 #[repr(C)]
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(from = "named::NamedSlots8", into = "named::NamedSlots8")
+)]
 pub struct StandardTimings {
-    pub st1: Option<STiming>,
-    pub st2: Option<STiming>,
-    pub st3: Option<STiming>,
-    pub st4: Option<STiming>,
-    pub st5: Option<STiming>,
-    pub st6: Option<STiming>,
-    pub st7: Option<STiming>,
-    pub st8: Option<STiming>,
+    /// The eight standard timing slots, in order. Unused slots are `None`.
+    pub timings: [Option<STiming>; 8],
+
+    /// The EDID revision used to read these timings.
+    ///
+    /// Before v1.3, the 16:10 aspect ratio bits meant 1:1 instead.
+    pub interpretation: Interpretation,
+}
+
+impl StandardTimings {
+    /// The timings in the slots that are used, in order.
+    pub fn iter(&self) -> impl Iterator<Item = &STiming> + '_ {
+        self.timings.iter().flatten()
+    }
 }
 
 /// One standard timing.
 #[repr(C)]
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct STiming {
     /// The horizontal active pixels.
     //
@@ -27,14 +45,181 @@ pub struct STiming {
     pub field_refresh_rate: u8,
 }
 
+impl STiming {
+    /// The vertical active lines, found from the width and aspect ratio.
+    ///
+    /// The `interpretation` matters: before EDID v1.3, the bits for 16:10
+    /// meant 1:1.
+    pub fn vertical_active(&self, interpretation: Interpretation) -> u16 {
+        let (h, v) = self.aspect_ratio.ratio(interpretation);
+        // at most 2288 * 10, so this can't overflow
+        self.horizontal_addr_pixel_ct * v / h
+    }
+}
+
+/// Prints a standard timing with the rules of a given EDID revision.
+struct Versioned<'a>(&'a STiming, Interpretation);
+
+impl fmt::Display for Versioned<'_> {
+    /// Writes the timing like `1920x1080@60Hz (16:9)`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self(t, interpretation) = *self;
+        let (h, v) = t.aspect_ratio.ratio(interpretation);
+        write!(
+            f,
+            "{}x{}@{}Hz ({h}:{v})",
+            t.horizontal_addr_pixel_ct,
+            t.vertical_active(interpretation),
+            t.field_refresh_rate
+        )
+    }
+}
+
 /// The aspect ratio of a standard timing.
 ///
 /// Limited to these values by the standard. See the spec for more info.
 #[repr(C)]
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StandardAspectRatio {
     _16_10,
     _4_3,
     _5_4,
     _16_9,
 }
+
+impl StandardAspectRatio {
+    /// The ratio as `(horizontal, vertical)`, like `(16, 9)`.
+    ///
+    /// Before EDID v1.3, [`Self::_16_10`] was 1:1 instead.
+    pub fn ratio(&self, interpretation: Interpretation) -> (u16, u16) {
+        match self {
+            Self::_16_10 if interpretation < Interpretation::V1_3 => (1, 1),
+            Self::_16_10 => (16, 10),
+            Self::_4_3 => (4, 3),
+            Self::_5_4 => (5, 4),
+            Self::_16_9 => (16, 9),
+        }
+    }
+}
+
+impl fmt::Display for StandardTimings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_list(f, self.iter().map(|t| Versioned(t, self.interpretation)))
+    }
+}
+
+impl fmt::Display for STiming {
+    /// Writes the timing like `1920x1080@60Hz (16:9)`.
+    ///
+    /// This uses the rules of EDID v1.3 and later. Printing the whole
+    /// [`StandardTimings`] uses the revision it was read with.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Versioned(self, Interpretation::V1_4))
+    }
+}
+
+impl fmt::Display for StandardAspectRatio {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::_16_10 => "16:10",
+            Self::_4_3 => "4:3",
+            Self::_5_4 => "5:4",
+            Self::_16_9 => "16:9",
+        })
+    }
+}
+
+/// Keeps the serialized shape from before the slots were arrays, with one key
+/// per slot, numbered like the spec does.
+#[cfg(feature = "serde")]
+pub(crate) mod named {
+    use super::{Interpretation, STiming, StandardTimings};
+
+    /// [`StandardTimings`], as it's serialized.
+    #[derive(serde::Serialize, serde::Deserialize)]
+    pub(crate) struct NamedSlots8 {
+        st1: Option<STiming>,
+        st2: Option<STiming>,
+        st3: Option<STiming>,
+        st4: Option<STiming>,
+        st5: Option<STiming>,
+        st6: Option<STiming>,
+        st7: Option<STiming>,
+        st8: Option<STiming>,
+
+        // older json didn't have this, so it's read with the newest rules
+        #[serde(default = "newest")]
+        interpretation: Interpretation,
+    }
+
+    fn newest() -> Interpretation {
+        Interpretation::V1_4
+    }
+
+    impl From<StandardTimings> for NamedSlots8 {
+        fn from(value: StandardTimings) -> Self {
+            let [st1, st2, st3, st4, st5, st6, st7, st8] = value.timings;
+            Self {
+                st1,
+                st2,
+                st3,
+                st4,
+                st5,
+                st6,
+                st7,
+                st8,
+                interpretation: value.interpretation,
+            }
+        }
+    }
+
+    impl From<NamedSlots8> for StandardTimings {
+        fn from(value: NamedSlots8) -> Self {
+            Self {
+                timings: [
+                    value.st1, value.st2, value.st3, value.st4, value.st5, value.st6, value.st7,
+                    value.st8,
+                ],
+                interpretation: value.interpretation,
+            }
+        }
+    }
+
+    /// The timings of a Standard Timing Identifications (0xFA) descriptor, as
+    /// they're serialized.
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct NamedSlots6<T> {
+        _9: T,
+        _10: T,
+        _11: T,
+        _12: T,
+        _13: T,
+        _14: T,
+    }
+
+    pub(crate) fn serialize_6<S: serde::Serializer>(
+        timings: &[Option<STiming>; 6],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let [st9, st10, st11, st12, st13, st14] = timings.each_ref();
+        let named = NamedSlots6 {
+            _9: st9,
+            _10: st10,
+            _11: st11,
+            _12: st12,
+            _13: st13,
+            _14: st14,
+        };
+        serde::Serialize::serialize(&named, serializer)
+    }
+
+    pub(crate) fn deserialize_6<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<[Option<STiming>; 6], D::Error> {
+        let named: NamedSlots6<_> = serde::Deserialize::deserialize(deserializer)?;
+        Ok([
+            named._9, named._10, named._11, named._12, named._13, named._14,
+        ])
+    }
+}
@@ -1,10 +1,70 @@
+use core::fmt;
+
 /// Contains info about which version + revision of the standard this structure
 /// expects.
 #[repr(C)]
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EdidVersion {
     /// Should be 0x01 for v1.4.
     pub version: u8,
     /// Should be 0x04 for v1.4.
     pub revision: u8,
 }
+
+impl EdidVersion {
+    /// Finds which set of rules the parser should apply to an EDID with this
+    /// version + revision.
+    ///
+    /// Anything newer than v1.4 is read as v1.4, as no later revision exists.
+    pub fn interpretation(&self) -> Interpretation {
+        match (self.version, self.revision) {
+            (0x00, _) | (0x01, 0x00..=0x02) => Interpretation::PreV1_3,
+            (0x01, 0x03) => Interpretation::V1_3,
+            _ => Interpretation::V1_4,
+        }
+    }
+}
+
+/// The revision of the standard used to read some structure.
+///
+/// A few bytes changed meaning between EDID revisions, so structures that
+/// contain those bytes record which meaning the parser went with.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Interpretation {
+    /// EDID v1.0 to v1.2.
+    ///
+    /// These may not place a preferred timing in the first 18-byte block.
+    PreV1_3,
+
+    /// EDID v1.3. Feature support bit 0 indicates Default GTF support.
+    V1_3,
+
+    /// EDID v1.4.
+    V1_4,
+}
+
+impl Interpretation {
+    /// Whether these rules are at least those of EDID v1.4.
+    pub fn is_v1_4(&self) -> bool {
+        *self >= Self::V1_4
+    }
+}
+
+impl fmt::Display for EdidVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.version, self.revision)
+    }
+}
+
+impl fmt::Display for Interpretation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::PreV1_3 => "v1.0 to v1.2",
+            Self::V1_3 => "v1.3",
+            Self::V1_4 => "v1.4",
+        })
+    }
+}
@@ -0,0 +1,381 @@
+//! A borrowed EDID that parses each section only when you ask for it.
+
+use arrayvec::ArrayString;
+
+use crate::{
+    checksum::{self, ChecksumStatus},
+    parser::{
+        self,
+        header::{self, BLOCK_LEN},
+    },
+    prelude::internal::*,
+    structures::{color::ColorCharacteristics, id::VendorProductId},
+    ParseOptions,
+};
+
+/// An EDID that's parsed on demand, without copying its bytes.
+///
+/// `Edid::new` builds the whole tree up front, which is more than you need
+/// when a display gets plugged in and all you want is its model. Creating a
+/// view only checks the length and header, and each accessor only parses its
+/// own section:
+///
+/// ```edition2021
+/// use liboptic_edid::EdidView;
+///
+/// let data = std::fs::read("tests/assets/dell_s2417dg.raw.input")?;
+/// let view = EdidView::new(&data)?;
+///
+/// assert_eq!(view.manufacturer_id()?.as_str(), "DEL");
+/// assert_eq!(view.product_code(), 41191);
+/// #
+/// # Ok::<(), Box<dyn core::error::Error>>(())
+/// ```
+///
+/// Sections are parsed again every time you call their accessor, so hold
+/// onto the results if you need them more than once. To check the structure
+/// without parsing anything, use [`EdidView::validate`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EdidView<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> EdidView<'a> {
+    /// Wraps the given bytes, checking that they start with a full base
+    /// block.
+    pub fn new(bytes: &'a [u8]) -> Result<Self, EdidError> {
+        parser::check_length(bytes)?;
+        header::parse(bytes).map_err(|e| e.within(0, Section::Header, 0x00))?;
+        Ok(Self { bytes })
+    }
+
+    /// Checks the EDID's structure, without parsing its sections.
+    ///
+    /// That's the length, header, version, checksums, and each descriptor's
+    /// header and tag. Anything this rejects, [`Edid::new`] rejects too, but
+    /// `Edid::new` can still fail on a value inside a section, like a range
+    /// limit with its reserved bits set.
+    pub fn validate(&self) -> Result<(), EdidError> {
+        self.validate_with_options(&ParseOptions::default())
+    }
+
+    /// Like [`EdidView::validate`], but with the given `options`.
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
+    pub fn validate_with_options(&self, options: &ParseOptions) -> Result<(), EdidError> {
+        // the length and header were checked on creation
+        let version = self.version()?;
+        parser::checksums(self.bytes, options)?;
+        parser::_18bytes::check_headers(self.bytes, &version)
+    }
+
+    /// Parses every section into an [`Edid`].
+    pub fn to_edid(&self) -> Result<Edid, EdidError> {
+        parser::parse(self.bytes)
+    }
+
+    /// The bytes this view wraps, including any extension blocks.
+    pub fn bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// The base block's bytes.
+    pub fn base_block(&self) -> &'a [u8; BLOCK_LEN] {
+        self.bytes[..BLOCK_LEN]
+            .try_into()
+            .expect("the length was checked on creation")
+    }
+
+    /// The manufacturer's three-letter PNP ID, like `DEL`.
+    ///
+    /// Unlike [`EdidView::vendor_product_info`], this doesn't look up the
+    /// manufacturer's name.
+    pub fn manufacturer_id(&self) -> Result<ArrayString<3>, EdidError> {
+        parser::id::pnp_id(&mut [self.bytes[0x08], self.bytes[0x09]])
+            .map_err(|e| e.within(0, Section::VendorProduct, 0x08))
+    }
+
+    /// The manufacturer-unique identifier for this display's model.
+    pub fn product_code(&self) -> u16 {
+        parser::le::read_u16([self.bytes[0x0A], self.bytes[0x0B]])
+    }
+
+    /// Info about the product vendor.
+    pub fn vendor_product_info(&self) -> Result<VendorProductId, EdidError> {
+        parser::id::parse(self.bytes).map_err(|e| e.within(0, Section::VendorProduct, 0x08))
+    }
+
+    /// The version + revision that this EDID was created for.
+    pub fn version(&self) -> Result<EdidVersion, EdidError> {
+        parser::version::parse(self.bytes).map_err(|e| e.within(0, Section::Version, 0x12))
+    }
+
+    /// Basic info about the display, like its input and screen size.
+    pub fn basic_display_info(&self) -> Result<BasicDisplayInfo, EdidError> {
+        parser::basic_info::parse(self.bytes, &self.version()?)
+            .map_err(|e| e.within(0, Section::BasicInfo, 0x14))
+    }
+
+    /// Info about this display's placements in the CIE 1931 color space.
+    pub fn color_characteristics(&self) -> ColorCharacteristics {
+        parser::color::parse(self.bytes)
+    }
+
+    /// Various hardcoded timing booleans.
+    pub fn established_timings(&self) -> EstablishedTimings {
+        parser::est_timings::parse(self.bytes)
+    }
+
+    /// Dynamic timings.
+    pub fn standard_timings(&self) -> Result<StandardTimings, EdidError> {
+        Ok(parser::std_timings::parse(self.bytes, &self.version()?))
+    }
+
+    /// The four 18-byte data blocks.
+    pub fn eighteen_byte_data_blocks(&self) -> Result<EighteenByteDescriptors, EdidError> {
+        parser::_18bytes::parse(self.bytes, &self.version()?)
+    }
+
+    /// The number of extension blocks the base block says follow it.
+    pub fn extension_info(&self) -> u8 {
+        self.bytes[0x7E]
+    }
+
+    /// The base block's checksum byte.
+    pub fn checksum(&self) -> u8 {
+        self.bytes[0x7F]
+    }
+
+    /// Whether the base block's checksum is correct.
+    ///
+    /// Use [`EdidView::checksum_statuses`] to check the extension blocks
+    /// too.
+    pub fn checksum_valid(&self) -> bool {
+        self.checksum_status().is_valid()
+    }
+
+    /// The base block's stored and expected checksums.
+    pub fn checksum_status(&self) -> ChecksumStatus {
+        checksum::status(self.base_block())
+    }
+
+    /// The checksum status of the base block and each extension block that
+    /// was given, in order.
+    pub fn checksum_statuses(&self) -> impl Iterator<Item = ChecksumStatus> + 'a {
+        checksum::statuses(self.bytes)
+    }
+
+    /// Each extension block that was given, in order.
+    ///
+    /// This uses the blocks that are actually present, not
+    /// [`EdidView::extension_info`]. A trailing partial block is skipped.
+    pub fn extensions(&self) -> impl Iterator<Item = ExtensionBlock<'a>> + 'a {
+        self.bytes[BLOCK_LEN..]
+            .chunks_exact(BLOCK_LEN)
+            .zip(1..=u8::MAX)
+            .filter_map(|(block, index)| {
+                let bytes: &[u8; BLOCK_LEN] = block.try_into().ok()?;
+                Some(ExtensionBlock {
+                    index,
+                    tag: bytes[0],
+                    bytes,
+                })
+            })
+    }
+}
+
+/// An extension block from an [`EdidView`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExtensionBlock<'a> {
+    /// Where this block is in the EDID. The first extension is `1`, right
+    /// after the base block.
+    pub index: u8,
+
+    /// The block's tag, like `0x02` for CTA-861.
+    pub tag: u8,
+
+    /// The block's bytes, including its tag and checksum.
+    pub bytes: &'a [u8; BLOCK_LEN],
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate alloc;
+    use alloc::vec::Vec;
+
+    use crate::prelude::internal::*;
+    use crate::{EdidView, ParseOptions};
+
+    /// every accessor should agree with the eager parser
+    #[test]
+    fn matches_edid() {
+        logger();
+        let assets = [
+            raw_edid_by_filename("dell_s2417dg.raw.input"),
+            edid_by_filename("1.input"),
+            edid_by_filename("linuxhw_edid_Analog_AOC_AOC0320_455954E7CA14.input"),
+            edid_by_filename("linuxhw_edid_Digital_BOE_BOE07AF_BD22D8FDF96B.input"),
+            edid_by_filename("linuxhw_edid_Digital_Goldstar_GSM7666_FE91A60D5B6E.input"),
+            edid_by_filename("linuxhw_edid_Digital_Lenovo_LEN0017_3AF8B597ECB9.input"),
+            edid_by_filename("linuxhw_edid_Digital_MSI_MSIAF82_4B2991D4299A.input"),
+            edid_by_filename("linuxhw_edid_Digital_Others_HJW0000_F67302F2ED4C.input"),
+            edid_by_filename("linuxhw_edid_EDID_Digital_Samsung_SAM02E3_2C47316EFF13.input"),
+            edid_by_filename("linuxhw_edid_EDID_Digital_Sony_SNY05FA_93D328459FF6.input"),
+        ];
+
+        for asset in &assets {
+            let edid = Edid::new(asset).unwrap();
+            let view = EdidView::new(asset).unwrap();
+            view.validate().unwrap();
+
+            let id = &edid.vendor_product_info;
+            assert_eq!(view.manufacturer_id().unwrap(), id.manufacturer_id);
+            assert_eq!(view.product_code(), id.product_code);
+            assert_eq!(&view.vendor_product_info().unwrap(), id);
+            assert_eq!(view.version().unwrap(), edid.version);
+            assert_eq!(view.basic_display_info().unwrap(), edid.basic_display_info);
+            assert_eq!(view.color_characteristics(), edid.color_characteristics);
+            assert_eq!(view.established_timings(), edid.established_timings);
+            assert_eq!(view.standard_timings().unwrap(), edid.standard_timings);
+            assert_eq!(
+                view.eighteen_byte_data_blocks().unwrap(),
+                edid.eighteen_byte_data_blocks
+            );
+            assert_eq!(view.extension_info(), edid.extension_info);
+            assert_eq!(view.checksum(), edid.checksum);
+            assert_eq!(view.to_edid().unwrap(), edid);
+        }
+    }
+
+    #[test]
+    fn extensions() {
+        logger();
+        let input = raw_edid_by_filename("dell_s2417dg.raw.input");
+        let view = EdidView::new(&input).unwrap();
+
+        let extensions: Vec<_> = view.extensions().collect();
+        assert_eq!(extensions.len(), 1);
+        assert_eq!(extensions[0].index, 1);
+        assert_eq!(extensions[0].tag, 0x02); // cta-861
+        assert_eq!(extensions[0].bytes, &input[128..]);
+        assert_eq!(view.base_block(), &input[..128]);
+
+        // a partial block at the end is skipped
+        let view = EdidView::new(&input[..200]).unwrap();
+        assert_eq!(view.extensions().count(), 0);
+    }
+
+    /// errors should say where they happened
+    #[test]
+    fn error_context() {
+        extern crate std;
+        use std::string::ToString as _;
+
+        logger();
+        let input = raw_edid_by_filename("dell_s2417dg.raw.input");
+
+        // break the second descriptor's header
+        let mut broken = input.clone();
+        broken[0x48 + 2] = 0x01;
+        let e = EdidView::new(&broken).unwrap().validate().unwrap_err();
+        assert!(matches!(
+            e.kind,
+            EdidErrorKind::DescriptorUnexpectedHeader([0x00, 0x00, 0x01, 0xFF, 0x00])
+        ));
+        assert_eq!(e.context.block, 0);
+        assert_eq!(e.context.section, Some(Section::Descriptor(2)));
+        assert_eq!(e.context.offset, Some(0x4A));
+        assert_eq!(
+            e.to_string(),
+            "Block 0, Base EDID: Descriptor #2 (offset 0x4a): Descriptor used an unexpected \
+            value within the first five bytes. (bytes: [0, 0, 1, ff, 0])"
+        );
+
+        // and the header
+        let mut broken = input.clone();
+        broken[1] = 0x00;
+        let e = Edid::new(&broken).unwrap_err();
+        assert_eq!(e.context.section, Some(Section::Header));
+        assert_eq!(e.context.offset, Some(0x01));
+        assert!(e
+            .to_string()
+            .starts_with("Block 0, Base EDID: Header (offset 0x01): "));
+
+        // a bad byte in the range limits points at that byte, not the start
+        let mut broken = input.clone();
+        broken[0x5A + 4] = 0xF0;
+        let e = Edid::new(&broken).unwrap_err();
+        assert!(matches!(
+            e.kind,
+            EdidErrorKind::DescriptorRangeLimitsUsedReservedBits { input: 0xF0 }
+        ));
+        assert_eq!(e.context.section, Some(Section::Descriptor(3)));
+        assert_eq!(e.context.offset, Some(0x5E));
+
+        // errors that aren't about a part of the edid are just the message
+        let e = EdidError::from(EdidErrorKind::LoadOddHexLength);
+        assert_eq!(
+            e.to_string(),
+            "The input's hex had an odd number of digits."
+        );
+    }
+
+    /// anything `validate` refuses, `Edid::new` should too
+    #[test]
+    fn validate_bad() {
+        logger();
+        let bad = edid_by_filename("bad/bad.1.input");
+        if EdidView::new(&bad).and_then(|v| v.validate()).is_err() {
+            assert!(Edid::new(&bad).is_err());
+        }
+
+        // a reserved descriptor tag is part of the structure
+        let mut broken = raw_edid_by_filename("dell_s2417dg.raw.input");
+        broken[0x48 + 3] = 0x20;
+        let e = EdidView::new(&broken).unwrap().validate().unwrap_err();
+        assert!(matches!(
+            e.kind,
+            EdidErrorKind::DescriptorUsedReservedKind { kind_byte: 0x20 }
+        ));
+        assert_eq!(e.context.section, Some(Section::Descriptor(2)));
+        assert_eq!(Edid::new(&broken).unwrap_err().context, e.context);
+
+        // the options count too
+        let mut broken = raw_edid_by_filename("dell_s2417dg.raw.input");
+        broken[0x7F] = 0x50;
+        let reject = ParseOptions {
+            reject_bad_checksum: true,
+        };
+        let view = EdidView::new(&broken).unwrap();
+        view.validate().unwrap();
+        let e = view.validate_with_options(&reject).unwrap_err();
+        assert!(matches!(e.kind, EdidErrorKind::BadChecksum { .. }));
+        assert!(Edid::new_with_options(&broken, reject).is_err());
+
+        // too short to be a base block, so the first missing byte is wrong
+        let e = EdidView::new(&bad[..127]).unwrap_err();
+        assert!(matches!(e.kind, EdidErrorKind::TooShort { got: 127, .. }));
+        assert_eq!(e.context.section, Some(Section::Checksum));
+        assert_eq!(e.context.offset, Some(127));
+
+        let e = EdidView::new(&bad[..0x40]).unwrap_err();
+        assert_eq!(e.context.section, Some(Section::Descriptor(1)));
+        assert_eq!(e.context.offset, Some(0x40));
+    }
+
+    /// `validate` doesn't parse inside the sections, so it misses what only
+    /// the full parse would find
+    #[test]
+    fn validate_is_shallow() {
+        logger();
+        let mut broken = raw_edid_by_filename("dell_s2417dg.raw.input");
+
+        // the range limits' reserved bits are inside the descriptor
+        broken[0x5A + 4] = 0xF0;
+        crate::checksum::fix_checksums(&mut broken);
+
+        let view = EdidView::new(&broken).unwrap();
+        view.validate().unwrap();
+        assert!(view.eighteen_byte_data_blocks().is_err());
+        assert!(Edid::new(&broken).is_err());
+    }
+}